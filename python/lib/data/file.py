@@ -40,6 +40,8 @@ class DataFileInfo:
         self.root_wdl = meta.pop("root_wdl", None)
         self.hit_move_limit = meta.pop("hit_move_limit", None)
         self.includes_simulation_start_indices = meta.pop("includes_game_start_indices", False)
+        # only AlphaZero selfplay writes child stats, MuZero and converted files never include them
+        self.includes_child_stats = meta.pop("includes_child_stats", False)
 
         total_move_count = self.position_count - self.includes_final_positions * self.simulation_count
         self.mean_simulation_length = total_move_count / self.simulation_count
@@ -128,6 +130,7 @@ class DataFile:
             game=self.info.game,
             file_pi=pi,
             includes_final=self.info.includes_final_positions,
+            includes_child_stats=self.info.includes_child_stats,
             scalar_names=self.info.scalar_names,
             data=data,
             final_position=None
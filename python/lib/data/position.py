@@ -1,4 +1,5 @@
 import random
+import re
 from dataclasses import dataclass
 from typing import List, Optional
 
@@ -37,6 +38,7 @@ class Position:
             game: Game,
             file_pi: int,
             includes_final: bool,
+            includes_child_stats: bool,
             scalar_names: List[str],
             data: bytes,
             final_position: Optional['Position']
@@ -88,6 +90,25 @@ class Position:
         self.zero_moves_left = float(scalars.pop("zero_moves_left", np.nan))
         self.net_moves_left = float(scalars.pop("net_moves_left", np.nan))
 
+        # final score difference, only available for games that have a score (eg. Go)
+        self.final_score = float(scalars.pop("final_score", np.nan))
+        # actual board size, only available for games that are played on multiple board sizes
+        self.board_size = float(scalars.pop("board_size", np.nan))
+
+        # short-term search value targets, keyed by their horizon
+        self.short_v = {}
+        self.short_wdl = {}
+        for name in list(scalars.keys()):
+            m = re.fullmatch(r"short_(\d+)_v", name)
+            if m:
+                horizon = int(m.group(1))
+                self.short_v[horizon] = float(scalars.pop(name))
+                self.short_wdl[horizon] = np.array([
+                    scalars.pop(f"short_{horizon}_wdl_w"),
+                    scalars.pop(f"short_{horizon}_wdl_d"),
+                    scalars.pop(f"short_{horizon}_wdl_l"),
+                ])
+
         if len(scalars):
             print(f"Leftover scalars: {list(scalars.keys())}")
 
@@ -100,6 +121,16 @@ class Position:
         self.policy_indices = np.frombuffer(data.take(self.available_mv_count * 4), dtype=np.int32)
         self.policy_values = np.frombuffer(data.take(self.available_mv_count * 4), dtype=np.float32)
 
+        # per-move search statistics, in the same order as the policy
+        if includes_child_stats:
+            self.child_visits = np.frombuffer(data.take(self.available_mv_count * 4), dtype=np.float32)
+            self.child_q = np.frombuffer(data.take(self.available_mv_count * 4), dtype=np.float32)
+            self.child_prior = np.frombuffer(data.take(self.available_mv_count * 4), dtype=np.float32)
+        else:
+            self.child_visits = None
+            self.child_q = None
+            self.child_prior = None
+
         data.finish()
 
     def map_symmetry_inplace(self, index: int):
@@ -128,6 +159,10 @@ class PostFinalPosition:
         self.policy_indices = np.zeros(0, dtype=np.int32)
         self.policy_values = np.zeros(0, dtype=np.float32)
 
+        self.child_visits = np.zeros(0, dtype=np.float32)
+        self.child_q = np.zeros(0, dtype=np.float32)
+        self.child_prior = np.zeros(0, dtype=np.float32)
+
         self.move_index = -1
         self.file_pi = -1
 
@@ -151,6 +186,9 @@ class PostFinalPosition:
         self.final_moves_left = 0.0
         self.zero_moves_left = 0.0
         self.net_moves_left = 0.0
+        self.final_score = final_position.final_score
+        self.short_v = {h: final_position.final_v for h in final_position.short_v}
+        self.short_wdl = {h: final_position.final_wdl for h in final_position.short_wdl}
 
         # TODO what about these values?
         self.is_full_search = False
@@ -161,13 +199,19 @@ class PostFinalPosition:
 
 
 class PositionBatch:
-    def __init__(self, game: Game, positions: List[Position], include_final_for_each: bool, pin_memory: bool):
+    def __init__(
+            self,
+            game: Game, positions: List[Position],
+            include_final_for_each: bool, pin_memory: bool,
+            include_child_stats: bool = False,
+    ):
         self.max_available_moves = max(p.available_mv_count if p is not None else 0 for p in positions)
 
         input_full = torch.empty(len(positions), *game.full_input_shape, pin_memory=pin_memory)
         all_wdls = torch.empty(len(positions), 3 * 3, pin_memory=pin_memory)
         all_values = torch.empty(len(positions), 3, pin_memory=pin_memory)
         all_moves_left = torch.empty(len(positions), 3, pin_memory=pin_memory)
+        final_score = torch.empty(len(positions), pin_memory=pin_memory)
 
         final_input_full = torch.empty(len(positions), *game.full_input_shape, pin_memory=pin_memory) \
             if include_final_for_each else None
@@ -177,6 +221,13 @@ class PositionBatch:
         policy_values = torch.empty(len(positions), self.max_available_moves, pin_memory=pin_memory)
         policy_values.fill_(-1)
 
+        # child stats get the same padding, with visits 0 and NaN for q and prior
+        if include_child_stats:
+            child_stats = torch.zeros(len(positions), 3, self.max_available_moves, pin_memory=pin_memory)
+            child_stats[:, 1:, :] = np.nan
+        else:
+            child_stats = None
+
         played_mv = torch.empty(len(positions), dtype=torch.int64, pin_memory=pin_memory)
         sim_index = torch.empty(len(positions), dtype=torch.int64, pin_memory=pin_memory)
         move_index = torch.empty(len(positions), dtype=torch.int64, pin_memory=pin_memory)
@@ -208,10 +259,17 @@ class PositionBatch:
             all_moves_left[i, 0] = p.final_moves_left
             all_moves_left[i, 1] = p.zero_moves_left
             all_moves_left[i, 2] = p.net_moves_left
+            final_score[i] = p.final_score
 
             policy_indices[i, :p.available_mv_count] = torch.from_numpy(p.policy_indices.copy())
             policy_values[i, :p.available_mv_count] = torch.from_numpy(p.policy_values.copy())
 
+            if include_child_stats:
+                assert p.child_visits is not None, "Position does not include child stats"
+                child_stats[i, 0, :p.available_mv_count] = torch.from_numpy(p.child_visits.copy())
+                child_stats[i, 1, :p.available_mv_count] = torch.from_numpy(p.child_q.copy())
+                child_stats[i, 2, :p.available_mv_count] = torch.from_numpy(p.child_prior.copy())
+
             played_mv[i] = p.played_mv
             move_index[i] = p.move_index
             file_pi[i] = p.file_pi
@@ -236,6 +294,16 @@ class PositionBatch:
         self.policy_indices = policy_indices.to(DEVICE)
         self.policy_values = policy_values.to(DEVICE)
 
+        if include_child_stats:
+            child_stats = child_stats.to(DEVICE)
+            self.child_visits = child_stats[:, 0, :]
+            self.child_q = child_stats[:, 1, :]
+            self.child_prior = child_stats[:, 2, :]
+        else:
+            self.child_visits = None
+            self.child_q = None
+            self.child_prior = None
+
         self.played_mv = played_mv.to(DEVICE)
         self.move_index = move_index.to(DEVICE)
         self.file_pi = file_pi.to(DEVICE)
@@ -259,6 +327,8 @@ class PositionBatch:
         self.moves_left_final = self.all_moves_left[:, 0]
         self.moves_left_zero = self.all_moves_left[:, 1]
         self.moves_left_net = self.all_moves_left[:, 2]
+        # NaN for games without a score and for games that didn't finish
+        self.score_final = final_score.to(DEVICE)
 
     def __len__(self):
         return len(self.input_full)
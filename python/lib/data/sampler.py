@@ -18,6 +18,7 @@ class PositionSampler:
             include_final_for_each: bool,
             random_symmetries: bool,
             threads: int,
+            include_child_stats: bool = False,
     ):
         self.group = group
 
@@ -29,6 +30,11 @@ class PositionSampler:
         self.include_final = include_final
         self.include_final_for_each = include_final_for_each
         self.random_symmetries = random_symmetries
+        self.include_child_stats = include_child_stats
+
+        if include_child_stats:
+            assert unroll_steps is None, "Child stats not yet supported for unrolled sampling"
+            assert all(v.file.info.includes_child_stats for v in group._sim_views), "Not all files include child stats"
 
         self.queue = CQueue(threads + 1)
 
@@ -84,7 +90,10 @@ def collect_simple_batch(sampler: PositionSampler, group: DataGroup):
 
         positions.append(p)
 
-    return PositionBatch(group.game, positions, sampler.include_final_for_each, PIN_MEMORY)
+    return PositionBatch(
+        group.game, positions, sampler.include_final_for_each, PIN_MEMORY,
+        include_child_stats=sampler.include_child_stats
+    )
 
 
 def collect_unrolled_batch(sampler: PositionSampler, group: DataGroup, unroll_steps: int):
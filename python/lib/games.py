@@ -6,7 +6,7 @@ from typing import Tuple, Optional, Callable, Sequence
 import numpy as np
 
 from lib.mapping.mapping import CHESS_FLAT_TO_MOVE_INPUT, ATAXX_VALID_MOVES, ATAXX_INDEX_TO_MOVE_INPUT, \
-    get_ataxx_symmetry_data
+    get_ataxx_symmetry_data, CHESS_FLAT_TO_ATT
 from lib.util import prod
 
 
@@ -27,10 +27,14 @@ class Symmetry(ABC):
 @dataclass
 class Game:
     name: str
+    # the mapper id used on the rust side, see `InputMapper::input_mapper_id` and `PolicyMapper::policy_mapper_id`
+    input_mapper: str
+    policy_mapper: str
 
     board_size: int
     board_height: Optional[int] = None
     board_width: Optional[int] = None
+    board_area: int = field(init=False)
 
     input_bool_channels: int
     input_scalar_channels: int
@@ -59,6 +63,7 @@ class Game:
             self.board_height = self.board_size
         if self.board_width is None:
             self.board_width = self.board_size
+        self.board_area = self.board_height * self.board_width
 
         self.input_bool_shape = (self.input_bool_channels, self.board_height, self.board_width)
         self.input_scalar_shape = (self.input_scalar_channels, self.board_height, self.board_width)
@@ -84,15 +89,24 @@ class Game:
             return game
 
         game = None
-        m = re.match(r"ataxx-(\d+)", name)
+        m = re.fullmatch(r"(.+)-hist-(\d+)", name)
+        if m and m.group(1) != "chess":
+            game = _hist_game(Game.find(m.group(1)), int(m.group(2)))
+        m = re.fullmatch(r"ataxx-(\d+)", name)
         if m:
             game = _ataxx_game(int(m.group(1)))
         m = re.match(r"chess-hist-(\d+)", name)
         if m:
             game = _chess_hist_game(int(m.group(1)))
-        m = re.match(r"go-(\d+)", name)
+        m = re.fullmatch(r"go-(\d+)", name)
         if m:
-            game = _go_game(int(m.group(1)))
+            game = _go_game(int(m.group(1)), int(m.group(1)))
+        m = re.fullmatch(r"go-(\d+)\.\.(\d+)", name)
+        if m:
+            game = _go_game(int(m.group(1)), int(m.group(2)))
+        m = re.fullmatch(r"hex-(\d+)", name)
+        if m:
+            game = _hex_game(int(m.group(1)))
 
         if game is None:
             raise KeyError(f"Game '{name}' not found")
@@ -115,6 +129,47 @@ class UnitSymmetry(Symmetry):
         return moves
 
 
+class SquareSymmetry(Symmetry):
+    """
+    The 8 symmetries of a square board, for games with a flat policy of `extra_moves` moves
+    followed by one move per tile.
+    """
+
+    def __init__(self, size: int, extra_moves: int):
+        self.size = size
+        self.extra_moves = extra_moves
+
+    def __len__(self) -> int:
+        return 8
+
+    def map_bools(self, index: int, bools):
+        assert index < len(self)
+        assert len(bools.shape) == 3, f"Unexpected bool shape {bools.shape}"
+
+        if index & 4:
+            bools = np.transpose(bools, (0, 2, 1))
+        if index & 1:
+            bools = bools[:, :, ::-1]
+        if index & 2:
+            bools = bools[:, ::-1, :]
+
+        # fix potentially negative strides
+        return np.copy(bools)
+
+    def map_moves(self, index: int, moves):
+        assert index < len(self)
+        area = self.size * self.size
+
+        # mapped[q] = p means tile p ends up at q
+        mapped = self.map_bools(index, np.arange(area).reshape(1, self.size, self.size)).reshape(-1)
+        map_mv = np.empty(self.extra_moves + area, dtype=int)
+        map_mv[:self.extra_moves] = np.arange(self.extra_moves)
+        map_mv[self.extra_moves + mapped] = self.extra_moves + np.arange(area)
+
+        assert np.all((0 <= moves) & (moves < len(map_mv))), "Got invalid input move"
+        return map_mv[moves]
+
+
 class AtaxxSymmetry(Symmetry):
     def __init__(self, size: int):
         self.size = size
@@ -153,6 +208,8 @@ def _ataxx_game(size: int):
     assert 2 <= size <= 8
     return Game(
         name=f"ataxx-{size}",
+        input_mapper="ataxx-std-v1",
+        policy_mapper="ataxx-std-v1",
         board_size=size,
         input_bool_channels=3,
         input_scalar_channels=1,
@@ -172,6 +229,8 @@ def _chess_hist_game(length: int):
     chess = GAMES["chess"]
     return Game(
         name=f"chess-hist-{length}",
+        input_mapper="chess-hist-v1",
+        policy_mapper="chess-std-v1",
         board_size=chess.board_size,
         input_bool_channels=1 + (length + 1) * (2 * 6),
         input_scalar_channels=7 + (length + 1),
@@ -185,12 +244,41 @@ def _chess_hist_game(length: int):
     )
 
 
-def _go_game(size: int):
-    assert size >= 0
+def _hist_game(inner: Game, length: int):
+    # see `HistoryMapper` on the rust side
+    assert length >= 0
     return Game(
-        name=f"go-{size}",
+        name=f"{inner.name}-hist-{length}",
+        input_mapper=f"hist-v1-{inner.input_mapper}-{length}",
+        policy_mapper=inner.policy_mapper,
+        board_size=inner.board_size,
+        board_height=inner.board_height,
+        board_width=inner.board_width,
+        input_bool_channels=(length + 1) * inner.input_bool_channels,
+        input_scalar_channels=(length + 1) * inner.input_scalar_channels + length,
+        input_mv_channels=inner.input_mv_channels,
+        policy_shape=inner.policy_shape,
+        policy_conv_channels=inner.policy_conv_channels,
+        estimate_moves_per_game=inner.estimate_moves_per_game,
+        encode_mv=inner.encode_mv,
+        possible_mvs=inner.possible_mvs,
+        symmetry=inner.symmetry,
+    )
+
+
+def _go_game(min_size: int, max_size: int, territory: bool = True):
+    # smaller boards are padded to the max size, the mapper marks the tiles that are actually on the board
+    assert 0 <= min_size <= max_size
+    size = max_size
+    name = f"go-{size}" if min_size == max_size else f"go-{min_size}..{max_size}"
+
+    # the rust registry always enables the territory (ownership) input channels
+    return Game(
+        name=name,
+        input_mapper="go-std-territory-v1" if territory else "go-std-v1",
+        policy_mapper="go-std-v1",
         board_size=size,
-        input_bool_channels=4,
+        input_bool_channels=7 if territory else 4,
         input_scalar_channels=6,
         input_mv_channels=None,
         policy_shape=(1 + size * size,),
@@ -202,6 +290,26 @@ def _go_game(size: int):
     )
 
 
+def _hex_game(size: int):
+    assert 1 <= size <= 19
+    return Game(
+        name=f"hex-{size}",
+        input_mapper="hex-std-v1",
+        policy_mapper="hex-std-v1",
+        board_size=size,
+        input_bool_channels=2,
+        input_scalar_channels=1,
+        input_mv_channels=2,
+        policy_shape=(1 + size * size,),
+        policy_conv_channels=1,
+        # games typically end with a bit more than half of the board filled
+        estimate_moves_per_game=size * size // 2 + 1,
+        encode_mv=lambda mv: encode_hex_mv(size, mv),
+        possible_mvs=range(1 + size * size),
+        symmetry=UnitSymmetry(),
+    )
+
+
 def encode_chess_move(mv: int) -> np.array:
     encoded = CHESS_FLAT_TO_MOVE_INPUT[mv, :]
     result = np.zeros((8, 8, 8), dtype=np.uint8)
@@ -230,6 +338,50 @@ def encode_ataxx_mv(size: int, mv: int):
     return result
 
 
+def encode_trictrac_mv(mv: int) -> np.array:
+    # mirrors the rust `TrictracStdMapper::encode_mv`: roll, go, then (dice_order, checker1, checker2)
+    result = np.zeros((5, 24, 1))
+
+    if mv == 0:
+        result[0, :, :] = 1
+    elif mv == 1:
+        result[1, :, :] = 1
+    else:
+        dice_order, rest = divmod(mv - 2, 16 * 16)
+        checker1, checker2 = divmod(rest, 16)
+        result[2, :, :] = dice_order
+        result[3, checker1, 0] = 1
+        result[4, checker2, 0] = 1
+
+    return result
+
+
+def encode_connect4_mv(mv: int) -> np.array:
+    # mark the entire column
+    result = np.zeros((1, 6, 7))
+    result[0, :, mv] = 1
+    return result
+
+
+def encode_othello_mv(mv: int) -> np.array:
+    result = np.zeros((2, 8, 8))
+    if mv == 0:
+        result[0, :, :] = 1
+    else:
+        result[1, :, :].reshape(-1)[mv - 1] = 1
+    return result
+
+
+def encode_hex_mv(size: int, mv: int) -> np.array:
+    # same layout as othello, with swap instead of pass
+    result = np.zeros((2, size, size))
+    if mv == 0:
+        result[0, :, :] = 1
+    else:
+        result[1, :, :].reshape(-1)[mv - 1] = 1
+    return result
+
+
 def encode_ttt_move(mv: int) -> np.array:
     result = np.zeros((1, 3, 3))
     result.reshape(-1)[mv] = 1
@@ -239,6 +391,8 @@ def encode_ttt_move(mv: int) -> np.array:
 GAMES = {
     "chess": Game(
         name="chess",
+        input_mapper="chess-std-v1",
+        policy_mapper="chess-std-v1",
         board_size=8,
         input_bool_channels=13,
         input_scalar_channels=8,
@@ -250,8 +404,26 @@ GAMES = {
         possible_mvs=range(1880),
         symmetry=UnitSymmetry(),
     ),
+    # same inputs as chess, but with the attention policy layout, see `ChessAttPolicyMapper`
+    "chess-att": Game(
+        name="chess-att",
+        input_mapper="chess-std-v1",
+        policy_mapper="chess-att-v1",
+        board_size=8,
+        input_bool_channels=13,
+        input_scalar_channels=8,
+        input_mv_channels=None,
+        policy_shape=(64, 64 + 3 * 8),
+        policy_conv_channels=None,
+        estimate_moves_per_game=150,
+        encode_mv=None,
+        possible_mvs=sorted(set(CHESS_FLAT_TO_ATT.tolist())),
+        symmetry=UnitSymmetry(),
+    ),
     "sttt": Game(
         name="sttt",
+        input_mapper="sttt-std-v1",
+        policy_mapper="sttt-std-v1",
         board_size=9,
         input_bool_channels=3,
         input_scalar_channels=0,
@@ -265,6 +437,8 @@ GAMES = {
     ),
     "ttt": Game(
         name="ttt",
+        input_mapper="ttt-std-v1",
+        policy_mapper="ttt-std-v1",
         board_size=3,
         input_bool_channels=2,
         input_scalar_channels=0,
@@ -278,6 +452,8 @@ GAMES = {
     ),
     "arimaa-split": Game(
         name="arimaa-split",
+        input_mapper="arimaa-split-v1",
+        policy_mapper="arimaa-split-v1",
         board_size=8,
         input_bool_channels=4 * 6 + 2,
         input_scalar_channels=12,
@@ -289,18 +465,69 @@ GAMES = {
         possible_mvs=range(1 + 6 + 256),
         symmetry=UnitSymmetry(),
     ),
+    "connect4": Game(
+        name="connect4",
+        input_mapper="connect4-std-v1",
+        policy_mapper="connect4-std-v1",
+        board_size=7,
+        board_height=6,
+        board_width=7,
+        input_bool_channels=2,
+        input_scalar_channels=0,
+        input_mv_channels=1,
+        policy_shape=(7,),
+        policy_conv_channels=None,
+        estimate_moves_per_game=36,
+        encode_mv=encode_connect4_mv,
+        possible_mvs=range(7),
+        symmetry=UnitSymmetry(),
+    ),
+    "othello": Game(
+        name="othello",
+        input_mapper="othello-std-v1",
+        policy_mapper="othello-std-v1",
+        board_size=8,
+        input_bool_channels=3,
+        input_scalar_channels=0,
+        input_mv_channels=2,
+        policy_shape=(1 + 8 * 8,),
+        policy_conv_channels=1,
+        estimate_moves_per_game=60,
+        encode_mv=encode_othello_mv,
+        possible_mvs=range(1 + 8 * 8),
+        symmetry=SquareSymmetry(8, extra_moves=1),
+    ),
     "trictrac": Game(
         name="trictrac",
+        input_mapper="trictrac-std-v1",
+        policy_mapper="trictrac-std-v1",
         board_size=24,
         board_height=24,
         board_width=1,
         input_bool_channels=30,
         input_scalar_channels=15,
-        input_mv_channels=None,
+        input_mv_channels=5,
         policy_shape=(514,),
         policy_conv_channels=None,
         estimate_moves_per_game=150,
-        encode_mv=None,
+        encode_mv=encode_trictrac_mv,
+        possible_mvs=range(514),
+        symmetry=UnitSymmetry(),
+    ),
+    "trictrac-v2": Game(
+        name="trictrac-v2",
+        input_mapper="trictrac-v2",
+        policy_mapper="trictrac-std-v1",
+        board_size=24,
+        board_height=24,
+        board_width=1,
+        input_bool_channels=2 * 15 + 2,
+        input_scalar_channels=6 + 2 + 3 + 2 * 5,
+        input_mv_channels=5,
+        policy_shape=(514,),
+        policy_conv_channels=None,
+        estimate_moves_per_game=150,
+        encode_mv=encode_trictrac_mv,
         possible_mvs=range(514),
         symmetry=UnitSymmetry(),
     ),
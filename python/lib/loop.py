@@ -42,6 +42,10 @@ class FixedSelfplaySettings:
     saved_state_channels: int
     eval_random_symmetries: bool
 
+    # extra compiled batch sizes for the adaptive executors, gpu_batch_size is always included
+    adaptive_batching: bool = False
+    gpu_batch_sizes: List[int] = field(default_factory=list)
+
     def to_startup(self, output_folder: str, first_gen: int):
         return StartupSettings(
             game=self.game.name,
@@ -57,6 +61,8 @@ class FixedSelfplaySettings:
             search_batch_size=self.search_batch_size,
             saved_state_channels=self.saved_state_channels,
             eval_random_symmetries=self.eval_random_symmetries,
+            adaptive_batching=self.adaptive_batching,
+            gpu_batch_sizes=self.gpu_batch_sizes,
         )
 
 
@@ -13,6 +13,15 @@ def rel_path(name: str):
     return os.path.join(os.path.dirname(__file__), name)
 
 
+def load_mapping_export(game: str) -> dict:
+    """
+    Load the mapping description of a game written by `write_mapping.rs`, see `MappingExport` on the rust side.
+    The files are written to `ignored/mapping` and should be copied here.
+    """
+    with open(rel_path(f"{game}.json")) as f:
+        return json.load(f)
+
+
 def load_file_lines_to_tensor(name: str):
     with open(rel_path(name)) as f:
         data = [int(line) for line in f]
@@ -36,8 +45,12 @@ def load_file_lines_as_nested(name: str):
 CHESS_FLAT_TO_CONV = load_file_lines_to_tensor("chess_flat_to_conv.txt")
 assert CHESS_FLAT_TO_CONV.shape == (1880,), CHESS_FLAT_TO_CONV.shape
 
+# Generated by `write_chess_mapping` from `ChessAttPolicyMapper`, so it always matches the chess-att layout.
+# Before chess-att existed, underpromotions used a file-major layout with off-by-one piece indices. That didn't
+#   match the piece-major layout of `AttentionPolicyHead` and put h-file knight underpromotions in the row of the next
+#   from square. Networks trained with the old table keep it in their `FLAT_TO_ATT` buffer, so they still load.
 CHESS_FLAT_TO_ATT = load_file_lines_to_tensor("chess_flat_to_att.txt")
-assert CHESS_FLAT_TO_CONV.shape == (1880,), CHESS_FLAT_TO_CONV.shape
+assert CHESS_FLAT_TO_ATT.shape == (1880,), CHESS_FLAT_TO_ATT.shape
 
 CHESS_FLAT_TO_MOVE_INPUT = np.genfromtxt(
     rel_path("chess_flat_to_move_input.txt"),
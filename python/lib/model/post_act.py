@@ -1,4 +1,4 @@
-from typing import Optional
+from typing import Optional, List
 
 import torch
 from torch import nn
@@ -8,13 +8,13 @@ from lib.mapping.mapping import CHESS_FLAT_TO_ATT, CHESS_FLAT_TO_CONV
 
 
 class ScalarHead(nn.Module):
-    def __init__(self, board_size: int, channels: int, hidden_channels: int, hidden_size: int):
+    def __init__(self, game: Game, channels: int, hidden_channels: int, hidden_size: int):
         super().__init__()
         self.seq = nn.Sequential(
             conv2d(channels, hidden_channels, 1),
             nn.ReLU(),
             nn.Flatten(),
-            nn.Linear(hidden_channels * board_size * board_size, hidden_size),
+            nn.Linear(hidden_channels * game.board_area, hidden_size),
             nn.ReLU(),
             nn.Linear(hidden_size, 1 + 3 + 1)
         )
@@ -23,6 +23,25 @@ class ScalarHead(nn.Module):
         return self.seq(common)
 
 
+class ScoreHead(nn.Module):
+    """Predicts the final score lead for the next player, see `SCORE_LEAD_OUTPUT` on the rust side."""
+
+    def __init__(self, game: Game, channels: int, hidden_channels: int, hidden_size: int):
+        super().__init__()
+        self.seq = nn.Sequential(
+            conv2d(channels, hidden_channels, 1),
+            nn.ReLU(),
+            nn.Flatten(),
+            nn.Linear(hidden_channels * game.board_area, hidden_size),
+            nn.ReLU(),
+            nn.Linear(hidden_size, 1)
+        )
+
+    def forward(self, common):
+        # the rust side expects a scalar per board
+        return self.seq(common).squeeze(1)
+
+
 class DensePolicyHead(nn.Module):
     def __init__(self, game: Game, channels: int, hidden_channels: Optional[int], hidden_size: Optional[int]):
         super().__init__()
@@ -35,7 +54,7 @@ class DensePolicyHead(nn.Module):
             channels = hidden_channels
 
         seq.append(nn.Flatten())
-        size = channels * game.board_size * game.board_size
+        size = channels * game.board_area
 
         if hidden_size is not None:
             seq.append(nn.Linear(size, hidden_size))
@@ -56,14 +75,14 @@ class ConvPolicyHead(nn.Module):
         assert game.policy_conv_channels is not None, "Conv head only works for games with policy_conv_channels set"
         super().__init__()
 
-        policy_count = extra_moves + game.policy_conv_channels * game.board_size * game.board_size
+        policy_count = extra_moves + game.policy_conv_channels * game.board_area
         assert game.policy_shape == (policy_count,)
 
         self.extra_moves = extra_moves
         self.seq_extra = nn.Sequential(
             conv2d(channels, 1, 1),
             nn.Flatten(),
-            nn.Linear(game.board_size * game.board_size, extra_moves)
+            nn.Linear(game.board_area, extra_moves)
         )
 
         self.seq = nn.Sequential(
@@ -115,9 +134,12 @@ class AtaxxConvPolicyHead(nn.Module):
 class AttentionPolicyHead(nn.Module):
     def __init__(self, game: Game, channels: int, query_channels: int):
         super().__init__()
-        assert game.name == "chess" or game.name.startswith("chess-hist"), \
+        assert game.name == "chess" or game.name.startswith("chess-hist") or game.name == "chess-att", \
             "Attention policy head only works for chess for now"
 
+        # the chess-att policy layout is exactly the attention matrix, otherwise we need to gather the flat moves
+        self.gather_flat = game.policy_mapper != "chess-att-v1"
+
         self.query_channels = query_channels
         self.conv_bulk = conv2d(channels, 2 * query_channels, 1)
         self.conv_under = conv2d(channels, 3 * query_channels, 1)
@@ -137,6 +159,9 @@ class AttentionPolicyHead(nn.Module):
         # TODO try to do this scaling inside of the weight (and bias?) initializations instead
         policy = torch.bmm(q_from.transpose(1, 2), q_to) / self.query_channels ** 0.5
 
+        if not self.gather_flat:
+            return policy.flatten(1)
+
         flat_policy = policy.flatten(1)[:, self.FLAT_TO_ATT]
         return flat_policy
 
@@ -156,7 +181,7 @@ class ArimaaPolicyHead(nn.Module):
             conv2d(channels, hidden_channels, 1),
             nn.ReLU(),
             nn.Flatten(),
-            nn.Linear(hidden_channels * game.board_size * game.board_size, hidden_size),
+            nn.Linear(hidden_channels * game.board_area, hidden_size),
             nn.ReLU(),
             nn.Linear(hidden_size, 1 + 6)
         )
@@ -185,17 +210,30 @@ class ConcatInputsChannelwise(nn.Module):
 
 
 class PredictionHeads(nn.Module):
-    def __init__(self, common: nn.Module, scalar_head: nn.Module, policy_head: nn.Module):
+    def __init__(
+            self,
+            common: nn.Module, scalar_head: nn.Module, policy_head: nn.Module,
+            score_head: Optional[nn.Module] = None,
+    ):
         super().__init__()
         self.common = common
         self.scalar_head = scalar_head
         self.policy_head = policy_head
+        self.score_head = score_head
+
+        # the names of the extra outputs after (scalars, policy), in the order of the mapper aux outputs
+        self.aux_outputs: List[str] = ["score_lead"] if score_head is not None else []
 
     def forward(self, input):
         common = self.common(input)
         scalars = self.scalar_head(common)
         policy = self.policy_head(common)
-        return scalars, policy
+
+        if self.score_head is None:
+            return scalars, policy
+
+        score_lead = self.score_head(common)
+        return scalars, policy, score_lead
 
 
 class ResTower(nn.Module):
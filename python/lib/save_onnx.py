@@ -2,7 +2,7 @@ import json
 import os.path
 import warnings
 from pathlib import Path
-from typing import Optional, List
+from typing import Optional, List, Tuple
 
 import torch
 from torch import nn
@@ -11,12 +11,18 @@ from lib.games import Game
 from lib.networks import MuZeroNetworks
 from lib.util import guess_module_device
 
+# the standard outputs of each layout, see `OutputLayout` on the rust side
+OUTPUT_LAYOUTS = {
+    "scalars_policy": ["scalars", "policy"],
+    "value_wdl_policy": ["value", "wdl", "policy"],
+}
+
 
 def save_muzero_onnx(game: Game, path_base: str, networks: MuZeroNetworks, check_batch_size: Optional[int]):
     assert path_base.endswith("_"), f"Path must end with '_', got '{path_base}'"
 
-    state_shape = (networks.state_channels, game.board_size, game.board_size)
-    state_limit_shape = (networks.state_channels_saved, game.board_size, game.board_size)
+    state_shape = (networks.state_channels, game.board_height, game.board_width)
+    state_limit_shape = (networks.state_channels_saved, game.board_height, game.board_width)
 
     info_path = path_base + "info.json"
     assert not os.path.exists(info_path), f"Path '{info_path}' already exists"
@@ -47,24 +53,60 @@ def save_muzero_onnx(game: Game, path_base: str, networks: MuZeroNetworks, check
         check_batch_size
     )
 
+    output_names = network_output_names(networks.prediction)
     save_onnx_inner(
         path_base + "prediction.onnx",
         networks.prediction,
         [state_shape],
         ["state"],
-        ["scalars", "policy"],
+        output_names,
         check_batch_size
     )
+    # the prediction network is the one that produces the mapped outputs, so it carries the metadata
+    save_metadata(game, path_base + "prediction.onnx", output_names)
 
 
 def save_onnx(game: Game, path_onnx: str, network: nn.Module, check_batch_size: Optional[int]):
+    output_names = network_output_names(network)
     save_onnx_inner(
         path_onnx,
         network, [game.full_input_shape],
-        ["input"], ["scalars", "policy"],
+        ["input"], output_names,
         check_batch_size
     )
 
+    save_metadata(game, path_onnx, output_names)
+
+
+def network_output_names(network: nn.Module) -> List[str]:
+    """
+    The names of the outputs of `network`: the standard `(scalars, policy)` outputs,
+    followed by the auxiliary outputs listed in `network.aux_outputs`, if any.
+    """
+    return OUTPUT_LAYOUTS["scalars_policy"] + list(getattr(network, "aux_outputs", []))
+
+
+def split_output_names(output_names: List[str]) -> Tuple[str, List[str]]:
+    """Split the output names into the output layout and the names of the auxiliary outputs that follow it."""
+    for layout, standard_names in OUTPUT_LAYOUTS.items():
+        if output_names[:len(standard_names)] == standard_names:
+            return layout, output_names[len(standard_names):]
+    raise ValueError(f"Outputs {output_names} don't start with any known layout")
+
+
+def save_metadata(game: Game, path_onnx: str, output_names: List[str]):
+    # sidecar metadata, see `NetworkMetadata` on the rust side
+    output_layout, aux_outputs = split_output_names(output_names)
+    metadata = {
+        "game": game.name,
+        "input_mapper": game.input_mapper,
+        "policy_mapper": game.policy_mapper,
+        "output_layout": output_layout,
+        "aux_outputs": aux_outputs,
+    }
+    with open(Path(path_onnx).with_suffix(".json"), "w") as f:
+        json.dump(metadata, f)
+
 
 def save_onnx_inner(
         path_onnx,
@@ -90,6 +132,8 @@ def save_onnx_inner(
 
     check_inputs_device = [x.to(guessed_device) for x in check_inputs]
     check_outputs_device = network(*check_inputs_device)
+    assert len(check_outputs_device) == len(output_names), \
+        f"Network has {len(check_outputs_device)} outputs but got {len(output_names)} names {output_names}"
 
     # optionally save the inputs and outputs for testing purposes
     if check_batch_size is not None:
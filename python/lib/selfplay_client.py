@@ -3,8 +3,8 @@ import json
 import os
 import socket
 import time
-from dataclasses import dataclass
-from typing import Union, Optional
+from dataclasses import dataclass, field
+from typing import Union, Optional, List
 
 
 @dataclass
@@ -26,6 +26,9 @@ class StartupSettings:
     saved_state_channels: int
     eval_random_symmetries: bool
 
+    adaptive_batching: bool = False
+    gpu_batch_sizes: List[int] = field(default_factory=list)
+
     def as_dict(self):
         return dataclasses.asdict(self)
 
@@ -36,12 +39,17 @@ class UctWeights:
     moves_left_weight: Optional[float]
     moves_left_clip: Optional[float]
     moves_left_sharpness: Optional[float]
+    score_weight: Optional[float]
+    score_scale: Optional[float]
+    forced_playouts_k: Optional[float]
 
     @staticmethod
     def default():
         return UctWeights(
             exploration_weight=None, moves_left_weight=None,
-            moves_left_clip=None, moves_left_sharpness=None
+            moves_left_clip=None, moves_left_sharpness=None,
+            score_weight=None, score_scale=None,
+            forced_playouts_k=None,
         )
 
     def as_dict(self):
@@ -53,8 +61,9 @@ class SelfplaySettings:
     max_game_length: int
     weights: UctWeights
     q_mode: str
-    temperature: float
-    zero_temp_move_count: int
+    temperature: str
+    temperature_visit_threshold: float
+    temperature_best_value_target: bool
     dirichlet_alpha: float
     dirichlet_eps: float
     search_policy_temperature_root: float
@@ -62,10 +71,12 @@ class SelfplaySettings:
     search_fpu_root: str
     search_fpu_child: str
     search_virtual_loss_weight: float
+    policy_target_pruning: bool
     full_search_prob: float
     full_iterations: int
     part_iterations: int
     top_moves: int
+    # cached evaluations per generator, the generators on a device share one cache of the combined size
     cache_size: int
 
     def as_dict(self):
@@ -127,6 +138,8 @@ class SelfplayClient:
         message = json.loads(line)
         if message == "Stopped":
             raise RuntimeError("Selfplay server stopped")
+        if isinstance(message, dict) and "Error" in message:
+            raise RuntimeError(f"Selfplay server error: {message['Error']['message']}")
 
         print(f"Received message {message}")
         return message["FinishedFile"]["index"]
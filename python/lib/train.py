@@ -56,6 +56,10 @@ class TrainSettings:
 
     mask_policy: bool
 
+    # only used for networks with a score head, positions without a final score are skipped
+    score_weight: float = 0.0
+    score_delta: float = 20.0
+
     def train_step(
             self,
             batch: EitherBatch,
@@ -93,8 +97,8 @@ class TrainSettings:
         return loss
 
     def evaluate_batch(self, network: nn.Module, batch: PositionBatch, log_prefix: str, logger: Logger):
-        scalars, policy_logits = network(batch.input_full)
-        loss = self.evaluate_batch_predictions(log_prefix, logger, False, batch, scalars, policy_logits)
+        scalars, policy_logits, *aux = network(batch.input_full)
+        loss = self.evaluate_batch_predictions(log_prefix, logger, False, batch, scalars, policy_logits, *aux)
         return loss
 
     def evaluate_batch_unrolled(
@@ -124,7 +128,7 @@ class TrainSettings:
                         logger, step_prefix
                     )
 
-            scalars_k, policy_logits_k = networks.prediction(curr_state)
+            scalars_k, policy_logits_k, *aux_k = networks.prediction(curr_state)
 
             # limit the number of channels that have to be saved
             curr_state = curr_state[:, :networks.state_channels_saved, :, :]
@@ -135,7 +139,7 @@ class TrainSettings:
 
             total_loss += self.evaluate_batch_predictions(
                 step_prefix, logger, True,
-                batch.positions[k], scalars_k, policy_logits_k
+                batch.positions[k], scalars_k, policy_logits_k, *aux_k
             )
 
             # TODO is a BN layer inside of the networks enough for hidden state normalization?
@@ -170,6 +174,7 @@ class TrainSettings:
             log_prefix: str, logger: Logger, log_policy_norm: bool,
             batch: PositionBatch,
             scalars, policy_logits,
+            score_lead=None,
     ):
         """Returns the total loss for the given batch while logging a bunch of statistics"""
 
@@ -202,6 +207,9 @@ class TrainSettings:
             eval_policy.train_loss
         )
 
+        if score_lead is not None:
+            loss_total = loss_total + self.evaluate_score(log_prefix, logger, batch, score_lead)
+
         # log terminal losses
         terminal_count = batch.is_terminal.sum()
         loss_wdl_terminal = (loss_wdl_separate * batch.is_terminal).sum() / terminal_count
@@ -232,6 +240,27 @@ class TrainSettings:
 
         return loss_total
 
+    def evaluate_score(self, log_prefix: str, logger: Logger, batch: PositionBatch, score_lead):
+        has_score = ~batch.score_final.isnan()
+        has_score_count = has_score.sum()
+
+        if has_score_count == 0:
+            loss_score = torch.zeros((), device=score_lead.device)
+        else:
+            loss_score_separate = nnf.huber_loss(
+                score_lead[has_score], batch.score_final[has_score],
+                delta=self.score_delta, reduction="none"
+            )
+            # normalize by the full batch so positions without a score don't get a larger weight
+            loss_score = loss_score_separate.sum() / len(batch)
+
+        score_weighed = self.score_weight * loss_score
+
+        logger.log("loss-score", f"{log_prefix} score", loss_score)
+        logger.log("loss-part", f"{log_prefix} score", score_weighed)
+
+        return score_weighed
+
     def combine_losses(
             self, log_prefix: str, logger: Logger,
             value, wdl, moves_left, policy
@@ -32,8 +32,9 @@ def main():
     )
 
     selfplay_settings = SelfplaySettings(
-        temperature=1.0,
-        zero_temp_move_count=30,
+        temperature="step:1.0@30",
+        temperature_visit_threshold=0.0,
+        temperature_best_value_target=False,
         q_mode="wdl+0.0",
         max_game_length=400,
         dirichlet_alpha=0.03,
@@ -43,6 +44,7 @@ def main():
         search_fpu_root="fixed+0.1",
         search_fpu_child="relative+0",
         search_virtual_loss_weight=1.0,
+        policy_target_pruning=False,
         full_search_prob=1.0,
         full_iterations=800,
         part_iterations=20,
@@ -68,7 +70,7 @@ def main():
     def build_network(depth: int, channels: int):
         return PredictionHeads(
             common=ResTower(depth, game.full_input_channels, channels),
-            scalar_head=ScalarHead(game.board_size, channels, 4, 32),
+            scalar_head=ScalarHead(game, channels, 4, 32),
             policy_head=ConvPolicyHead(game, channels, extra_moves=1),
         )
 
@@ -37,8 +37,9 @@ def main():
     )
 
     selfplay_settings = SelfplaySettings(
-        temperature=1.0,
-        zero_temp_move_count=30,
+        temperature="step:1.0@30",
+        temperature_visit_threshold=0.0,
+        temperature_best_value_target=False,
         q_mode="wdl+0.0",
         max_game_length=400,
         dirichlet_alpha=0.2,
@@ -49,6 +50,7 @@ def main():
         search_fpu_root="fixed+1",
         search_fpu_child="relative+0",
         search_virtual_loss_weight=1.0,
+        policy_target_pruning=False,
         full_search_prob=1.0,
         full_iterations=200,
         part_iterations=20,
@@ -85,7 +87,7 @@ def main():
         ))
         prediction = PredictionHeads(
             common=ResBlock(channels),
-            scalar_head=ScalarHead(game.board_size, channels, 8, 128),
+            scalar_head=ScalarHead(game, channels, 8, 128),
             # policy_head=AttentionPolicyHead(game, channels, channels)
             policy_head=ConvPolicyHead(game, channels)
         )
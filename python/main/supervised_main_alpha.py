@@ -72,7 +72,7 @@ def main(plotter: LogPlotter):
             common=AttentionTower(game.board_size, game.full_input_channels, 16, channels, 8, 16, 16, 256, 0.1),
             # common=ResTower(8, game.full_input_channels, channels),
 
-            scalar_head=ScalarHead(game.board_size, channels, 4, 32),
+            scalar_head=ScalarHead(game, channels, 4, 32),
             policy_head=AttentionPolicyHead(game, channels, channels),
         )
 
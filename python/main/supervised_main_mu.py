@@ -77,7 +77,7 @@ def main(plotter: LogPlotter):
     ))
     prediction = PredictionHeads(
         common=ResTower(depth, saved_channels, channels, final_affine=True),
-        scalar_head=ScalarHead(game.board_size, channels, 8, 128),
+        scalar_head=ScalarHead(game, channels, 8, 128),
         policy_head=DensePolicyHead(game, channels, 32, None)
     )
 
@@ -41,10 +41,10 @@ def save_networks(output_folder: str, check_batch_size: int, networks: Dict[str,
 
 
 def prepare_output_folder(output_folder):
-    """ check whether the folder exists and only contains onnx and bin files, then delete those """
+    """ check whether the folder exists and only contains onnx, bin and metadata files, then delete those """
     assert os.path.exists(output_folder)
     for file in glob.glob(output_folder + "*"):
-        assert file.endswith(".onnx") or file.endswith(".bin")
+        assert file.endswith(".onnx") or file.endswith(".bin") or file.endswith(".json")
     for file in glob.glob(output_folder + "*"):
         os.remove(file)
 
@@ -0,0 +1,166 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use kn_graph::shape::Shape;
+
+use kz_util::game::Game;
+
+use crate::network::metadata::MetadataError;
+
+/// Anything that can go wrong while loading or evaluating a network.
+#[derive(Debug)]
+pub enum NetworkError {
+    GraphLoad {
+        path: PathBuf,
+        message: String,
+    },
+    Metadata(MetadataError),
+    Shape(ShapeError),
+    NanOutput(NanOutputError),
+    Mapping(MappingError),
+    /// Communication with a remote network failed.
+    Io(std::io::Error),
+}
+
+/// The graph inputs or outputs don't match the mapper.
+#[derive(Debug)]
+pub enum ShapeError {
+    InputCount {
+        actual: usize,
+    },
+    Input {
+        expected: Shape,
+        actual: Shape,
+    },
+    OutputCount {
+        actual: usize,
+    },
+    Output {
+        output: &'static str,
+        expected: Shape,
+        actual: Shape,
+    },
+}
+
+/// The network returned a non-finite value for one of the boards in a batch.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct NanOutputError {
+    pub batch_index: usize,
+    pub output: &'static str,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MappingError {
+    UnknownGame(String),
+    PolicyIndexOutOfBounds { index: usize, policy_len: usize },
+}
+
+/// Fallible version of [Game::parse].
+pub fn parse_game(game: &str) -> Result<Game, MappingError> {
+    Game::parse(game).ok_or_else(|| MappingError::UnknownGame(game.to_owned()))
+}
+
+impl From<MetadataError> for NetworkError {
+    fn from(e: MetadataError) -> Self {
+        NetworkError::Metadata(e)
+    }
+}
+
+impl From<ShapeError> for NetworkError {
+    fn from(e: ShapeError) -> Self {
+        NetworkError::Shape(e)
+    }
+}
+
+impl From<NanOutputError> for NetworkError {
+    fn from(e: NanOutputError) -> Self {
+        NetworkError::NanOutput(e)
+    }
+}
+
+impl From<MappingError> for NetworkError {
+    fn from(e: MappingError) -> Self {
+        NetworkError::Mapping(e)
+    }
+}
+
+impl From<std::io::Error> for NetworkError {
+    fn from(e: std::io::Error) -> Self {
+        NetworkError::Io(e)
+    }
+}
+
+impl Display for NetworkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkError::GraphLoad { path, message } => write!(f, "Failed to load graph {:?}: {}", path, message),
+            NetworkError::Metadata(e) => write!(f, "{}", e),
+            NetworkError::Shape(e) => write!(f, "{}", e),
+            NetworkError::NanOutput(e) => write!(f, "{}", e),
+            NetworkError::Mapping(e) => write!(f, "{}", e),
+            NetworkError::Io(e) => write!(f, "Network IO error: {}", e),
+        }
+    }
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeError::InputCount { actual } => write!(f, "Expected a single graph input, got {}", actual),
+            ShapeError::Input { expected, actual } => {
+                write!(
+                    f,
+                    "Input shape mismatch, mapper expects {:?}, graph has {:?}",
+                    expected, actual
+                )
+            }
+            ShapeError::OutputCount { actual } => write!(
+                f,
+                "Wrong number of outputs, expected either (value, wdl, policy) or (scalars, policy), got {}",
+                actual
+            ),
+            ShapeError::Output {
+                output,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Wrong {} shape, expected {:?}, graph has {:?}",
+                output, expected, actual
+            ),
+        }
+    }
+}
+
+impl Display for NanOutputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Network output '{}' is not finite for batch index {}",
+            self.output, self.batch_index
+        )
+    }
+}
+
+impl Display for MappingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MappingError::UnknownGame(game) => write!(f, "Unknown game '{}'", game),
+            MappingError::PolicyIndexOutOfBounds { index, policy_len } => {
+                write!(
+                    f,
+                    "Policy index {} out of bounds for policy length {}",
+                    index, policy_len
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+impl std::error::Error for ShapeError {}
+
+impl std::error::Error for NanOutputError {}
+
+impl std::error::Error for MappingError {}
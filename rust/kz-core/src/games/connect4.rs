@@ -0,0 +1,244 @@
+use std::fmt::{Display, Formatter};
+
+use board_game::board::{AltBoard, Board, BoardDone, BoardMoves, BoardSymmetry, Outcome, PlayError, Player};
+use board_game::symmetry::UnitSymmetry;
+use internal_iterator::{Internal, IntoInternal};
+
+pub const WIDTH: u8 = 7;
+pub const HEIGHT: u8 = 6;
+
+/// Bits per column in the bitboards, the extra top bit is always empty
+/// so lines can't wrap around to the next column.
+const COLUMN_STRIDE: u8 = HEIGHT + 1;
+const COLUMN_MASK: u64 = (1 << HEIGHT) - 1;
+
+/// Drop a piece in the given column, `0..WIDTH` from left to right.
+/// Formatted as the 1-based column index, the usual notation for move sequences.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Connect4Move(u8);
+
+impl Connect4Move {
+    pub fn new(column: u8) -> Self {
+        assert!(column < WIDTH, "Column {} out of bounds", column);
+        Connect4Move(column)
+    }
+
+    pub fn column(self) -> u8 {
+        self.0
+    }
+}
+
+impl Display for Connect4Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0 + 1)
+    }
+}
+
+/// Standard 7x6 Connect Four, player A moves first.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Connect4Board {
+    /// Bitboards of the pieces of player A and B, bit `column * COLUMN_STRIDE + row` with row 0 at the bottom.
+    tiles: [u64; 2],
+    next_player: Player,
+    outcome: Option<Outcome>,
+}
+
+impl Default for Connect4Board {
+    fn default() -> Self {
+        Connect4Board {
+            tiles: [0, 0],
+            next_player: Player::A,
+            outcome: None,
+        }
+    }
+}
+
+impl Connect4Board {
+    /// Play the given sequence of 1-based column indices starting from the empty board, eg. `"4453"`.
+    pub fn from_moves(moves: &str) -> Option<Self> {
+        let mut board = Connect4Board::default();
+        for c in moves.chars() {
+            let column = c.to_digit(10)?;
+            if column == 0 || column > WIDTH as u32 {
+                return None;
+            }
+            board.play(Connect4Move::new(column as u8 - 1)).ok()?;
+        }
+        Some(board)
+    }
+
+    /// The player that owns the piece at the given position, `row` counts from the bottom.
+    pub fn tile(&self, column: u8, row: u8) -> Option<Player> {
+        assert!(column < WIDTH && row < HEIGHT);
+        let bit = 1 << (column * COLUMN_STRIDE + row);
+
+        if self.tiles[0] & bit != 0 {
+            Some(Player::A)
+        } else if self.tiles[1] & bit != 0 {
+            Some(Player::B)
+        } else {
+            None
+        }
+    }
+
+    /// The number of pieces in the given column.
+    pub fn column_height(&self, column: u8) -> u8 {
+        assert!(column < WIDTH);
+        let occupied = self.tiles[0] | self.tiles[1];
+        ((occupied >> (column * COLUMN_STRIDE)) & COLUMN_MASK).count_ones() as u8
+    }
+
+    pub fn move_count(&self) -> u32 {
+        (self.tiles[0] | self.tiles[1]).count_ones()
+    }
+
+    fn ensure_not_done(&self) -> Result<(), BoardDone> {
+        match self.outcome {
+            None => Ok(()),
+            Some(_) => Err(BoardDone),
+        }
+    }
+
+    fn available_columns(&self) -> ColumnIterator {
+        let mask = (0..WIDTH)
+            .filter(|&c| self.column_height(c) < HEIGHT)
+            .fold(0, |mask, c| mask | 1 << c);
+        ColumnIterator { mask }
+    }
+}
+
+fn player_index(player: Player) -> usize {
+    match player {
+        Player::A => 0,
+        Player::B => 1,
+    }
+}
+
+fn has_four_in_a_row(tiles: u64) -> bool {
+    // vertical, horizontal and both diagonals
+    [1, COLUMN_STRIDE, COLUMN_STRIDE - 1, COLUMN_STRIDE + 1]
+        .iter()
+        .any(|&shift| {
+            let pairs = tiles & (tiles >> shift);
+            pairs & (pairs >> (2 * shift)) != 0
+        })
+}
+
+impl Board for Connect4Board {
+    type Move = Connect4Move;
+
+    fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    fn is_available_move(&self, mv: Self::Move) -> Result<bool, BoardDone> {
+        self.ensure_not_done()?;
+        Ok(self.column_height(mv.column()) < HEIGHT)
+    }
+
+    fn play(&mut self, mv: Self::Move) -> Result<(), PlayError> {
+        if !self.is_available_move(mv)? {
+            return Err(PlayError::UnavailableMove);
+        }
+
+        let column = mv.column();
+        let bit = 1 << (column * COLUMN_STRIDE + self.column_height(column));
+
+        let player = self.next_player;
+        let tiles = &mut self.tiles[player_index(player)];
+        *tiles |= bit;
+
+        if has_four_in_a_row(*tiles) {
+            self.outcome = Some(Outcome::WonBy(player));
+        } else if self.move_count() == (WIDTH * HEIGHT) as u32 {
+            self.outcome = Some(Outcome::Draw);
+        }
+
+        self.next_player = player.other();
+        Ok(())
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    fn can_lose_after_move() -> bool {
+        false
+    }
+}
+
+impl AltBoard for Connect4Board {}
+
+/// Iterator over the columns set in a bitmask.
+#[derive(Debug, Clone)]
+pub struct ColumnIterator {
+    mask: u8,
+}
+
+impl Iterator for ColumnIterator {
+    type Item = Connect4Move;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.mask == 0 {
+            return None;
+        }
+        let column = self.mask.trailing_zeros() as u8;
+        self.mask &= self.mask - 1;
+        Some(Connect4Move::new(column))
+    }
+}
+
+impl<'a> BoardMoves<'a, Connect4Board> for Connect4Board {
+    type AllMovesIterator = Internal<ColumnIterator>;
+    type AvailableMovesIterator = Internal<ColumnIterator>;
+
+    fn all_possible_moves() -> Self::AllMovesIterator {
+        ColumnIterator { mask: (1 << WIDTH) - 1 }.into_internal()
+    }
+
+    fn available_moves(&'a self) -> Result<Self::AvailableMovesIterator, BoardDone> {
+        self.ensure_not_done()?;
+        Ok(self.available_columns().into_internal())
+    }
+}
+
+// TODO use the horizontal mirror symmetry
+impl BoardSymmetry<Connect4Board> for Connect4Board {
+    type Symmetry = UnitSymmetry;
+    type CanonicalKey = ();
+
+    fn map(&self, _: Self::Symmetry) -> Self {
+        self.clone()
+    }
+
+    fn map_move(&self, _: Self::Symmetry, mv: Connect4Move) -> Connect4Move {
+        mv
+    }
+
+    fn canonical_key(&self) -> Self::CanonicalKey {}
+}
+
+impl Display for Connect4Board {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in (0..HEIGHT).rev() {
+            for column in 0..WIDTH {
+                let c = match self.tile(column, row) {
+                    None => '.',
+                    Some(Player::A) => 'x',
+                    Some(Player::B) => 'o',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        for column in 0..WIDTH {
+            write!(f, "{}", column + 1)?;
+        }
+        writeln!(f)?;
+
+        match self.outcome {
+            None => writeln!(f, "next: {:?}", self.next_player),
+            Some(outcome) => writeln!(f, "outcome: {:?}", outcome),
+        }
+    }
+}
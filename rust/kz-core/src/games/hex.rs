@@ -0,0 +1,320 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use board_game::board::{AltBoard, Board, BoardDone, BoardMoves, BoardSymmetry, Outcome, PlayError, Player};
+use board_game::symmetry::UnitSymmetry;
+use internal_iterator::{Internal, IntoInternal};
+
+pub const MAX_SIZE: u8 = 19;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum HexMove {
+    /// The pie rule, only available as the second move of the game.
+    /// The first stone is mirrored along the long diagonal and becomes a stone of player B,
+    /// so each player keeps their own edges.
+    Swap,
+    /// Place a stone on the tile `(x, y)`.
+    Place(u8, u8),
+}
+
+impl Display for HexMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            HexMove::Swap => write!(f, "swap"),
+            HexMove::Place(x, y) => write!(f, "{}{}", (b'a' + x) as char, y + 1),
+        }
+    }
+}
+
+impl FromStr for HexMove {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "swap" {
+            return Ok(HexMove::Swap);
+        }
+
+        let mut chars = s.chars();
+        let x = chars.next().ok_or(())?;
+        if !x.is_ascii_lowercase() {
+            return Err(());
+        }
+        let y: u8 = chars.as_str().parse().map_err(|_| ())?;
+        if y == 0 {
+            return Err(());
+        }
+
+        Ok(HexMove::Place(x as u8 - b'a', y - 1))
+    }
+}
+
+/// Hex on a rhombus of `size x size` tiles, with the pie rule.
+///
+/// Player A moves first and connects the top and bottom edges (`y == 0` and `y == size - 1`),
+/// player B connects the left and right edges. Tile `(x, y)` neighbors `(x±1, y)`, `(x, y±1)`, `(x+1, y-1)` and `(x-1, y+1)`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct HexBoard {
+    size: u8,
+    tiles: Vec<Option<Player>>,
+    stone_count: u16,
+    next_player: Player,
+    outcome: Option<Outcome>,
+}
+
+impl HexBoard {
+    pub fn new(size: u8) -> Self {
+        assert!(
+            (1..=MAX_SIZE).contains(&size),
+            "Hex size must be in 1..={}, got {}",
+            MAX_SIZE,
+            size
+        );
+
+        HexBoard {
+            size,
+            tiles: vec![None; size as usize * size as usize],
+            stone_count: 0,
+            next_player: Player::A,
+            outcome: None,
+        }
+    }
+
+    /// Play the given space-separated moves starting from an empty board, eg. `"c3 swap d2"`.
+    pub fn from_moves(size: u8, moves: &str) -> Option<Self> {
+        let mut board = HexBoard::new(size);
+        for mv in moves.split_whitespace() {
+            board.play(mv.parse().ok()?).ok()?;
+        }
+        Some(board)
+    }
+
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    pub fn tile(&self, x: u8, y: u8) -> Option<Player> {
+        self.tiles[self.index(x, y)]
+    }
+
+    pub fn can_swap(&self) -> bool {
+        self.outcome.is_none() && self.stone_count == 1 && self.next_player == Player::B
+    }
+
+    fn index(&self, x: u8, y: u8) -> usize {
+        assert!(x < self.size && y < self.size);
+        y as usize * self.size as usize + x as usize
+    }
+
+    fn neighbors(&self, x: u8, y: u8) -> impl Iterator<Item = (u8, u8)> {
+        let size = self.size as i16;
+        let (x, y) = (x as i16, y as i16);
+
+        [(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1), (-1, 1)]
+            .into_iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(move |&(nx, ny)| 0 <= nx && nx < size && 0 <= ny && ny < size)
+            .map(|(nx, ny)| (nx as u8, ny as u8))
+    }
+
+    /// Whether the group of `player` stones containing `(x, y)` connects both edges of that player.
+    fn connects_edges(&self, player: Player, x: u8, y: u8) -> bool {
+        let edge_coord = |(x, y): (u8, u8)| match player {
+            Player::A => y,
+            Player::B => x,
+        };
+
+        let mut visited = vec![false; self.tiles.len()];
+        let mut stack = vec![(x, y)];
+        visited[self.index(x, y)] = true;
+
+        let mut start_edge = false;
+        let mut end_edge = false;
+
+        while let Some(curr) = stack.pop() {
+            start_edge |= edge_coord(curr) == 0;
+            end_edge |= edge_coord(curr) == self.size - 1;
+            if start_edge && end_edge {
+                return true;
+            }
+
+            for (nx, ny) in self.neighbors(curr.0, curr.1) {
+                let index = self.index(nx, ny);
+                if !visited[index] && self.tiles[index] == Some(player) {
+                    visited[index] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+
+        false
+    }
+
+    fn ensure_not_done(&self) -> Result<(), BoardDone> {
+        match self.outcome {
+            None => Ok(()),
+            Some(_) => Err(BoardDone),
+        }
+    }
+}
+
+impl Board for HexBoard {
+    type Move = HexMove;
+
+    fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    fn is_available_move(&self, mv: Self::Move) -> Result<bool, BoardDone> {
+        self.ensure_not_done()?;
+
+        Ok(match mv {
+            HexMove::Swap => self.can_swap(),
+            HexMove::Place(x, y) => x < self.size && y < self.size && self.tile(x, y).is_none(),
+        })
+    }
+
+    fn play(&mut self, mv: Self::Move) -> Result<(), PlayError> {
+        if !self.is_available_move(mv)? {
+            return Err(PlayError::UnavailableMove);
+        }
+
+        let player = self.next_player;
+        let (x, y) = match mv {
+            HexMove::Swap => {
+                let first = self.tiles.iter().position(|t| t.is_some()).unwrap();
+                let (x, y) = ((first % self.size as usize) as u8, (first / self.size as usize) as u8);
+
+                self.tiles[first] = None;
+                (y, x)
+            }
+            HexMove::Place(x, y) => {
+                self.stone_count += 1;
+                (x, y)
+            }
+        };
+
+        let index = self.index(x, y);
+        self.tiles[index] = Some(player);
+
+        if self.connects_edges(player, x, y) {
+            self.outcome = Some(Outcome::WonBy(player));
+        }
+
+        self.next_player = player.other();
+        Ok(())
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    fn can_lose_after_move() -> bool {
+        false
+    }
+}
+
+impl AltBoard for HexBoard {}
+
+/// Iterator over the optional swap move followed by the tiles set in a mask.
+#[derive(Debug, Clone)]
+pub struct MoveIterator {
+    size: u8,
+    swap: bool,
+    tiles: Vec<bool>,
+    next: usize,
+}
+
+impl Iterator for MoveIterator {
+    type Item = HexMove;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.swap {
+            self.swap = false;
+            return Some(HexMove::Swap);
+        }
+
+        while self.next < self.tiles.len() {
+            let index = self.next;
+            self.next += 1;
+
+            if self.tiles[index] {
+                let size = self.size as usize;
+                return Some(HexMove::Place((index % size) as u8, (index / size) as u8));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> BoardMoves<'a, HexBoard> for HexBoard {
+    type AllMovesIterator = Internal<MoveIterator>;
+    type AvailableMovesIterator = Internal<MoveIterator>;
+
+    fn all_possible_moves() -> Self::AllMovesIterator {
+        let size = MAX_SIZE as usize;
+        MoveIterator {
+            size: MAX_SIZE,
+            swap: true,
+            tiles: vec![true; size * size],
+            next: 0,
+        }
+        .into_internal()
+    }
+
+    fn available_moves(&'a self) -> Result<Self::AvailableMovesIterator, BoardDone> {
+        self.ensure_not_done()?;
+        Ok(MoveIterator {
+            size: self.size,
+            swap: self.can_swap(),
+            tiles: self.tiles.iter().map(|t| t.is_none()).collect(),
+            next: 0,
+        }
+        .into_internal())
+    }
+}
+
+// TODO use the 180 degree rotation symmetry
+impl BoardSymmetry<HexBoard> for HexBoard {
+    type Symmetry = UnitSymmetry;
+    type CanonicalKey = ();
+
+    fn map(&self, _: Self::Symmetry) -> Self {
+        self.clone()
+    }
+
+    fn map_move(&self, _: Self::Symmetry, mv: HexMove) -> HexMove {
+        mv
+    }
+
+    fn canonical_key(&self) -> Self::CanonicalKey {}
+}
+
+impl Display for HexBoard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "   ")?;
+        for x in 0..self.size {
+            write!(f, "{} ", (b'a' + x) as char)?;
+        }
+        writeln!(f)?;
+
+        for y in 0..self.size {
+            // shift each row to show the rhombus shape
+            write!(f, "{:>2} {}", y + 1, " ".repeat(y as usize))?;
+            for x in 0..self.size {
+                let c = match self.tile(x, y) {
+                    None => '.',
+                    Some(Player::A) => 'x',
+                    Some(Player::B) => 'o',
+                };
+                write!(f, "{} ", c)?;
+            }
+            writeln!(f)?;
+        }
+
+        match self.outcome {
+            None => writeln!(f, "next: {:?}", self.next_player),
+            Some(outcome) => writeln!(f, "outcome: {:?}", outcome),
+        }
+    }
+}
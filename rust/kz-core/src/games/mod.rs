@@ -0,0 +1,5 @@
+//! Games that are not available in `board_game`.
+
+pub mod connect4;
+pub mod hex;
+pub mod othello;
@@ -0,0 +1,404 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use board_game::board::{AltBoard, Board, BoardDone, BoardMoves, BoardSymmetry, Outcome, PlayError, Player};
+use board_game::symmetry::D4Symmetry;
+use internal_iterator::{Internal, IntoInternal};
+
+pub const SIZE: u8 = 8;
+pub const AREA: u8 = SIZE * SIZE;
+
+const NOT_FILE_A: u64 = 0xfefe_fefe_fefe_fefe;
+const NOT_FILE_H: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+
+/// The index of the tile at `(x, y)` in the bitboards, with `(0, 0)` being `a1`.
+pub fn tile_index(x: u8, y: u8) -> u8 {
+    assert!(x < SIZE && y < SIZE);
+    y * SIZE + x
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum OthelloMove {
+    /// Only available if there are no other moves.
+    Pass,
+    /// Place a piece on the tile with the given index, see [tile_index].
+    Place(u8),
+}
+
+impl Display for OthelloMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            OthelloMove::Pass => write!(f, "pass"),
+            OthelloMove::Place(index) => write!(f, "{}{}", (b'a' + index % SIZE) as char, index / SIZE + 1),
+        }
+    }
+}
+
+impl FromStr for OthelloMove {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "pass" {
+            return Ok(OthelloMove::Pass);
+        }
+
+        let bytes = s.as_bytes();
+        if bytes.len() != 2 {
+            return Err(());
+        }
+        let x = bytes[0].wrapping_sub(b'a');
+        let y = bytes[1].wrapping_sub(b'1');
+        if x >= SIZE || y >= SIZE {
+            return Err(());
+        }
+        Ok(OthelloMove::Place(tile_index(x, y)))
+    }
+}
+
+/// Standard 8x8 Othello, player A plays black and moves first.
+///
+/// A player that can't place a piece must pass, the game ends when neither player can place a piece.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct OthelloBoard {
+    /// Bitboards of the pieces of player A and B, see [tile_index].
+    tiles: [u64; 2],
+    next_player: Player,
+    outcome: Option<Outcome>,
+}
+
+impl Default for OthelloBoard {
+    fn default() -> Self {
+        let tiles_a = 1 << tile_index(4, 3) | 1 << tile_index(3, 4);
+        let tiles_b = 1 << tile_index(3, 3) | 1 << tile_index(4, 4);
+        OthelloBoard::from_parts(tiles_a, tiles_b, Player::A)
+    }
+}
+
+impl OthelloBoard {
+    /// Build a board from the given tiles. The outcome is derived from the position,
+    /// so this can also be used for positions that are not reachable from the start position.
+    pub fn from_parts(tiles_a: u64, tiles_b: u64, next_player: Player) -> Self {
+        assert_eq!(tiles_a & tiles_b, 0, "Tiles can't be occupied by both players");
+
+        let mut board = OthelloBoard {
+            tiles: [tiles_a, tiles_b],
+            next_player,
+            outcome: None,
+        };
+        board.update_outcome();
+        board
+    }
+
+    /// Parse a fen string in the same format as ataxx, eg. `"8/8/8/3xo3/3ox3/8/8/8 x"`.
+    /// The rows are listed from top to bottom, `x` is player A and `o` is player B, digits are empty tiles.
+    pub fn from_fen(fen: &str) -> Option<Self> {
+        let (tiles, next) = fen.trim().split_once(' ')?;
+
+        let rows: Vec<&str> = tiles.split('/').collect();
+        if rows.len() != SIZE as usize {
+            return None;
+        }
+
+        let mut tiles_a = 0;
+        let mut tiles_b = 0;
+        for (i, row) in rows.iter().enumerate() {
+            let y = SIZE - 1 - i as u8;
+            let mut x = 0;
+
+            for c in row.chars() {
+                match c {
+                    'x' | 'o' => {
+                        if x >= SIZE {
+                            return None;
+                        }
+                        let bit = 1 << tile_index(x, y);
+                        if c == 'x' {
+                            tiles_a |= bit;
+                        } else {
+                            tiles_b |= bit;
+                        }
+                        x += 1;
+                    }
+                    '1'..='8' => x += c.to_digit(10).unwrap() as u8,
+                    _ => return None,
+                }
+            }
+
+            if x != SIZE {
+                return None;
+            }
+        }
+
+        let next_player = match next {
+            "x" => Player::A,
+            "o" => Player::B,
+            _ => return None,
+        };
+
+        Some(OthelloBoard::from_parts(tiles_a, tiles_b, next_player))
+    }
+
+    pub fn tiles(&self, player: Player) -> u64 {
+        self.tiles[player_index(player)]
+    }
+
+    pub fn tile(&self, x: u8, y: u8) -> Option<Player> {
+        let bit = 1 << tile_index(x, y);
+        if self.tiles[0] & bit != 0 {
+            Some(Player::A)
+        } else if self.tiles[1] & bit != 0 {
+            Some(Player::B)
+        } else {
+            None
+        }
+    }
+
+    /// The tiles the next player can place a piece on.
+    pub fn placeable_tiles(&self) -> u64 {
+        if self.outcome.is_some() {
+            return 0;
+        }
+        placeable(self.tiles(self.next_player), self.tiles(self.next_player.other()))
+    }
+
+    pub fn must_pass(&self) -> bool {
+        self.outcome.is_none() && self.placeable_tiles() == 0
+    }
+
+    fn update_outcome(&mut self) {
+        let [a, b] = self.tiles;
+        let done = placeable(a, b) == 0 && placeable(b, a) == 0;
+
+        self.outcome = if done {
+            Some(match a.count_ones().cmp(&b.count_ones()) {
+                std::cmp::Ordering::Greater => Outcome::WonBy(Player::A),
+                std::cmp::Ordering::Less => Outcome::WonBy(Player::B),
+                std::cmp::Ordering::Equal => Outcome::Draw,
+            })
+        } else {
+            None
+        };
+    }
+
+    fn ensure_not_done(&self) -> Result<(), BoardDone> {
+        match self.outcome {
+            None => Ok(()),
+            Some(_) => Err(BoardDone),
+        }
+    }
+}
+
+fn player_index(player: Player) -> usize {
+    match player {
+        Player::A => 0,
+        Player::B => 1,
+    }
+}
+
+/// Shift all tiles one step in the given direction, `0..8` starting east and going counterclockwise.
+fn shift(tiles: u64, dir: u8) -> u64 {
+    match dir {
+        0 => (tiles << 1) & NOT_FILE_A,
+        1 => (tiles << 9) & NOT_FILE_A,
+        2 => tiles << 8,
+        3 => (tiles << 7) & NOT_FILE_H,
+        4 => (tiles >> 1) & NOT_FILE_H,
+        5 => (tiles >> 9) & NOT_FILE_H,
+        6 => tiles >> 8,
+        7 => (tiles >> 7) & NOT_FILE_A,
+        _ => unreachable!(),
+    }
+}
+
+fn placeable(own: u64, opp: u64) -> u64 {
+    let empty = !(own | opp);
+    let mut result = 0;
+
+    for dir in 0..8 {
+        // opponent lines starting next to our pieces, at most 6 long
+        let mut line = shift(own, dir) & opp;
+        for _ in 0..5 {
+            line |= shift(line, dir) & opp;
+        }
+        result |= shift(line, dir) & empty;
+    }
+
+    result
+}
+
+fn flipped(own: u64, opp: u64, placed: u64) -> u64 {
+    let mut result = 0;
+
+    for dir in 0..8 {
+        let mut line = 0;
+        let mut curr = shift(placed, dir);
+        while curr & opp != 0 {
+            line |= curr;
+            curr = shift(curr, dir);
+        }
+        if curr & own != 0 {
+            result |= line;
+        }
+    }
+
+    result
+}
+
+impl Board for OthelloBoard {
+    type Move = OthelloMove;
+
+    fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    fn is_available_move(&self, mv: Self::Move) -> Result<bool, BoardDone> {
+        self.ensure_not_done()?;
+        let placeable = self.placeable_tiles();
+
+        Ok(match mv {
+            OthelloMove::Pass => placeable == 0,
+            OthelloMove::Place(index) => index < AREA && placeable & (1 << index) != 0,
+        })
+    }
+
+    fn play(&mut self, mv: Self::Move) -> Result<(), PlayError> {
+        if !self.is_available_move(mv)? {
+            return Err(PlayError::UnavailableMove);
+        }
+
+        if let OthelloMove::Place(index) = mv {
+            let own_index = player_index(self.next_player);
+            let opp_index = 1 - own_index;
+
+            let placed = 1 << index;
+            let flipped = flipped(self.tiles[own_index], self.tiles[opp_index], placed);
+
+            self.tiles[own_index] |= placed | flipped;
+            self.tiles[opp_index] &= !flipped;
+        }
+
+        self.next_player = self.next_player.other();
+        self.update_outcome();
+
+        Ok(())
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    fn can_lose_after_move() -> bool {
+        true
+    }
+}
+
+impl AltBoard for OthelloBoard {}
+
+/// Iterator over a pass move followed by the tiles set in a bitmask.
+#[derive(Debug, Clone)]
+pub struct MoveIterator {
+    pass: bool,
+    tiles: u64,
+}
+
+impl Iterator for MoveIterator {
+    type Item = OthelloMove;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pass {
+            self.pass = false;
+            return Some(OthelloMove::Pass);
+        }
+        if self.tiles == 0 {
+            return None;
+        }
+        let index = self.tiles.trailing_zeros() as u8;
+        self.tiles &= self.tiles - 1;
+        Some(OthelloMove::Place(index))
+    }
+}
+
+impl<'a> BoardMoves<'a, OthelloBoard> for OthelloBoard {
+    type AllMovesIterator = Internal<MoveIterator>;
+    type AvailableMovesIterator = Internal<MoveIterator>;
+
+    fn all_possible_moves() -> Self::AllMovesIterator {
+        MoveIterator {
+            pass: true,
+            tiles: u64::MAX,
+        }
+        .into_internal()
+    }
+
+    fn available_moves(&'a self) -> Result<Self::AvailableMovesIterator, BoardDone> {
+        self.ensure_not_done()?;
+        let tiles = self.placeable_tiles();
+        Ok(MoveIterator {
+            pass: tiles == 0,
+            tiles,
+        }
+        .into_internal())
+    }
+}
+
+fn map_index(sym: D4Symmetry, index: u8) -> u8 {
+    let (x, y) = (index % SIZE, index / SIZE);
+    let (x, y) = if sym.transpose { (y, x) } else { (x, y) };
+    let x = if sym.flip_x { SIZE - 1 - x } else { x };
+    let y = if sym.flip_y { SIZE - 1 - y } else { y };
+    tile_index(x, y)
+}
+
+fn map_tiles(sym: D4Symmetry, tiles: u64) -> u64 {
+    MoveIterator { pass: false, tiles }.fold(0, |result, mv| match mv {
+        OthelloMove::Place(index) => result | 1 << map_index(sym, index),
+        OthelloMove::Pass => unreachable!(),
+    })
+}
+
+impl BoardSymmetry<OthelloBoard> for OthelloBoard {
+    type Symmetry = D4Symmetry;
+    type CanonicalKey = [u64; 2];
+
+    fn map(&self, sym: Self::Symmetry) -> Self {
+        OthelloBoard {
+            tiles: self.tiles.map(|tiles| map_tiles(sym, tiles)),
+            next_player: self.next_player,
+            outcome: self.outcome,
+        }
+    }
+
+    fn map_move(&self, sym: Self::Symmetry, mv: OthelloMove) -> OthelloMove {
+        match mv {
+            OthelloMove::Pass => OthelloMove::Pass,
+            OthelloMove::Place(index) => OthelloMove::Place(map_index(sym, index)),
+        }
+    }
+
+    fn canonical_key(&self) -> Self::CanonicalKey {
+        self.tiles
+    }
+}
+
+impl Display for OthelloBoard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in (0..SIZE).rev() {
+            write!(f, "{} ", y + 1)?;
+            for x in 0..SIZE {
+                let c = match self.tile(x, y) {
+                    None => '.',
+                    Some(Player::A) => 'x',
+                    Some(Player::B) => 'o',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "  abcdefgh")?;
+
+        match self.outcome {
+            None => writeln!(f, "next: {:?}", self.next_player),
+            Some(outcome) => writeln!(f, "outcome: {:?}", outcome),
+        }
+    }
+}
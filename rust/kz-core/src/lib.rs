@@ -2,8 +2,11 @@
 pub mod muzero;
 pub mod zero;
 
+pub mod games;
 pub mod mapping;
 pub mod network;
 pub mod oracle;
+pub mod registry;
 
 pub mod bot;
+pub mod error;
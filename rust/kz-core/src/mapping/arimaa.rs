@@ -12,6 +12,10 @@ use crate::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
 pub struct ArimaaSplitMapper;
 
 impl InputMapper<ArimaaBoard> for ArimaaSplitMapper {
+    fn input_mapper_id(&self) -> String {
+        "arimaa-split-v1".to_owned()
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         [4 * 6 + 2, 8, 8]
     }
@@ -20,6 +24,27 @@ impl InputMapper<ArimaaBoard> for ArimaaSplitMapper {
         12
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        let mut names = vec![];
+        for prefix in ["us", "them", "pull", "push"] {
+            names.extend(
+                Piece::ALL
+                    .iter()
+                    .map(|piece| format!("{}_{:?}", prefix, piece).to_lowercase()),
+            );
+        }
+        names.push("placement".to_owned());
+        names.push("traps".to_owned());
+        names
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        let mut names = ["place", "play", "pull", "push"].map(str::to_owned).to_vec();
+        names.extend((0..ArimaaBoard::MAX_STEPS_PER_TURN).map(|i| format!("steps_taken_{}", i)));
+        names.extend(["gold_turn", "silver_turn", "history_len", "move_number"].map(str::to_owned));
+        names
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &ArimaaBoard) {
         let state = board.state();
         let next_player = board.next_player();
@@ -88,6 +113,10 @@ fn append_push_pull_planes(bools: &mut BitBuffer, pair: Option<(Square, Piece)>,
 }
 
 impl PolicyMapper<ArimaaBoard> for ArimaaSplitMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "arimaa-split-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         &[1 + 6 + 4 * 8 * 8]
     }
@@ -136,7 +165,7 @@ impl PolicyMapper<ArimaaBoard> for ArimaaSplitMapper {
 }
 
 impl MuZeroMapper<ArimaaBoard> for ArimaaSplitMapper {
-    fn state_board_size(&self) -> usize {
+    fn state_board_shape(&self) -> [usize; 2] {
         todo!()
     }
 
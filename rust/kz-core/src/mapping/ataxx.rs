@@ -91,6 +91,10 @@ impl AtaxxStdMapper {
 }
 
 impl InputMapper<AtaxxBoard> for AtaxxStdMapper {
+    fn input_mapper_id(&self) -> String {
+        "ataxx-std-v1".to_owned()
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         [3, self.size as usize, self.size as usize]
     }
@@ -103,6 +107,18 @@ impl InputMapper<AtaxxBoard> for AtaxxStdMapper {
         }
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        vec!["tiles_us".to_owned(), "tiles_them".to_owned(), "gaps".to_owned()]
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        if self.include_move_counter {
+            vec!["moves_since_last_copy".to_owned()]
+        } else {
+            vec![]
+        }
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &AtaxxBoard) {
         if self.include_move_counter {
             scalars.push(board.moves_since_last_copy() as f32 / MAX_MOVES_SINCE_LAST_COPY as f32);
@@ -116,6 +132,10 @@ impl InputMapper<AtaxxBoard> for AtaxxStdMapper {
 }
 
 impl PolicyMapper<AtaxxBoard> for AtaxxStdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "ataxx-std-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         &self.policy_shape
     }
@@ -151,8 +171,8 @@ pub const FROM_DX_DY: [(i8, i8); 16] = [
 ];
 
 impl MuZeroMapper<AtaxxBoard> for AtaxxStdMapper {
-    fn state_board_size(&self) -> usize {
-        self.size as usize
+    fn state_board_shape(&self) -> [usize; 2] {
+        [self.size as usize, self.size as usize]
     }
 
     fn encoded_move_shape(&self) -> [usize; 3] {
@@ -23,6 +23,13 @@ pub struct ChessStdMapper;
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct ChessLegacyConvPolicyMapper;
 
+/// Attention-style policy layout, with shape `[64, 64 + 3 * 8]`.
+/// The first axis is the POV from square, the second axis is either the POV to square or, for underpromotions,
+/// `64 + piece * 8 + to_file` with the pieces in the order rook, bishop, knight.
+/// Queen promotions share the index of the corresponding queen move.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ChessAttPolicyMapper;
+
 impl ChessHistoryMapper {
     pub fn new(length: usize) -> Self {
         Self { length }
@@ -30,6 +37,10 @@ impl ChessHistoryMapper {
 }
 
 impl InputMapper<ChessBoard> for ChessHistoryMapper {
+    fn input_mapper_id(&self) -> String {
+        "chess-hist-v1".to_owned()
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         let c = 1 + (self.length + 1) * (2 * 6);
         [c, 8, 8]
@@ -39,6 +50,25 @@ impl InputMapper<ChessBoard> for ChessHistoryMapper {
         7 + (self.length + 1)
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        let mut names = vec!["en_passant".to_owned()];
+        for i in 0..=self.length {
+            names.extend(piece_plane_names(&history_suffix(i)));
+        }
+        names
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = ["white_turn", "black_turn"]
+            .into_iter()
+            .chain(CASTLING_NAMES)
+            .chain(["non_pawn_or_capture_moves"])
+            .map(str::to_owned)
+            .collect();
+        names.extend((0..=self.length).map(|i| format!("repetitions{}", history_suffix(i))));
+        names
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &ChessBoard) {
         let inner = board.inner();
         let pov = inner.side_to_move();
@@ -95,6 +125,10 @@ fn append_board(bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &ChessBoar
 }
 
 impl PolicyMapper<ChessBoard> for ChessHistoryMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "chess-std-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         ChessStdMapper.policy_shape()
     }
@@ -109,8 +143,8 @@ impl PolicyMapper<ChessBoard> for ChessHistoryMapper {
 }
 
 impl MuZeroMapper<ChessBoard> for ChessHistoryMapper {
-    fn state_board_size(&self) -> usize {
-        ChessStdMapper.state_board_size()
+    fn state_board_shape(&self) -> [usize; 2] {
+        ChessStdMapper.state_board_shape()
     }
 
     fn encoded_move_shape(&self) -> [usize; 3] {
@@ -123,6 +157,10 @@ impl MuZeroMapper<ChessBoard> for ChessHistoryMapper {
 }
 
 impl InputMapper<ChessBoard> for ChessStdMapper {
+    fn input_mapper_id(&self) -> String {
+        "chess-std-v1".to_owned()
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         // pieces, en passant
         [(2 * 6) + 1, 8, 8]
@@ -133,6 +171,21 @@ impl InputMapper<ChessBoard> for ChessStdMapper {
         2 + (1 + 1) + (2 * 2)
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        let mut names = piece_plane_names("");
+        names.push("en_passant".to_owned());
+        names
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        ["white_turn", "black_turn"]
+            .into_iter()
+            .chain(CASTLING_NAMES)
+            .chain(["repetitions", "non_pawn_or_capture_moves"])
+            .map(str::to_owned)
+            .collect()
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &ChessBoard) {
         let inner = board.inner();
         let pov_color = inner.side_to_move();
@@ -177,6 +230,26 @@ fn pov_ranks(board: BitBoard, pov: Color) -> u64 {
     }
 }
 
+const CASTLING_NAMES: [&str; 4] = ["us_kingside", "us_queenside", "them_kingside", "them_queenside"];
+
+/// Names of the piece planes in the order [append_board] and [ChessStdMapper] write them, eg. `us_pawn{suffix}`.
+fn piece_plane_names(suffix: &str) -> Vec<String> {
+    let mut names = vec![];
+    for side in ["us", "them"] {
+        for piece in chess::ALL_PIECES {
+            names.push(format!("{}_{:?}{}", side, piece, suffix).to_lowercase());
+        }
+    }
+    names
+}
+
+fn history_suffix(i: usize) -> String {
+    match i {
+        0 => String::new(),
+        _ => format!("_prev_{}", i),
+    }
+}
+
 struct FlatMoveInfo {
     index_to_mv: Vec<ChessMove>,
     mv_to_index: HashMap<ChessMove, usize>,
@@ -195,6 +268,10 @@ lazy_static! {
 }
 
 impl PolicyMapper<ChessBoard> for ChessStdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "chess-std-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         &[FLAT_MOVE_COUNT]
     }
@@ -217,6 +294,10 @@ impl PolicyMapper<ChessBoard> for ChessStdMapper {
 }
 
 impl PolicyMapper<ChessBoard> for ChessLegacyConvPolicyMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "chess-legacy-conv-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         &[CONV_POLICY_CHANNELS, 8, 8]
     }
@@ -253,6 +334,111 @@ impl PolicyMapper<ChessBoard> for ChessLegacyConvPolicyMapper {
     }
 }
 
+pub const ATT_FROM_COUNT: usize = 64;
+pub const ATT_TO_COUNT: usize = 64 + UNDERPROMOTION_PIECES.len() * 8;
+
+impl ChessAttPolicyMapper {
+    /// The index of a move that is already from the POV of the player making it.
+    pub fn pov_move_to_index(mv: ChessMove) -> usize {
+        let from = mv.get_source().to_index();
+
+        let underpromotion = mv
+            .get_promotion()
+            .and_then(|piece| UNDERPROMOTION_PIECES.iter().index_of(&piece));
+        let to = match underpromotion {
+            None => mv.get_dest().to_index(),
+            Some(piece) => 64 + piece * 8 + mv.get_dest().get_file().to_index(),
+        };
+
+        from * ATT_TO_COUNT + to
+    }
+
+    /// The inverse of [Self::pov_move_to_index]. `moving_pawn` decides whether a move to the last rank is a queen promotion.
+    /// Returns `None` for indices that don't correspond to any possible move.
+    pub fn index_to_pov_move(index: usize, moving_pawn: bool) -> Option<ChessMove> {
+        let from = square_from_index(index / ATT_TO_COUNT);
+        let to = index % ATT_TO_COUNT;
+
+        let from_rank = from.get_rank().to_index() as isize;
+        let from_file = from.get_file().to_index() as isize;
+
+        if to < 64 {
+            let to = square_from_index(to);
+            let rank_delta = to.get_rank().to_index() as isize - from_rank;
+            let file_delta = to.get_file().to_index() as isize - from_file;
+
+            let is_queen =
+                (rank_delta == 0) ^ (file_delta == 0) || (rank_delta != 0 && rank_delta.abs() == file_delta.abs());
+            let is_knight = KNIGHT_DELTAS.contains(&(rank_delta, file_delta));
+            if !is_queen && !is_knight {
+                return None;
+            }
+
+            let promotion = if moving_pawn && to.get_rank() == Rank::Eighth {
+                Some(Piece::Queen)
+            } else {
+                None
+            };
+            Some(ChessMove::new(from, to, promotion))
+        } else {
+            let left = to - 64;
+            let piece = UNDERPROMOTION_PIECES[left / 8];
+            let to_file = (left % 8) as isize;
+
+            if from.get_rank() != Rank::Seventh || (to_file - from_file).abs() > 1 {
+                return None;
+            }
+
+            let to = square(Rank::Eighth.to_index() as isize, to_file)?;
+            Some(ChessMove::new(from, to, Some(piece)))
+        }
+    }
+}
+
+impl PolicyMapper<ChessBoard> for ChessAttPolicyMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "chess-att-v1"
+    }
+
+    fn policy_shape(&self) -> &[usize] {
+        &[ATT_FROM_COUNT, ATT_TO_COUNT]
+    }
+
+    fn move_to_index(&self, board: &ChessBoard, mv: ChessMove) -> usize {
+        let mv_pov = move_pov(board.inner().side_to_move(), mv);
+        let index = Self::pov_move_to_index(mv_pov);
+        assert!(index < self.policy_len());
+        index
+    }
+
+    fn index_to_move(&self, board: &ChessBoard, index: usize) -> Option<ChessMove> {
+        let pov = board.inner().side_to_move();
+        let from_abs = square_pov(pov, square_from_index(index / ATT_TO_COUNT));
+        let moving_pawn = board.inner().piece_on(from_abs) == Some(Piece::Pawn);
+
+        let mv_pov = Self::index_to_pov_move(index, moving_pawn)?;
+        Some(move_pov(pov, mv_pov))
+    }
+}
+
+impl MuZeroMapper<ChessBoard> for ChessAttPolicyMapper {
+    fn state_board_shape(&self) -> [usize; 2] {
+        ChessStdMapper.state_board_shape()
+    }
+
+    fn encoded_move_shape(&self) -> [usize; 3] {
+        ChessStdMapper.encoded_move_shape()
+    }
+
+    fn encode_mv(&self, result: &mut Vec<f32>, mv_index: usize) {
+        // we don't know the moving piece here, so queen promotions are encoded as the corresponding queen move
+        let mv_pov = Self::index_to_pov_move(mv_index, false)
+            .unwrap_or_else(|| panic!("Invalid attention move index {}", mv_index));
+        let flat_index = FLAT_MOVES_POV.mv_to_index[&mv_pov];
+        ChessStdMapper.encode_mv(result, flat_index)
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum ClassifiedPovMove {
     Queen { direction: usize, distance_m1: usize },
@@ -481,8 +667,8 @@ pub fn generate_all_flat_moves_pov() -> Vec<ChessMove> {
 }
 
 impl MuZeroMapper<ChessBoard> for ChessStdMapper {
-    fn state_board_size(&self) -> usize {
-        8
+    fn state_board_shape(&self) -> [usize; 2] {
+        [8, 8]
     }
 
     fn encoded_move_shape(&self) -> [usize; 3] {
@@ -0,0 +1,72 @@
+use board_game::board::Board;
+
+use crate::games::connect4::{Connect4Board, Connect4Move, HEIGHT, WIDTH};
+use crate::mapping::bit_buffer::BitBuffer;
+use crate::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
+
+/// Encodes the pieces from the POV of the next player, with row 0 at the bottom.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Connect4StdMapper;
+
+fn all_tiles() -> impl Iterator<Item = (u8, u8)> {
+    (0..HEIGHT).flat_map(|row| (0..WIDTH).map(move |column| (column, row)))
+}
+
+impl InputMapper<Connect4Board> for Connect4StdMapper {
+    fn input_mapper_id(&self) -> String {
+        "connect4-std-v1".to_owned()
+    }
+
+    fn input_bool_shape(&self) -> [usize; 3] {
+        [2, HEIGHT as usize, WIDTH as usize]
+    }
+
+    fn input_scalar_count(&self) -> usize {
+        0
+    }
+
+    fn input_bool_names(&self) -> Vec<String> {
+        vec!["pieces_us".to_owned(), "pieces_them".to_owned()]
+    }
+
+    fn encode_input(&self, bools: &mut BitBuffer, _: &mut Vec<f32>, board: &Connect4Board) {
+        for player in [board.next_player(), board.next_player().other()] {
+            bools.extend(all_tiles().map(|(column, row)| board.tile(column, row) == Some(player)));
+        }
+    }
+}
+
+impl PolicyMapper<Connect4Board> for Connect4StdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "connect4-std-v1"
+    }
+
+    fn policy_shape(&self) -> &[usize] {
+        &[WIDTH as usize]
+    }
+
+    fn move_to_index(&self, _: &Connect4Board, mv: Connect4Move) -> usize {
+        mv.column() as usize
+    }
+
+    fn index_to_move(&self, _: &Connect4Board, index: usize) -> Option<Connect4Move> {
+        assert!(index < WIDTH as usize);
+        Some(Connect4Move::new(index as u8))
+    }
+}
+
+impl MuZeroMapper<Connect4Board> for Connect4StdMapper {
+    fn state_board_shape(&self) -> [usize; 2] {
+        [HEIGHT as usize, WIDTH as usize]
+    }
+
+    fn encoded_move_shape(&self) -> [usize; 3] {
+        [1, HEIGHT as usize, WIDTH as usize]
+    }
+
+    fn encode_mv(&self, result: &mut Vec<f32>, mv_index: usize) {
+        // mark the entire column
+        assert!(mv_index < WIDTH as usize);
+        result.extend(all_tiles().map(|(column, _)| (column as usize == mv_index) as u8 as f32))
+    }
+}
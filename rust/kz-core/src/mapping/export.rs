@@ -0,0 +1,121 @@
+use board_game::board::{Board, BoardSymmetry};
+use board_game::symmetry::Symmetry;
+use serde::Serialize;
+
+use crate::mapping::BoardMapper;
+
+/// A description of a mapper that can be written as json,
+/// so other tools (mostly the python training code) don't have to duplicate the mapping logic.
+#[derive(Debug, Clone, Serialize)]
+pub struct MappingExport {
+    pub game: String,
+    pub input_mapper: String,
+    pub policy_mapper: &'static str,
+
+    pub input_bool_shape: [usize; 3],
+    pub input_bool_names: Vec<String>,
+    pub input_scalar_names: Vec<String>,
+
+    pub policy_shape: Vec<usize>,
+    pub aux_outputs: Vec<AuxOutputExport>,
+
+    /// The board-dependent parts of the policy mapping, for each of the example boards.
+    /// These differ between boards for mappers that encode moves from the POV of the next player
+    /// or that support multiple board sizes.
+    pub boards: Vec<BoardExport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardExport {
+    pub name: String,
+
+    /// The move for each flat policy index, `None` for indices that don't correspond to any move.
+    pub index_to_move: Vec<Option<String>>,
+
+    /// For each board symmetry the flat policy index each index is mapped to, `-1` for indices without a move.
+    /// Empty if the board doesn't have any symmetries besides the identity.
+    pub symmetries: Vec<SymmetryExport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuxOutputExport {
+    pub name: &'static str,
+    pub shape: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SymmetryExport {
+    pub name: String,
+    pub map_mv: Vec<isize>,
+}
+
+/// Describe `mapper`, `boards` are named examples used for the board-dependent parts of the policy mapping.
+pub fn export_mapping<B: Board, M: BoardMapper<B>>(game: &str, mapper: M, boards: &[(String, B)]) -> MappingExport {
+    let boards = boards
+        .iter()
+        .map(|(name, board)| export_board(mapper, name, board))
+        .collect();
+
+    let aux_outputs = mapper
+        .aux_outputs()
+        .into_iter()
+        .map(|spec| AuxOutputExport {
+            name: spec.name,
+            shape: spec.shape,
+        })
+        .collect();
+
+    MappingExport {
+        game: game.to_owned(),
+        input_mapper: mapper.input_mapper_id(),
+        policy_mapper: mapper.policy_mapper_id(),
+        input_bool_shape: mapper.input_bool_shape(),
+        input_bool_names: mapper.input_bool_names(),
+        input_scalar_names: mapper.input_scalar_names(),
+        policy_shape: mapper.policy_shape().to_vec(),
+        aux_outputs,
+        boards,
+    }
+}
+
+fn export_board<B: Board, M: BoardMapper<B>>(mapper: M, name: &str, board: &B) -> BoardExport {
+    let index_to_move = (0..mapper.policy_len())
+        .map(|index| mapper.index_to_move(board, index).map(|mv| mv.to_string()))
+        .collect();
+
+    let all_syms = <B as BoardSymmetry<B>>::Symmetry::all();
+    let symmetries = if all_syms.len() > 1 {
+        all_syms
+            .iter()
+            .map(|&sym| export_symmetry(mapper, board, sym))
+            .collect()
+    } else {
+        vec![]
+    };
+
+    BoardExport {
+        name: name.to_owned(),
+        index_to_move,
+        symmetries,
+    }
+}
+
+fn export_symmetry<B: Board, M: BoardMapper<B>>(
+    mapper: M,
+    board: &B,
+    sym: <B as BoardSymmetry<B>>::Symmetry,
+) -> SymmetryExport {
+    let mapped_board = board.map(sym);
+
+    let map_mv = (0..mapper.policy_len())
+        .map(|index| match mapper.index_to_move(board, index) {
+            None => -1,
+            Some(mv) => mapper.move_to_index(&mapped_board, board.map_move(sym, mv)) as isize,
+        })
+        .collect();
+
+    SymmetryExport {
+        name: format!("{:?}", sym),
+        map_mv,
+    }
+}
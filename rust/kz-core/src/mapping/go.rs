@@ -2,7 +2,7 @@ use board_game::board::{Board, Player};
 use board_game::games::go::{FlatTile, GoBoard, Move, State, Tile};
 
 use crate::mapping::bit_buffer::BitBuffer;
-use crate::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
+use crate::mapping::{AuxOutputSpec, InputMapper, MuZeroMapper, PolicyMapper, SCORE_LEAD_OUTPUT};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct GoStdMapper {
@@ -43,6 +43,14 @@ impl GoStdMapper {
 }
 
 impl InputMapper<GoBoard> for GoStdMapper {
+    fn input_mapper_id(&self) -> String {
+        // the ownership channels change the input shape, so they need a different id
+        match self.territory {
+            false => "go-std-v1".to_owned(),
+            true => "go-std-territory-v1".to_owned(),
+        }
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         // stones_us, stones_them, in-board, illegal_move (ko)
         let channels_basic = 2 + 1 + 1;
@@ -61,6 +69,27 @@ impl InputMapper<GoBoard> for GoStdMapper {
         6
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        let mut names = vec!["stones_us", "stones_them", "in_board", "illegal_move"];
+        if self.territory {
+            names.extend(["own_us", "own_neither", "own_them"]);
+        }
+        names.into_iter().map(str::to_owned).collect()
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        [
+            "black_turn",
+            "white_turn",
+            "pass_count_1",
+            "pass_count_2",
+            "komi",
+            "multi_suicide",
+        ]
+        .map(str::to_owned)
+        .to_vec()
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &GoBoard) {
         let size = board.size();
         assert!(size <= self.max_size);
@@ -111,9 +140,17 @@ impl InputMapper<GoBoard> for GoStdMapper {
         scalars.push(komi_pov.as_float() / 15.0);
         scalars.push(board.rules().allow_multi_stone_suicide as u8 as f32);
     }
+
+    fn board_size(&self, board: &GoBoard) -> Option<usize> {
+        Some(board.size() as usize)
+    }
 }
 
 impl PolicyMapper<GoBoard> for GoStdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "go-std-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         &self.policy_shape
     }
@@ -122,15 +159,35 @@ impl PolicyMapper<GoBoard> for GoStdMapper {
         self.move_to_index(mv)
     }
 
-    fn index_to_move(&self, _: &GoBoard, index: usize) -> Option<Move> {
-        Some(self.index_to_move(index))
+    fn index_to_move(&self, board: &GoBoard, index: usize) -> Option<Move> {
+        // boards smaller than the max size don't have moves for the padding tiles
+        match self.index_to_move(index) {
+            Move::Place(tile) if !tile.exists(board.size()) => None,
+            mv => Some(mv),
+        }
+    }
+
+    fn aux_outputs(&self) -> Vec<AuxOutputSpec> {
+        vec![AuxOutputSpec::new(SCORE_LEAD_OUTPUT, vec![])]
+    }
+
+    fn final_score(&self, board: &GoBoard) -> Option<f32> {
+        // komi is added to the score of player B (white)
+        let score = board.current_score();
+        let score_a = score.a as f32 - score.b as f32 - board.komi().as_float();
+
+        let score_pov = match board.next_player() {
+            Player::A => score_a,
+            Player::B => -score_a,
+        };
+        Some(score_pov)
     }
 }
 
 // TODO use better pass encoding that doesn't take up an entire plane
 impl MuZeroMapper<GoBoard> for GoStdMapper {
-    fn state_board_size(&self) -> usize {
-        self.max_size as usize
+    fn state_board_shape(&self) -> [usize; 2] {
+        [self.max_size as usize, self.max_size as usize]
     }
 
     fn encoded_move_shape(&self) -> [usize; 3] {
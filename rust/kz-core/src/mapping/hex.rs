@@ -0,0 +1,132 @@
+use board_game::board::{Board, Player};
+
+use crate::games::hex::{HexBoard, HexMove};
+use crate::mapping::bit_buffer::BitBuffer;
+use crate::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
+
+/// Maps the rhombus directly onto a square grid, the hexagonal neighbors are then the 4 orthogonal tiles
+/// plus the tiles at `(x+1, y-1)` and `(x-1, y+1)`.
+///
+/// Everything is encoded from the POV of the next player: if player B is to move the board is transposed,
+/// so the next player always connects the top and bottom edges.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct HexStdMapper {
+    size: u8,
+    policy_shape: [usize; 1],
+}
+
+impl HexStdMapper {
+    pub fn new(size: u8) -> Self {
+        HexStdMapper {
+            size,
+            policy_shape: [1 + size as usize * size as usize],
+        }
+    }
+
+    fn area(&self) -> usize {
+        self.size as usize * self.size as usize
+    }
+
+    /// Map between board and POV coordinates, this is its own inverse.
+    fn pov(&self, board: &HexBoard, x: u8, y: u8) -> (u8, u8) {
+        match board.next_player() {
+            Player::A => (x, y),
+            Player::B => (y, x),
+        }
+    }
+}
+
+impl InputMapper<HexBoard> for HexStdMapper {
+    fn input_mapper_id(&self) -> String {
+        "hex-std-v1".to_owned()
+    }
+
+    fn input_bool_shape(&self) -> [usize; 3] {
+        // stones_us, stones_them
+        [2, self.size as usize, self.size as usize]
+    }
+
+    fn input_scalar_count(&self) -> usize {
+        // can_swap
+        1
+    }
+
+    fn input_bool_names(&self) -> Vec<String> {
+        vec!["stones_us".to_owned(), "stones_them".to_owned()]
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        vec!["can_swap".to_owned()]
+    }
+
+    fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &HexBoard) {
+        assert_eq!(board.size(), self.size);
+        let next = board.next_player();
+
+        for color in [next, next.other()] {
+            for y in 0..self.size {
+                for x in 0..self.size {
+                    let (bx, by) = self.pov(board, x, y);
+                    bools.push(board.tile(bx, by) == Some(color));
+                }
+            }
+        }
+
+        scalars.push(board.can_swap() as u8 as f32);
+    }
+}
+
+impl PolicyMapper<HexBoard> for HexStdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "hex-std-v1"
+    }
+
+    fn policy_shape(&self) -> &[usize] {
+        &self.policy_shape
+    }
+
+    fn move_to_index(&self, board: &HexBoard, mv: HexMove) -> usize {
+        match mv {
+            HexMove::Swap => 0,
+            HexMove::Place(x, y) => {
+                let (px, py) = self.pov(board, x, y);
+                1 + py as usize * self.size as usize + px as usize
+            }
+        }
+    }
+
+    fn index_to_move(&self, board: &HexBoard, index: usize) -> Option<HexMove> {
+        match index {
+            0 => Some(HexMove::Swap),
+            _ => {
+                let tile_index = index - 1;
+                assert!(tile_index < self.area());
+
+                let size = self.size as usize;
+                let (px, py) = ((tile_index % size) as u8, (tile_index / size) as u8);
+                let (x, y) = self.pov(board, px, py);
+                Some(HexMove::Place(x, y))
+            }
+        }
+    }
+}
+
+impl MuZeroMapper<HexBoard> for HexStdMapper {
+    fn state_board_shape(&self) -> [usize; 2] {
+        [self.size as usize, self.size as usize]
+    }
+
+    fn encoded_move_shape(&self) -> [usize; 3] {
+        [2, self.size as usize, self.size as usize]
+    }
+
+    fn encode_mv(&self, result: &mut Vec<f32>, mv_index: usize) {
+        assert!(mv_index <= self.area());
+        for _ in 0..self.area() {
+            result.push((mv_index == 0) as u8 as f32);
+        }
+        for i in 0..self.area() {
+            result.push((mv_index == 1 + i) as u8 as f32);
+        }
+    }
+}
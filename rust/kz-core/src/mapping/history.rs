@@ -0,0 +1,289 @@
+use std::collections::VecDeque;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+
+use board_game::board::{AltBoard, Board, BoardDone, BoardMoves, BoardSymmetry, Outcome, PlayError, Player};
+
+use crate::mapping::bit_buffer::BitBuffer;
+use crate::mapping::{AuxOutputSpec, InputMapper, MuZeroMapper, PolicyMapper};
+
+/// A board that remembers up to `length` previous positions, for use with [HistoryMapper].
+///
+/// The history is part of the board identity, so two boards with the same current position but a different history
+/// are not equal and get evaluated separately by the network and cache.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct HistoryBoard<B: Board> {
+    board: B,
+    /// The previous positions, most recent first.
+    history: VecDeque<B>,
+    length: usize,
+}
+
+impl<B: Board> HistoryBoard<B> {
+    /// Start a new game at `board` without any history.
+    pub fn new(board: B, length: usize) -> Self {
+        HistoryBoard {
+            board,
+            history: VecDeque::with_capacity(length),
+            length,
+        }
+    }
+
+    /// The current position.
+    pub fn current(&self) -> &B {
+        &self.board
+    }
+
+    /// The previous positions, most recent first. Contains at most `length` boards.
+    pub fn history(&self) -> impl Iterator<Item = &B> + '_ {
+        self.history.iter()
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+}
+
+impl<B: Board> Board for HistoryBoard<B> {
+    type Move = B::Move;
+
+    fn next_player(&self) -> Player {
+        self.board.next_player()
+    }
+
+    fn is_available_move(&self, mv: Self::Move) -> Result<bool, BoardDone> {
+        self.board.is_available_move(mv)
+    }
+
+    fn play(&mut self, mv: Self::Move) -> Result<(), PlayError> {
+        let prev = self.board.clone();
+        self.board.play(mv)?;
+
+        if self.length > 0 {
+            if self.history.len() == self.length {
+                self.history.pop_back();
+            }
+            self.history.push_front(prev);
+        }
+
+        Ok(())
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.board.outcome()
+    }
+
+    fn can_lose_after_move() -> bool {
+        B::can_lose_after_move()
+    }
+}
+
+impl<B: AltBoard> AltBoard for HistoryBoard<B> {}
+
+impl<'a, B: Board> BoardMoves<'a, HistoryBoard<B>> for HistoryBoard<B> {
+    type AllMovesIterator = <B as BoardMoves<'a, B>>::AllMovesIterator;
+    type AvailableMovesIterator = <B as BoardMoves<'a, B>>::AvailableMovesIterator;
+
+    fn all_possible_moves() -> Self::AllMovesIterator {
+        B::all_possible_moves()
+    }
+
+    fn available_moves(&'a self) -> Result<Self::AvailableMovesIterator, BoardDone> {
+        self.board.available_moves()
+    }
+}
+
+impl<B: Board> BoardSymmetry<HistoryBoard<B>> for HistoryBoard<B> {
+    type Symmetry = B::Symmetry;
+    type CanonicalKey = B::CanonicalKey;
+
+    fn map(&self, sym: Self::Symmetry) -> Self {
+        HistoryBoard {
+            board: self.board.map(sym),
+            history: self.history.iter().map(|b| b.map(sym)).collect(),
+            length: self.length,
+        }
+    }
+
+    fn map_move(&self, sym: Self::Symmetry, mv: B::Move) -> B::Move {
+        self.board.map_move(sym, mv)
+    }
+
+    fn canonical_key(&self) -> Self::CanonicalKey {
+        self.board.canonical_key()
+    }
+}
+
+impl<B: Board> Display for HistoryBoard<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.board)
+    }
+}
+
+/// Input mapper that stacks the inputs of the inner mapper for the current position and the last `length` positions
+/// of a [HistoryBoard]. Missing history at the start of the game is zero-padded,
+/// and a final scalar per history entry marks whether it's present.
+///
+/// Each position is encoded as-is by the inner mapper, so from the POV of the player to move in that position.
+///
+/// The policy and muzero mappings are forwarded to the inner mapper for the current position.
+pub struct HistoryMapper<B: Board, M> {
+    inner: M,
+    length: usize,
+    ph: PhantomData<B>,
+}
+
+impl<B: Board, M> HistoryMapper<B, M> {
+    pub fn new(inner: M, length: usize) -> Self {
+        HistoryMapper {
+            inner,
+            length,
+            ph: PhantomData,
+        }
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// The inner names for the current board, followed by the same names suffixed with `_prev_i` for each history board.
+    fn history_names(&self, inner: Vec<String>) -> Vec<String> {
+        let mut names = inner.clone();
+        for i in 1..=self.length {
+            names.extend(inner.iter().map(|name| format!("{}_prev_{}", name, i)));
+        }
+        names
+    }
+}
+
+impl<B: Board, M: Debug> Debug for HistoryMapper<B, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistoryMapper")
+            .field("inner", &self.inner)
+            .field("length", &self.length)
+            .finish()
+    }
+}
+
+impl<B: Board, M: Copy> Copy for HistoryMapper<B, M> {}
+
+impl<B: Board, M: Copy> Clone for HistoryMapper<B, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B: Board, M: PartialEq> PartialEq for HistoryMapper<B, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner && self.length == other.length
+    }
+}
+
+impl<B: Board, M: Eq> Eq for HistoryMapper<B, M> {}
+
+impl<B: Board, M: InputMapper<B>> InputMapper<HistoryBoard<B>> for HistoryMapper<B, M> {
+    fn input_mapper_id(&self) -> String {
+        // networks for different inner mappers or lengths are not interchangeable
+        format!("hist-v1-{}-{}", self.inner.input_mapper_id(), self.length)
+    }
+
+    fn input_bool_shape(&self) -> [usize; 3] {
+        let [c, w, h] = self.inner.input_bool_shape();
+        [(self.length + 1) * c, w, h]
+    }
+
+    fn input_scalar_count(&self) -> usize {
+        // inner scalars for each board, history present flags
+        (self.length + 1) * self.inner.input_scalar_count() + self.length
+    }
+
+    fn input_bool_names(&self) -> Vec<String> {
+        self.history_names(self.inner.input_bool_names())
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        let mut names = self.history_names(self.inner.input_scalar_names());
+        names.extend((1..=self.length).map(|i| format!("prev_{}_present", i)));
+        names
+    }
+
+    fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &HistoryBoard<B>) {
+        assert!(
+            board.length() >= self.length,
+            "Board only keeps {} history positions, mapper needs {}",
+            board.length(),
+            self.length
+        );
+
+        // the inner mapper writes the scalars of all boards, we need to write the present flags after them
+        let mut present = Vec::with_capacity(self.length);
+
+        self.inner.encode_input(bools, scalars, board.current());
+
+        for i in 0..self.length {
+            match board.history.get(i) {
+                Some(prev) => {
+                    self.inner.encode_input(bools, scalars, prev);
+                    present.push(1.0);
+                }
+                None => {
+                    for _ in 0..self.inner.input_bool_len() {
+                        bools.push(false);
+                    }
+                    scalars.extend(std::iter::repeat(0.0).take(self.inner.input_scalar_count()));
+                    present.push(0.0);
+                }
+            }
+        }
+
+        scalars.extend_from_slice(&present);
+    }
+
+    fn board_size(&self, board: &HistoryBoard<B>) -> Option<usize> {
+        self.inner.board_size(board.current())
+    }
+}
+
+impl<B: Board, M: PolicyMapper<B>> PolicyMapper<HistoryBoard<B>> for HistoryMapper<B, M> {
+    fn policy_mapper_id(&self) -> &'static str {
+        self.inner.policy_mapper_id()
+    }
+
+    fn policy_shape(&self) -> &[usize] {
+        self.inner.policy_shape()
+    }
+
+    fn move_to_index(&self, board: &HistoryBoard<B>, mv: B::Move) -> usize {
+        self.inner.move_to_index(board.current(), mv)
+    }
+
+    fn index_to_move(&self, board: &HistoryBoard<B>, index: usize) -> Option<B::Move> {
+        self.inner.index_to_move(board.current(), index)
+    }
+
+    fn aux_outputs(&self) -> Vec<AuxOutputSpec> {
+        self.inner.aux_outputs()
+    }
+
+    fn final_score(&self, board: &HistoryBoard<B>) -> Option<f32> {
+        self.inner.final_score(board.current())
+    }
+}
+
+impl<B: Board, M: MuZeroMapper<B>> MuZeroMapper<HistoryBoard<B>> for HistoryMapper<B, M> {
+    fn state_board_shape(&self) -> [usize; 2] {
+        self.inner.state_board_shape()
+    }
+
+    fn encoded_move_shape(&self) -> [usize; 3] {
+        self.inner.encoded_move_shape()
+    }
+
+    fn encode_mv(&self, result: &mut Vec<f32>, mv_index: usize) {
+        self.inner.encode_mv(result, mv_index)
+    }
+}
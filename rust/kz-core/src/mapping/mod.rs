@@ -9,7 +9,12 @@ pub mod arimaa;
 pub mod ataxx;
 pub mod bit_buffer;
 pub mod chess;
+pub mod connect4;
+pub mod export;
 pub mod go;
+pub mod hex;
+pub mod history;
+pub mod othello;
 pub mod sttt;
 pub mod trictrac;
 pub mod ttt;
@@ -18,6 +23,10 @@ pub mod ttt;
 /// TODO switch to encoding/decoding from Vec<DTensors> directly
 ///    then we lose bitpacking booleans, is that a big deal?
 pub trait InputMapper<B: Board>: Debug + Copy + Send + Sync + Eq + PartialEq {
+    /// A unique id for this input encoding, stored in the network metadata.
+    /// Should be changed whenever the encoding changes in an incompatible way.
+    fn input_mapper_id(&self) -> String;
+
     fn input_bool_shape(&self) -> [usize; 3];
     fn input_scalar_count(&self) -> usize;
 
@@ -30,6 +39,19 @@ pub trait InputMapper<B: Board>: Debug + Copy + Send + Sync + Eq + PartialEq {
     fn input_bool_len(&self) -> usize {
         self.input_bool_shape().iter().product()
     }
+
+    /// A name for each of the `input_bool_shape()[0]` bool channels, only used to describe the mapping.
+    fn input_bool_names(&self) -> Vec<String> {
+        (0..self.input_bool_shape()[0]).map(|i| format!("bool_{}", i)).collect()
+    }
+
+    /// A name for each of the `input_scalar_count()` scalars, only used to describe the mapping.
+    fn input_scalar_names(&self) -> Vec<String> {
+        (0..self.input_scalar_count())
+            .map(|i| format!("scalar_{}", i))
+            .collect()
+    }
+
     fn input_full_len(&self) -> usize {
         self.input_full_shape().iter().product()
     }
@@ -38,6 +60,12 @@ pub trait InputMapper<B: Board>: Debug + Copy + Send + Sync + Eq + PartialEq {
     /// Should append `BOOL_COUNT` booleans to `bool_result` and `FLOAT_COUNT` floats to `float_result`..
     fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &B);
 
+    /// The actual size of `board`, for mappers that pad boards of different sizes to the same input shape.
+    fn board_size(&self, board: &B) -> Option<usize> {
+        let _ = board;
+        None
+    }
+
     fn encode_input_full(&self, result: &mut Vec<f32>, board: &B) {
         let bool_count = self.input_bool_len();
         let [_, w, h] = self.input_bool_shape();
@@ -66,6 +94,10 @@ pub trait InputMapper<B: Board>: Debug + Copy + Send + Sync + Eq + PartialEq {
 
 /// A way to encode and decode moves on a board into a tensor.
 pub trait PolicyMapper<B: Board>: Debug + Copy + Send + Sync + Eq + PartialEq {
+    /// A unique id for this policy layout, stored in the network metadata.
+    /// Should be changed whenever the layout changes in an incompatible way.
+    fn policy_mapper_id(&self) -> &'static str;
+
     fn policy_shape(&self) -> &[usize];
 
     fn policy_len(&self) -> usize {
@@ -79,11 +111,54 @@ pub trait PolicyMapper<B: Board>: Debug + Copy + Send + Sync + Eq + PartialEq {
     /// Get the move corresponding to the given index in the policy tensor.
     /// A return of `None` means that this index does not correspond to any move (on this board or otherwise).
     fn index_to_move(&self, board: &B, index: usize) -> Option<B::Move>;
+
+    /// The auxiliary heads a network for this mapper can have, in the order they follow the standard outputs.
+    /// Networks without any auxiliary outputs are always accepted.
+    fn aux_outputs(&self) -> Vec<AuxOutputSpec> {
+        vec![]
+    }
+
+    /// The final score difference of a finished game from the POV of the next player,
+    /// used as the training target for the [SCORE_LEAD_OUTPUT] head. `None` for games without a score.
+    fn final_score(&self, board: &B) -> Option<f32> {
+        let _ = board;
+        None
+    }
+}
+
+/// The name of the optional score lead output, with shape `[]`.
+/// The value is the expected final score difference in points from the POV of the next player,
+/// it's used by the search if [UctWeights::score_weight](crate::zero::node::UctWeights::score_weight) is nonzero.
+pub const SCORE_LEAD_OUTPUT: &str = "score_lead";
+
+/// An additional named graph output, for example ownership or score lead.
+/// These are decoded and passed along in [ZeroEvaluation::aux](crate::network::ZeroEvaluation::aux),
+/// only [SCORE_LEAD_OUTPUT] is used by the search.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AuxOutputSpec {
+    pub name: &'static str,
+    /// The shape of the output for a single board, excluding the batch dimension.
+    pub shape: Vec<usize>,
+}
+
+impl AuxOutputSpec {
+    pub fn new(name: &'static str, shape: Vec<usize>) -> Self {
+        AuxOutputSpec { name, shape }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 //TODO update the docs in the file
 pub trait MuZeroMapper<B: Board>: Debug + Copy + Send + Sync + Eq + PartialEq {
-    fn state_board_size(&self) -> usize;
+    /// The spatial `[height, width]` shape of the muzero state, the same as the spatial shape of the input.
+    fn state_board_shape(&self) -> [usize; 2];
 
     fn encoded_move_shape(&self) -> [usize; 3];
 
@@ -126,6 +201,10 @@ impl<B: Board, I: InputMapper<B>, P: PolicyMapper<B>> Clone for ComposedMapper<B
 }
 
 impl<B: Board, I: InputMapper<B>, P: PolicyMapper<B>> InputMapper<B> for ComposedMapper<B, I, P> {
+    fn input_mapper_id(&self) -> String {
+        self.input_mapper.input_mapper_id()
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         self.input_mapper.input_bool_shape()
     }
@@ -134,12 +213,28 @@ impl<B: Board, I: InputMapper<B>, P: PolicyMapper<B>> InputMapper<B> for Compose
         self.input_mapper.input_scalar_count()
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        self.input_mapper.input_bool_names()
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        self.input_mapper.input_scalar_names()
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &B) {
         self.input_mapper.encode_input(bools, scalars, board)
     }
+
+    fn board_size(&self, board: &B) -> Option<usize> {
+        self.input_mapper.board_size(board)
+    }
 }
 
 impl<B: Board, I: InputMapper<B>, P: PolicyMapper<B>> PolicyMapper<B> for ComposedMapper<B, I, P> {
+    fn policy_mapper_id(&self) -> &'static str {
+        self.policy_mapper.policy_mapper_id()
+    }
+
     fn policy_shape(&self) -> &[usize] {
         self.policy_mapper.policy_shape()
     }
@@ -151,4 +246,27 @@ impl<B: Board, I: InputMapper<B>, P: PolicyMapper<B>> PolicyMapper<B> for Compos
     fn index_to_move(&self, board: &B, index: usize) -> Option<B::Move> {
         self.policy_mapper.index_to_move(board, index)
     }
+
+    fn aux_outputs(&self) -> Vec<AuxOutputSpec> {
+        self.policy_mapper.aux_outputs()
+    }
+
+    fn final_score(&self, board: &B) -> Option<f32> {
+        self.policy_mapper.final_score(board)
+    }
+}
+
+/// Moves are encoded in the policy layout, so the muzero move encoding belongs to the policy mapper.
+impl<B: Board, I: InputMapper<B>, P: PolicyMapper<B> + MuZeroMapper<B>> MuZeroMapper<B> for ComposedMapper<B, I, P> {
+    fn state_board_shape(&self) -> [usize; 2] {
+        self.policy_mapper.state_board_shape()
+    }
+
+    fn encoded_move_shape(&self) -> [usize; 3] {
+        self.policy_mapper.encoded_move_shape()
+    }
+
+    fn encode_mv(&self, result: &mut Vec<f32>, mv_index: usize) {
+        self.policy_mapper.encode_mv(result, mv_index)
+    }
 }
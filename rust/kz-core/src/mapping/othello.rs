@@ -0,0 +1,85 @@
+use board_game::board::Board;
+
+use crate::games::othello::{OthelloBoard, OthelloMove, AREA, SIZE};
+use crate::mapping::bit_buffer::BitBuffer;
+use crate::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
+
+/// Encodes the pieces from the POV of the next player, followed by the tiles that player can place a piece on.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct OthelloStdMapper;
+
+fn push_tiles(bools: &mut BitBuffer, tiles: u64) {
+    bools.extend((0..AREA).map(|i| tiles & (1 << i) != 0));
+}
+
+impl InputMapper<OthelloBoard> for OthelloStdMapper {
+    fn input_mapper_id(&self) -> String {
+        "othello-std-v1".to_owned()
+    }
+
+    fn input_bool_shape(&self) -> [usize; 3] {
+        // stones_us, stones_them, placeable
+        [3, SIZE as usize, SIZE as usize]
+    }
+
+    fn input_scalar_count(&self) -> usize {
+        0
+    }
+
+    fn input_bool_names(&self) -> Vec<String> {
+        vec!["stones_us".to_owned(), "stones_them".to_owned(), "placeable".to_owned()]
+    }
+
+    fn encode_input(&self, bools: &mut BitBuffer, _: &mut Vec<f32>, board: &OthelloBoard) {
+        push_tiles(bools, board.tiles(board.next_player()));
+        push_tiles(bools, board.tiles(board.next_player().other()));
+        push_tiles(bools, board.placeable_tiles());
+    }
+}
+
+impl PolicyMapper<OthelloBoard> for OthelloStdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "othello-std-v1"
+    }
+
+    fn policy_shape(&self) -> &[usize] {
+        &[1 + AREA as usize]
+    }
+
+    fn move_to_index(&self, _: &OthelloBoard, mv: OthelloMove) -> usize {
+        match mv {
+            OthelloMove::Pass => 0,
+            OthelloMove::Place(index) => 1 + index as usize,
+        }
+    }
+
+    fn index_to_move(&self, _: &OthelloBoard, index: usize) -> Option<OthelloMove> {
+        match index {
+            0 => Some(OthelloMove::Pass),
+            _ => {
+                assert!(index <= AREA as usize);
+                Some(OthelloMove::Place((index - 1) as u8))
+            }
+        }
+    }
+}
+
+impl MuZeroMapper<OthelloBoard> for OthelloStdMapper {
+    fn state_board_shape(&self) -> [usize; 2] {
+        [SIZE as usize, SIZE as usize]
+    }
+
+    fn encoded_move_shape(&self) -> [usize; 3] {
+        [2, SIZE as usize, SIZE as usize]
+    }
+
+    fn encode_mv(&self, result: &mut Vec<f32>, mv_index: usize) {
+        assert!(mv_index <= AREA as usize);
+        for _ in 0..AREA {
+            result.push((mv_index == 0) as u8 as f32);
+        }
+        for i in 0..AREA as usize {
+            result.push((mv_index == 1 + i) as u8 as f32);
+        }
+    }
+}
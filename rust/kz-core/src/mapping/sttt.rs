@@ -8,6 +8,10 @@ use crate::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
 pub struct STTTStdMapper;
 
 impl InputMapper<STTTBoard> for STTTStdMapper {
+    fn input_mapper_id(&self) -> String {
+        "sttt-std-v1".to_owned()
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         [3, 9, 9]
     }
@@ -16,6 +20,10 @@ impl InputMapper<STTTBoard> for STTTStdMapper {
         0
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        vec!["tiles_us".to_owned(), "tiles_them".to_owned(), "available".to_owned()]
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, _: &mut Vec<f32>, board: &STTTBoard) {
         bools.extend(Coord::all().map(|c| board.tile(c) == Some(board.next_player())));
         bools.extend(Coord::all().map(|c| board.tile(c) == Some(board.next_player().other())));
@@ -25,6 +33,10 @@ impl InputMapper<STTTBoard> for STTTStdMapper {
 }
 
 impl PolicyMapper<STTTBoard> for STTTStdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "sttt-std-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         &[1, 9, 9]
     }
@@ -40,7 +52,7 @@ impl PolicyMapper<STTTBoard> for STTTStdMapper {
 }
 
 impl MuZeroMapper<STTTBoard> for STTTStdMapper {
-    fn state_board_size(&self) -> usize {
+    fn state_board_shape(&self) -> [usize; 2] {
         todo!()
     }
 
@@ -1,4 +1,5 @@
 use board_game::board::{Board, Player};
+use internal_iterator::InternalIterator;
 use trictrac_bot::training_common::{TrictracAction, ACTION_SPACE_SIZE};
 use trictrac_bot::trictrac_board::TrictracBoard;
 use trictrac_store::Color;
@@ -10,7 +11,45 @@ use crate::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct TrictracStdMapper;
 
+/// Richer input encoding than [TrictracStdMapper], with the same policy layout.
+/// * fields are seen from the POV of the player to move
+/// * checker counts are encoded as per-field thermometer planes
+/// * the fields checkers can move from and to in the available moves
+/// * normalized scalars
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TrictracV2Mapper;
+
+const FIELD_COUNT: usize = 24;
+const CHECKER_COUNT: usize = 15;
+const TURN_STAGE_COUNT: usize = 6;
+/// The number of holes needed to win the game.
+const HOLES_TO_WIN: f32 = 12.0;
+/// The number of points needed to win a hole.
+const POINTS_PER_HOLE: f32 = 12.0;
+/// Rough scale of the dice roll count, only used for normalization.
+const DICE_ROLL_COUNT_SCALE: f32 = 10.0;
+
+fn player_stat_names() -> Vec<String> {
+    let mut names = vec![];
+    for side in ["us", "them"] {
+        for stat in [
+            "points",
+            "holes",
+            "can_bredouille",
+            "can_big_bredouille",
+            "dice_roll_count",
+        ] {
+            names.push(format!("{}_{}", side, stat));
+        }
+    }
+    names
+}
+
 impl InputMapper<TrictracBoard> for TrictracStdMapper {
+    fn input_mapper_id(&self) -> String {
+        "trictrac-std-v1".to_owned()
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         // 15 checkers for each player
         // 24 places
@@ -25,6 +64,22 @@ impl InputMapper<TrictracBoard> for TrictracStdMapper {
         1 + 2 + 2 + 2 * 5
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        let mut names = vec![];
+        for side in ["us", "them"] {
+            names.extend((0..CHECKER_COUNT).map(|n| format!("{}_checkers_{}", side, n)));
+        }
+        names
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        let mut names = ["turn_stage", "white_turn", "black_turn", "die_1", "die_2"]
+            .map(str::to_owned)
+            .to_vec();
+        names.extend(player_stat_names());
+        names
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &TrictracBoard) {
         let game_state = board.inner();
         let pov_player = game_state.who_plays().unwrap();
@@ -70,6 +125,10 @@ impl InputMapper<TrictracBoard> for TrictracStdMapper {
 }
 
 impl PolicyMapper<TrictracBoard> for TrictracStdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "trictrac-std-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         &[ACTION_SPACE_SIZE]
     }
@@ -84,15 +143,261 @@ impl PolicyMapper<TrictracBoard> for TrictracStdMapper {
 }
 
 impl MuZeroMapper<TrictracBoard> for TrictracStdMapper {
-    fn state_board_size(&self) -> usize {
-        panic!("not implemented");
+    fn state_board_shape(&self) -> [usize; 2] {
+        [FIELD_COUNT, 1]
     }
 
     fn encoded_move_shape(&self) -> [usize; 3] {
-        panic!("not implemented");
+        // planes: roll, go, dice order, first checker, second checker
+        [5, FIELD_COUNT, 1]
     }
 
     fn encode_mv(&self, result: &mut Vec<f32>, move_index: usize) {
-        panic!("not implemented");
+        // mirrors `encode_trictrac_mv` in `python/lib/games.py`
+        let start = result.len();
+        let action = TrictracAction::from_action_index(move_index)
+            .unwrap_or_else(|| panic!("Invalid trictrac action index {}", move_index));
+
+        let mut push_plane = |value: bool| result.extend(std::iter::repeat(value as u8 as f32).take(FIELD_COUNT));
+        push_plane(matches!(action, TrictracAction::Roll));
+        push_plane(matches!(action, TrictracAction::Go));
+
+        match action {
+            TrictracAction::Move {
+                dice_order,
+                checker1,
+                checker2,
+            } => {
+                push_plane(dice_order);
+
+                // checkers are identified by their index (with 0 meaning no checker), one-hot encode those
+                for checker in [checker1, checker2] {
+                    assert!(checker <= CHECKER_COUNT);
+                    let plane_start = result.len();
+                    result.extend(std::iter::repeat(0.0).take(FIELD_COUNT));
+                    result[plane_start + checker] = 1.0;
+                }
+            }
+            _ => result.extend(std::iter::repeat(0.0).take(3 * FIELD_COUNT)),
+        }
+
+        assert_eq!(result.len() - start, self.encoded_mv_len());
+    }
+}
+
+impl TrictracStdMapper {
+    /// The shape of [Self::encode_mv_on_board]: the planes of [MuZeroMapper::encode_mv],
+    /// followed by the checkers leaving and arriving at each field.
+    pub fn encoded_move_on_board_shape(&self) -> [usize; 3] {
+        let [planes, height, width] = self.encoded_move_shape();
+        [planes + 2, height, width]
+    }
+
+    /// Extension of [MuZeroMapper::encode_mv] that also encodes the fields the checkers of the move
+    /// `move_index` played on `board` leave from and arrive at. The checker ordinals alone don't identify those fields.
+    pub fn encode_mv_on_board(&self, result: &mut Vec<f32>, board: &TrictracBoard, move_index: usize) {
+        let start = result.len();
+        self.encode_mv(result, move_index);
+
+        match TrictracAction::from_action_index(move_index).unwrap() {
+            action @ TrictracAction::Move { .. } => {
+                let (from, to) = checker_moves(board, action);
+                result.extend_from_slice(&from);
+                result.extend_from_slice(&to);
+            }
+            _ => result.extend(std::iter::repeat(0.0).take(2 * FIELD_COUNT)),
+        }
+
+        assert_eq!(
+            result.len() - start,
+            self.encoded_move_on_board_shape().iter().product::<usize>()
+        );
+    }
+}
+
+/// The number of checkers of the player to move that leave and arrive at each field when `action` is played,
+/// indexed from the POV of that player. Checkers that are borne off only leave their field.
+///
+/// The action only identifies checkers by their ordinal, so we play it and compare the fields instead.
+fn checker_moves(board: &TrictracBoard, action: TrictracAction) -> ([f32; FIELD_COUNT], [f32; FIELD_COUNT]) {
+    let pov = board.inner().who_plays().unwrap().color.clone();
+    let before = own_checker_counts(board, &pov);
+    let after = own_checker_counts(&board.clone_and_play(action).unwrap(), &pov);
+
+    let mut from = [0.0; FIELD_COUNT];
+    let mut to = [0.0; FIELD_COUNT];
+    for i in 0..FIELD_COUNT {
+        let delta = after[i] as f32 - before[i] as f32;
+        if delta < 0.0 {
+            from[i] = -delta;
+        } else {
+            to[i] = delta;
+        }
+    }
+    (from, to)
+}
+
+/// The number of checkers of `pov` on each field, indexed from the POV of `pov`.
+fn own_checker_counts(board: &TrictracBoard, pov: &Color) -> [usize; FIELD_COUNT] {
+    let mut counts = [0; FIELD_COUNT];
+    for (i, count) in counts.iter_mut().enumerate() {
+        let (n, color) = board.inner().board.get_field_checkers(field_pov(pov, i)).unwrap();
+        if color == Some(pov) {
+            *count = n as usize;
+        }
+    }
+    counts
+}
+
+impl InputMapper<TrictracBoard> for TrictracV2Mapper {
+    fn input_mapper_id(&self) -> String {
+        "trictrac-v2".to_owned()
+    }
+
+    fn input_bool_shape(&self) -> [usize; 3] {
+        // for each player: at least n checkers for each n, fields a checker can move from and to
+        [2 * CHECKER_COUNT + 2, FIELD_COUNT, 1]
+    }
+
+    fn input_scalar_count(&self) -> usize {
+        // turn stage one-hot
+        // white_turn, black_turn
+        // 2 dice, is double
+        // For each player: points, holes, can_bredouille, can_big_bredouille, dice_roll_count
+        TURN_STAGE_COUNT + 2 + 3 + 2 * 5
+    }
+
+    fn input_bool_names(&self) -> Vec<String> {
+        let mut names = vec![];
+        for side in ["us", "them"] {
+            names.extend((1..=CHECKER_COUNT).map(|n| format!("{}_at_least_{}", side, n)));
+        }
+        names.push("movable_from".to_owned());
+        names.push("movable_to".to_owned());
+        names
+    }
+
+    fn input_scalar_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = (0..TURN_STAGE_COUNT).map(|i| format!("turn_stage_{}", i)).collect();
+        names.extend(["white_turn", "black_turn", "die_1", "die_2", "is_double"].map(str::to_owned));
+        names.extend(player_stat_names());
+        names
+    }
+
+    fn encode_input(&self, bools: &mut BitBuffer, scalars: &mut Vec<f32>, board: &TrictracBoard) {
+        let game_state = board.inner();
+        let pov_player = game_state.who_plays().unwrap();
+        let opp_player = game_state
+            .players
+            .values()
+            .find(|p| p.color != pov_player.color)
+            .unwrap();
+
+        // checker count and color for each field, indexed from the POV of the player to move
+        let fields: Vec<(usize, Option<Color>)> = (0..FIELD_COUNT)
+            .map(|i| {
+                let field = field_pov(&pov_player.color, i);
+                let (count, color) = game_state.board.get_field_checkers(field).unwrap();
+                (count as usize, color.cloned())
+            })
+            .collect();
+
+        // checker counts
+        for player in [pov_player, opp_player] {
+            for n in 1..=CHECKER_COUNT {
+                for (count, color) in &fields {
+                    bools.push(color.as_ref() == Some(&player.color) && *count >= n);
+                }
+            }
+        }
+
+        // fields checkers can move from and to in any of the available moves
+        let mut movable_from = [false; FIELD_COUNT];
+        let mut movable_to = [false; FIELD_COUNT];
+        if let Ok(moves) = board.available_moves() {
+            moves.for_each(|mv| {
+                if let TrictracAction::Move { .. } = mv {
+                    let (from, to) = checker_moves(board, mv);
+                    for i in 0..FIELD_COUNT {
+                        movable_from[i] |= from[i] != 0.0;
+                        movable_to[i] |= to[i] != 0.0;
+                    }
+                }
+            });
+        }
+        for movable in [movable_from, movable_to] {
+            for x in movable {
+                bools.push(x);
+            }
+        }
+
+        let (die_1, die_2) = game_state.dice.values;
+
+        // turn stage
+        let turn_stage = game_state.turn_stage as usize;
+        assert!(turn_stage < TURN_STAGE_COUNT, "Unexpected turn stage {}", turn_stage);
+        for stage in 0..TURN_STAGE_COUNT {
+            scalars.push((stage == turn_stage) as u8 as f32);
+        }
+
+        // active player
+        for color in [Color::White, Color::Black] {
+            scalars.push((pov_player.color == color) as u8 as f32);
+        }
+
+        // dice
+        scalars.push(die_1 as f32 / 6.0);
+        scalars.push(die_2 as f32 / 6.0);
+        scalars.push((die_1 == die_2) as u8 as f32);
+
+        // player stats
+        for player in [pov_player, opp_player] {
+            scalars.push(player.points as f32 / POINTS_PER_HOLE);
+            scalars.push(player.holes as f32 / HOLES_TO_WIN);
+            scalars.push(player.can_bredouille as u8 as f32);
+            scalars.push(player.can_big_bredouille as u8 as f32);
+            scalars.push(player.dice_roll_count as f32 / DICE_ROLL_COUNT_SCALE);
+        }
+    }
+}
+
+/// The absolute board field (`1..=24`) for the field at POV index `i` (`0..24`).
+/// White moves from field 1 towards field 24, so the board is mirrored for black.
+fn field_pov(pov: &Color, i: usize) -> usize {
+    match pov {
+        Color::White => 1 + i,
+        Color::Black => FIELD_COUNT - i,
+    }
+}
+
+impl PolicyMapper<TrictracBoard> for TrictracV2Mapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        TrictracStdMapper.policy_mapper_id()
+    }
+
+    fn policy_shape(&self) -> &[usize] {
+        TrictracStdMapper.policy_shape()
+    }
+
+    fn move_to_index(&self, board: &TrictracBoard, mv: TrictracAction) -> usize {
+        TrictracStdMapper.move_to_index(board, mv)
+    }
+
+    fn index_to_move(&self, board: &TrictracBoard, index: usize) -> Option<TrictracAction> {
+        TrictracStdMapper.index_to_move(board, index)
+    }
+}
+
+impl MuZeroMapper<TrictracBoard> for TrictracV2Mapper {
+    fn state_board_shape(&self) -> [usize; 2] {
+        TrictracStdMapper.state_board_shape()
+    }
+
+    fn encoded_move_shape(&self) -> [usize; 3] {
+        TrictracStdMapper.encoded_move_shape()
+    }
+
+    fn encode_mv(&self, result: &mut Vec<f32>, mv_index: usize) {
+        TrictracStdMapper.encode_mv(result, mv_index)
     }
 }
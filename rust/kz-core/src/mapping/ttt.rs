@@ -9,6 +9,10 @@ use crate::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
 pub struct TTTStdMapper;
 
 impl InputMapper<TTTBoard> for TTTStdMapper {
+    fn input_mapper_id(&self) -> String {
+        "ttt-std-v1".to_owned()
+    }
+
     fn input_bool_shape(&self) -> [usize; 3] {
         [2, 3, 3]
     }
@@ -17,6 +21,10 @@ impl InputMapper<TTTBoard> for TTTStdMapper {
         0
     }
 
+    fn input_bool_names(&self) -> Vec<String> {
+        vec!["tiles_us".to_owned(), "tiles_them".to_owned()]
+    }
+
     fn encode_input(&self, bools: &mut BitBuffer, _: &mut Vec<f32>, board: &TTTBoard) {
         bools.extend(Coord3::all().map(|c| board.tile(c) == Some(board.next_player())));
         bools.extend(Coord3::all().map(|c| board.tile(c) == Some(board.next_player().other())));
@@ -24,6 +32,10 @@ impl InputMapper<TTTBoard> for TTTStdMapper {
 }
 
 impl PolicyMapper<TTTBoard> for TTTStdMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "ttt-std-v1"
+    }
+
     fn policy_shape(&self) -> &[usize] {
         &[1, 3, 3]
     }
@@ -39,8 +51,8 @@ impl PolicyMapper<TTTBoard> for TTTStdMapper {
 }
 
 impl MuZeroMapper<TTTBoard> for TTTStdMapper {
-    fn state_board_size(&self) -> usize {
-        3
+    fn state_board_shape(&self) -> [usize; 2] {
+        [3, 3]
     }
 
     fn encoded_move_shape(&self) -> [usize; 3] {
@@ -66,7 +66,12 @@ impl MuNode {
         let u = self.net_policy * ((parent_total_visits - 1) as f32).sqrt() / (1 + total_visits) as f32;
         //TODO make sure to remove this -1 if we ever split ZeroValuesPov.flip() into child() and parent()
         let m = values.moves_left - (fpu.moves_left - 1.0);
+        // unvisited nodes use the fpu values themselves, so their score delta is always zero,
+        //   the same as in the AlphaZero tree. The fpu at the root comes from `from_outcome` which has no score,
+        //   deeper fpus are the parent values and carry its score lead.
+        let s = values.score_lead() - fpu.score_lead();
+        let s = if s.is_nan() { 0.0 } else { s };
 
-        Uct { q: v, u, m }
+        Uct { q: v, u, m, s }
     }
 }
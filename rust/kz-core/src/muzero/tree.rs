@@ -127,6 +127,7 @@ impl<B: AltBoard, M: BoardMapper<B>> MuTree<B, M> {
         ZeroEvaluation {
             values: self.values(),
             policy: self.policy().collect(),
+            aux: None,
         }
     }
     #[must_use]
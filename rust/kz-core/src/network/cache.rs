@@ -0,0 +1,148 @@
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+
+use board_game::board::Board;
+use board_game::symmetry::Symmetry;
+use itertools::{izip, Itertools};
+use lru::LruCache;
+
+use crate::network::symmetry::unmap_eval;
+use crate::network::{Network, ZeroEvaluation};
+
+/// Wrapper around a `Network` that caches evaluations in an LRU cache.
+/// Only the boards that miss the cache are forwarded to the inner network.
+///
+/// If `canonicalize` is enabled boards are first mapped to their canonical symmetry,
+/// so all symmetric versions of a board share a single cache entry.
+pub struct CachedNetwork<B: Board + Hash, N: Network<B>> {
+    inner: N,
+    cache: LruCache<B, ZeroEvaluation<'static>>,
+    canonicalize: bool,
+    stats: CacheStats,
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn total(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    pub fn hit_rate(&self) -> f32 {
+        self.hits as f32 / self.total() as f32
+    }
+}
+
+impl<B: Board + Hash, N: Network<B>> CachedNetwork<B, N> {
+    pub fn new(inner: N, capacity: usize, canonicalize: bool) -> Self {
+        CachedNetwork {
+            inner,
+            cache: LruCache::new(capacity),
+            canonicalize,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn inner(&self) -> &N {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Remove all cached evaluations, for example after the weights of the inner network changed.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Map the board to the key used in the cache, together with the symmetry that was used to get there.
+    fn cache_key(&self, board: &B) -> (B::Symmetry, B) {
+        if !self.canonicalize || B::Symmetry::is_unit() {
+            return (B::Symmetry::default(), board.clone());
+        }
+
+        B::Symmetry::all()
+            .iter()
+            .map(|&sym| (sym, board.map(sym)))
+            .min_by_key(|(_, mapped)| mapped.canonical_key())
+            .unwrap()
+    }
+}
+
+impl<B: Board + Hash, N: Network<B>> Network<B> for CachedNetwork<B, N> {
+    fn max_batch_size(&self) -> usize {
+        self.inner.max_batch_size()
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
+        let keys = boards.iter().map(|b| self.cache_key(b.borrow())).collect_vec();
+
+        // look up all boards, collecting the misses
+        let mut mapped_evals: Vec<Option<ZeroEvaluation<'static>>> = Vec::with_capacity(boards.len());
+        let mut miss_boards = vec![];
+        for (_, key) in &keys {
+            let eval = self.cache.get(key).cloned();
+            if eval.is_none() {
+                miss_boards.push(key.clone());
+            }
+            mapped_evals.push(eval);
+        }
+
+        self.stats.hits += (boards.len() - miss_boards.len()) as u64;
+        self.stats.misses += miss_boards.len() as u64;
+
+        // evaluate the misses and fill them in, keeping the original order
+        if !miss_boards.is_empty() {
+            let miss_evals = self.inner.evaluate_batch(&miss_boards);
+            let mut miss_evals = miss_evals.into_iter();
+
+            for ((_, key), eval) in keys.iter().zip(&mut mapped_evals) {
+                if eval.is_none() {
+                    let miss_eval = miss_evals.next().unwrap();
+                    self.cache.put(key.clone(), miss_eval.clone());
+                    *eval = Some(miss_eval);
+                }
+            }
+
+            assert!(miss_evals.next().is_none());
+        }
+
+        // un-map the evaluations
+        izip!(boards, keys, mapped_evals)
+            .map(|(board, (sym, key), mapped_eval)| {
+                let mapped_eval = mapped_eval.unwrap();
+                if sym == B::Symmetry::default() {
+                    mapped_eval
+                } else {
+                    unmap_eval(board.borrow(), sym, key, mapped_eval)
+                }
+            })
+            .collect()
+    }
+}
+
+impl<B: Board + Hash, N: Network<B>> Debug for CachedNetwork<B, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedNetwork")
+            .field("capacity", &self.cache.cap())
+            .field("len", &self.cache.len())
+            .field("canonicalize", &self.canonicalize)
+            .field("stats", &self.stats)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
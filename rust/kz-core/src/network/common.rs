@@ -1,23 +1,39 @@
 use std::borrow::{Borrow, Cow};
+use std::path::Path;
+use std::sync::Arc;
 
 use board_game::board::Board;
 use board_game::pov::ScalarPov;
 use board_game::wdl::WDL;
 use internal_iterator::InternalIterator;
 use kn_graph::graph::Graph;
+use kn_graph::onnx::load_graph_from_onnx_path;
+use kn_graph::optimizer::optimize_graph;
 use kn_graph::shape;
 use kn_graph::shape::{Shape, Size};
 use ndarray::{s, ArrayView1, ArrayView2};
 
-use crate::mapping::{BoardMapper, PolicyMapper};
-use crate::network::ZeroEvaluation;
+use crate::error::{MappingError, NanOutputError, NetworkError, ShapeError};
+use crate::mapping::{BoardMapper, PolicyMapper, SCORE_LEAD_OUTPUT};
+use crate::network::{AuxOutputs, ZeroEvaluation};
 use crate::zero::values::ZeroValuesPov;
 
+/// Decode the raw network outputs, panicking if they are invalid. See [try_decode_output].
 pub fn decode_output<B: Board, P: PolicyMapper<B>>(
     policy_mapper: P,
     boards: &[impl Borrow<B>],
     outputs: &[&[f32]],
 ) -> Vec<ZeroEvaluation<'static>> {
+    try_decode_output(policy_mapper, boards, outputs).unwrap_or_else(|e| panic!("Failed to decode output: {}", e))
+}
+
+/// Decode the raw network outputs, returning an error if the outputs don't have the expected layout
+/// or contain non-finite values.
+pub fn try_decode_output<B: Board, P: PolicyMapper<B>>(
+    policy_mapper: P,
+    boards: &[impl Borrow<B>],
+    outputs: &[&[f32]],
+) -> Result<Vec<ZeroEvaluation<'static>>, NetworkError> {
     let batch_size = boards.len();
     let policy_len = policy_mapper.policy_len();
 
@@ -26,11 +42,38 @@ pub fn decode_output<B: Board, P: PolicyMapper<B>>(
     const NAN_SLICE: &[f32] = &[f32::NAN];
     let nan_array = ArrayView1::from(NAN_SLICE);
 
+    let shape_err = |output: &'static str, expected: Shape, actual: usize| ShapeError::Output {
+        output,
+        expected,
+        actual: shape![actual],
+    };
+
+    if batch_size == 0 {
+        return Ok(vec![]);
+    }
+
+    // split off the auxiliary outputs
+    let aux_specs = policy_mapper.aux_outputs();
+    let first_is_scalars = outputs.first().map_or(false, |o| o.len() == batch_size * 5);
+    let (standard_count, has_aux) = split_outputs(outputs.len(), first_is_scalars, aux_specs.len())?;
+    let (outputs, aux_outputs) = outputs.split_at(standard_count);
+
+    if has_aux {
+        for (spec, output) in aux_specs.iter().zip(aux_outputs) {
+            if output.len() != batch_size * spec.len() {
+                let expected = shape![batch_size].concat(&Shape::fixed(&spec.shape));
+                return Err(shape_err(spec.name, expected, output.len()).into());
+            }
+        }
+    }
+
     // interpret outputs
     let (batch_value_logit, batch_wdl_logit, batch_moves_left, batch_policy_logit) = match outputs.len() {
         2 => {
-            scalars = ArrayView2::from_shape((batch_size, 5), outputs[0]).unwrap();
-            let policy = ArrayView2::from_shape((batch_size, policy_len), outputs[1]).unwrap();
+            scalars = ArrayView2::from_shape((batch_size, 5), outputs[0])
+                .map_err(|_| shape_err("scalars", shape![batch_size, 5], outputs[0].len()))?;
+            let policy = ArrayView2::from_shape((batch_size, policy_len), outputs[1])
+                .map_err(|_| shape_err("policy", shape![batch_size, policy_len], outputs[1].len()))?;
 
             (
                 scalars.slice(s![.., 0]),
@@ -40,14 +83,17 @@ pub fn decode_output<B: Board, P: PolicyMapper<B>>(
             )
         }
         3 => {
-            let value = ArrayView1::from_shape(batch_size, outputs[0]).unwrap();
-            let wdl = ArrayView2::from_shape((batch_size, 3), outputs[1]).unwrap();
+            let value = ArrayView1::from_shape(batch_size, outputs[0])
+                .map_err(|_| shape_err("value", shape![batch_size], outputs[0].len()))?;
+            let wdl = ArrayView2::from_shape((batch_size, 3), outputs[1])
+                .map_err(|_| shape_err("wdl", shape![batch_size, 3], outputs[1].len()))?;
             let moves_left = nan_array.broadcast(batch_size).unwrap();
-            let policy = ArrayView2::from_shape((batch_size, policy_len), outputs[2]).unwrap();
+            let policy = ArrayView2::from_shape((batch_size, policy_len), outputs[2])
+                .map_err(|_| shape_err("policy", shape![batch_size, policy_len], outputs[2].len()))?;
 
             (value, wdl, moves_left, policy)
         }
-        _ => unreachable!("Output count should have been checked already"),
+        actual => return Err(ShapeError::OutputCount { actual }.into()),
     };
 
     boards
@@ -55,9 +101,17 @@ pub fn decode_output<B: Board, P: PolicyMapper<B>>(
         .enumerate()
         .map(|(bi, board)| {
             let board = board.borrow();
+            let nan_err = |output: &'static str| NanOutputError {
+                batch_index: bi,
+                output,
+            };
 
             // simple scalars
-            let value = batch_value_logit[bi].tanh();
+            let value_logit = batch_value_logit[bi];
+            if !value_logit.is_finite() {
+                return Err(nan_err("value").into());
+            }
+            let value = value_logit.tanh();
             let moves_left = batch_moves_left[bi];
 
             // wdl
@@ -66,6 +120,9 @@ pub fn decode_output<B: Board, P: PolicyMapper<B>>(
                 batch_wdl_logit[(bi, 1)],
                 batch_wdl_logit[(bi, 2)],
             ];
+            if !wdl.iter().all(|x| x.is_finite()) {
+                return Err(nan_err("wdl").into());
+            }
             softmax_in_place(&mut wdl);
             let wdl = WDL {
                 win: wdl[0],
@@ -74,27 +131,69 @@ pub fn decode_output<B: Board, P: PolicyMapper<B>>(
             };
 
             // policy
-            let policy = board.available_moves().map_or(vec![], |moves| {
-                let mut policy: Vec<f32> = moves
-                    .map(|mv| {
+            let policy = match board.available_moves() {
+                Err(_) => vec![],
+                Ok(moves) => {
+                    let mut policy = Vec::new();
+                    let mut error = None;
+
+                    moves.for_each(|mv| {
                         let index = policy_mapper.move_to_index(board, mv);
-                        batch_policy_logit[(bi, index)]
+                        if index >= policy_len {
+                            error.get_or_insert(NetworkError::from(MappingError::PolicyIndexOutOfBounds {
+                                index,
+                                policy_len,
+                            }));
+                            return;
+                        }
+
+                        let logit = batch_policy_logit[(bi, index)];
+                        if !logit.is_finite() {
+                            error.get_or_insert(NetworkError::from(nan_err("policy")));
+                        }
+                        policy.push(logit);
+                    });
+
+                    if let Some(error) = error {
+                        return Err(error);
+                    }
+                    softmax_in_place(&mut policy);
+                    policy
+                }
+            };
+
+            // auxiliary outputs are passed along as-is
+            let aux = has_aux.then(|| {
+                let aux: AuxOutputs = aux_specs
+                    .iter()
+                    .zip(aux_outputs)
+                    .map(|(spec, output)| {
+                        let len = spec.len();
+                        (spec.name, output[bi * len..(bi + 1) * len].to_vec())
                     })
                     .collect();
-                softmax_in_place(&mut policy);
-                policy
+                Arc::new(aux)
             });
 
+            let score_lead = aux
+                .as_ref()
+                .and_then(|aux| aux.get(SCORE_LEAD_OUTPUT))
+                .map(|score| score[0]);
+
             // combine everything
             let values = ZeroValuesPov {
                 value: ScalarPov::new(value),
                 wdl,
                 moves_left,
-            };
-            ZeroEvaluation {
+                score: ScalarPov::new(0.0),
+                score_weight: 0.0,
+            }
+            .with_score_lead(score_lead);
+            Ok(ZeroEvaluation {
                 values,
                 policy: Cow::Owned(policy),
-            }
+                aux,
+            })
         })
         .collect()
 }
@@ -162,39 +261,92 @@ pub fn policy_softmax_temperature_in_place(slice: &mut [f32], temperature: f32)
     }
 }
 
-pub fn check_graph_shapes<B: Board, M: BoardMapper<B>>(mapper: M, graph: &Graph) {
+pub fn check_graph_shapes<B: Board, M: BoardMapper<B>>(mapper: M, graph: &Graph) -> Result<(), ShapeError> {
     // input
     let inputs = graph.inputs();
-    assert_eq!(1, inputs.len(), "Wrong number of inputs");
+    if inputs.len() != 1 {
+        return Err(ShapeError::InputCount { actual: inputs.len() });
+    }
 
     let graph_input_shape = &graph[inputs[0]].shape;
     let mapper_input_shape = shape![Size::BATCH].concat(&Shape::fixed(&mapper.input_full_shape()));
-    assert_eq!(
-        graph_input_shape, &mapper_input_shape,
-        "Input shape mismatch between graph and mapper"
-    );
+    if graph_input_shape != &mapper_input_shape {
+        return Err(ShapeError::Input {
+            expected: mapper_input_shape,
+            actual: graph_input_shape.clone(),
+        });
+    }
 
     // outputs
     let outputs = graph.outputs();
     let expected_policy_shape = shape![Size::BATCH].concat(&Shape::fixed(mapper.policy_shape()));
 
-    match outputs.len() {
+    let aux_specs = mapper.aux_outputs();
+    let first_is_scalars = outputs
+        .first()
+        .map_or(false, |&o| graph[o].shape == shape![Size::BATCH, 5]);
+    let (standard_count, has_aux) = split_outputs(outputs.len(), first_is_scalars, aux_specs.len())?;
+
+    let check_output = |output: &'static str, index: usize, expected: Shape| {
+        let actual = &graph[outputs[index]].shape;
+        if actual != &expected {
+            return Err(ShapeError::Output {
+                output,
+                expected,
+                actual: actual.clone(),
+            });
+        }
+        Ok(())
+    };
+
+    match standard_count {
         2 => {
-            assert_eq!(&graph[outputs[0]].shape, &shape![Size::BATCH, 5], "Wrong scalars shape");
-            assert_eq!(&graph[outputs[1]].shape, &expected_policy_shape, "Wrong policy shape");
+            check_output("scalars", 0, shape![Size::BATCH, 5])?;
+            check_output("policy", 1, expected_policy_shape)?;
         }
         3 => {
-            assert_eq!(&graph[outputs[0]].shape, &shape![Size::BATCH], "Wrong value shape");
-            assert_eq!(&graph[outputs[1]].shape, &shape![Size::BATCH, 3], "Wrong wdl shape");
-            assert_eq!(&graph[outputs[2]].shape, &expected_policy_shape, "Wrong policy shape");
+            check_output("value", 0, shape![Size::BATCH])?;
+            check_output("wdl", 1, shape![Size::BATCH, 3])?;
+            check_output("policy", 2, expected_policy_shape)?;
         }
-        len => {
-            panic!(
-                "Wrong number of outputs, expected either (value, wdl, policy) or (scalars, policy), got {}",
-                len
-            );
+        _ => unreachable!(),
+    }
+
+    if has_aux {
+        for (i, spec) in aux_specs.iter().enumerate() {
+            let expected = shape![Size::BATCH].concat(&Shape::fixed(&spec.shape));
+            check_output(spec.name, standard_count + i, expected)?;
         }
     }
+
+    Ok(())
+}
+
+/// Split the outputs into the standard outputs and the auxiliary outputs, returns the number of standard outputs
+/// and whether the auxiliary outputs are present.
+/// The standard outputs are either `(scalars, policy)` or `(value, wdl, policy)`,
+/// they're distinguished by the shape of the first output.
+/// The auxiliary outputs follow in the order of [PolicyMapper::aux_outputs], either all of them or none.
+fn split_outputs(output_count: usize, first_is_scalars: bool, aux_count: usize) -> Result<(usize, bool), ShapeError> {
+    let standard_count = if first_is_scalars { 2 } else { 3 };
+
+    if output_count == standard_count {
+        Ok((standard_count, false))
+    } else if aux_count > 0 && output_count == standard_count + aux_count {
+        Ok((standard_count, true))
+    } else {
+        Err(ShapeError::OutputCount { actual: output_count })
+    }
+}
+
+/// Load and optimize the graph at the given onnx path.
+pub fn try_load_graph(path: impl AsRef<Path>) -> Result<Graph, NetworkError> {
+    let path = path.as_ref();
+    let graph = load_graph_from_onnx_path(path, false).map_err(|e| NetworkError::GraphLoad {
+        path: path.to_owned(),
+        message: format!("{:?}", e),
+    })?;
+    Ok(optimize_graph(&graph, Default::default()))
 }
 
 pub fn zero_values_from_scalars(scalars: &[f32]) -> ZeroValuesPov {
@@ -211,5 +363,26 @@ pub fn zero_values_from_scalars(scalars: &[f32]) -> ZeroValuesPov {
         value: ScalarPov::new(value),
         wdl: WDL::new(wdl[0], wdl[1], wdl[2]),
         moves_left,
+        score: ScalarPov::new(0.0),
+        score_weight: 0.0,
+    }
+}
+
+/// Check that the values and policy of each evaluation are finite, `moves_left` is allowed to be NaN.
+pub fn check_evaluations_finite(evals: &[ZeroEvaluation]) -> Result<(), NanOutputError> {
+    for (batch_index, eval) in evals.iter().enumerate() {
+        let values = eval.values;
+        let err = |output| Err(NanOutputError { batch_index, output });
+
+        if !values.value.value.is_finite() {
+            return err("value");
+        }
+        if !(values.wdl.win.is_finite() && values.wdl.draw.is_finite() && values.wdl.loss.is_finite()) {
+            return err("wdl");
+        }
+        if !eval.policy.iter().all(|p| p.is_finite()) {
+            return err("policy");
+        }
     }
+    Ok(())
 }
@@ -9,8 +9,10 @@ use kn_graph::dtype::{DTensor, Tensor};
 use kn_graph::graph::Graph;
 use kn_graph::ndarray::IxDyn;
 
+use crate::error::NetworkError;
 use crate::mapping::BoardMapper;
-use crate::network::common::{check_graph_shapes, decode_output};
+use crate::network::common::{check_graph_shapes, try_decode_output};
+use crate::network::metadata::{check_optional_metadata, NetworkMetadata};
 use crate::network::{Network, ZeroEvaluation};
 
 pub struct CPUNetwork<B: Board, M: BoardMapper<B>> {
@@ -20,14 +22,15 @@ pub struct CPUNetwork<B: Board, M: BoardMapper<B>> {
 }
 
 impl<B: Board, M: BoardMapper<B>> CPUNetwork<B, M> {
-    pub fn new(mapper: M, graph: Graph) -> Self {
-        check_graph_shapes(mapper, &graph);
+    pub fn new(mapper: M, graph: Graph, metadata: Option<&NetworkMetadata>) -> Result<Self, NetworkError> {
+        check_optional_metadata(metadata, mapper, &graph)?;
+        check_graph_shapes(mapper, &graph)?;
 
-        CPUNetwork {
+        Ok(CPUNetwork {
             mapper,
             graph,
             ph: Default::default(),
-        }
+        })
     }
 
     pub fn evaluate_batch_exec(&mut self, boards: &[impl Borrow<B>], keep_all: bool) -> ExecutionInfo {
@@ -65,13 +68,18 @@ impl<B: Board, M: BoardMapper<B>> Network<B> for CPUNetwork<B, M> {
     }
 
     fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
+        self.try_evaluate_batch(boards)
+            .unwrap_or_else(|e| panic!("Failed to evaluate batch: {}", e))
+    }
+
+    fn try_evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Result<Vec<ZeroEvaluation<'static>>, NetworkError> {
         let outputs = self.evaluate_batch_exec(boards, false).output_tensors();
         let batch_outputs = outputs
             .iter()
             .map(|t| t.unwrap_f32().unwrap().as_slice().unwrap())
             .collect_vec();
 
-        decode_output(self.mapper, boards, &batch_outputs)
+        try_decode_output(self.mapper, boards, &batch_outputs)
     }
 }
 
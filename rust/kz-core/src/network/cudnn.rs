@@ -11,8 +11,10 @@ use kn_graph::graph::Graph;
 
 use kz_util::sequence::VecExtPad;
 
+use crate::error::NetworkError;
 use crate::mapping::BoardMapper;
-use crate::network::common::{check_graph_shapes, decode_output};
+use crate::network::common::{check_graph_shapes, try_decode_output};
+use crate::network::metadata::{check_optional_metadata, NetworkMetadata};
 use crate::network::{Network, ZeroEvaluation};
 
 pub struct CudaNetwork<B: Board, M: BoardMapper<B>> {
@@ -26,20 +28,27 @@ pub struct CudaNetwork<B: Board, M: BoardMapper<B>> {
 }
 
 impl<B: Board, M: BoardMapper<B>> CudaNetwork<B, M> {
-    pub fn new(mapper: M, graph: &Graph, max_batch_size: usize, device: CudaDevice) -> Self {
-        check_graph_shapes(mapper, graph);
+    pub fn new(
+        mapper: M,
+        graph: &Graph,
+        max_batch_size: usize,
+        device: CudaDevice,
+        metadata: Option<&NetworkMetadata>,
+    ) -> Result<Self, NetworkError> {
+        check_optional_metadata(metadata, mapper, graph)?;
+        check_graph_shapes(mapper, graph)?;
 
         let executor = CudaExecutor::new(device, graph, max_batch_size);
 
         let input = vec![0.0; max_batch_size * mapper.input_full_len()];
 
-        CudaNetwork {
+        Ok(CudaNetwork {
             max_batch_size,
             mapper,
             executor,
             input,
             ph: PhantomData,
-        }
+        })
     }
 
     pub fn executor(&mut self) -> &mut CudaExecutor {
@@ -53,6 +62,11 @@ impl<B: Board, M: BoardMapper<B>> Network<B> for CudaNetwork<B, M> {
     }
 
     fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
+        self.try_evaluate_batch(boards)
+            .unwrap_or_else(|e| panic!("Failed to evaluate batch: {}", e))
+    }
+
+    fn try_evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Result<Vec<ZeroEvaluation<'static>>, NetworkError> {
         let batch_size = boards.len();
         let max_batch_size = self.max_batch_size;
         assert!(batch_size <= max_batch_size);
@@ -83,7 +97,7 @@ impl<B: Board, M: BoardMapper<B>> Network<B> for CudaNetwork<B, M> {
 
         // decode the relevant part of the output
         // the number and shape of outputs has been checked already
-        decode_output(self.mapper, boards, &relevant_outputs)
+        try_decode_output(self.mapper, boards, &relevant_outputs)
     }
 }
 
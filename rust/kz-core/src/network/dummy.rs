@@ -54,6 +54,7 @@ impl<B: Board> Network<B> for DummyNetwork {
                 policy: Cow::Owned(uniform_policy(
                     board.borrow().available_moves().map_or(0, |moves| moves.count()),
                 )),
+                aux: None,
             })
             .collect()
     }
@@ -71,6 +72,7 @@ impl<B: Board, N: Network<B>> Network<B> for DummyValueNetwork<B, N> {
             .map(|orig| ZeroEvaluation {
                 values: uniform_values(),
                 policy: orig.policy,
+                aux: orig.aux,
             })
             .collect()
     }
@@ -88,6 +90,7 @@ impl<B: Board, N: Network<B>> Network<B> for DummyPolicyNetwork<B, N> {
             .map(|orig| ZeroEvaluation {
                 values: orig.values,
                 policy: Cow::Owned(uniform_policy(orig.policy.len())),
+                aux: orig.aux,
             })
             .collect()
     }
@@ -102,6 +105,8 @@ pub fn uniform_values() -> ZeroValuesPov {
             loss: 1.0 / 3.0,
         },
         moves_left: 0.0,
+        score: ScalarPov::new(0.0),
+        score_weight: 0.0,
     }
 }
 
@@ -0,0 +1,180 @@
+use std::borrow::{Borrow, Cow};
+
+use board_game::board::Board;
+use itertools::Itertools;
+
+use kz_util::math::kdl_divergence;
+
+use crate::network::{Network, ZeroEvaluation};
+use crate::zero::values::ZeroValuesPov;
+
+/// Member policies are clamped to at least this before taking logs,
+/// so a member that assigns zero probability to a move doesn't turn the results into infinities.
+const POLICY_EPSILON: f32 = 1e-6;
+
+/// Wrapper that evaluates each batch on multiple networks and combines the results.
+/// Values are always combined with a weighted average, policies according to [EnsemblePolicyMode].
+#[derive(Debug)]
+pub struct EnsembleNetwork<N> {
+    networks: Vec<(f32, N)>,
+    policy_mode: EnsemblePolicyMode,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EnsemblePolicyMode {
+    /// Weighted arithmetic mean of the member policies.
+    Average,
+    /// Weighted geometric mean of the member policies, renormalized.
+    /// Moves only get a high probability if all members agree.
+    ProductOfExperts,
+}
+
+/// How much the members of an ensemble disagree about a single board.
+#[derive(Debug, Copy, Clone)]
+pub struct EnsembleDisagreement {
+    /// Mean KL divergence between the policies of all ordered pairs of members.
+    pub policy_kl_mean: f32,
+    /// Max KL divergence between the policies of any pair of members.
+    pub policy_kl_max: f32,
+    /// Weighted standard deviation of the member values.
+    pub value_std: f32,
+}
+
+impl<N> EnsembleNetwork<N> {
+    /// Build a new ensemble from `(weight, network)` pairs. The weights don't need to be normalized.
+    pub fn new(networks: Vec<(f32, N)>, policy_mode: EnsemblePolicyMode) -> Self {
+        assert!(!networks.is_empty(), "Ensemble needs at least one network");
+        assert!(
+            networks.iter().all(|&(w, _)| w >= 0.0) && networks.iter().any(|&(w, _)| w > 0.0),
+            "Ensemble weights must be non-negative and not all zero"
+        );
+
+        EnsembleNetwork { networks, policy_mode }
+    }
+
+    /// Build a new ensemble where all members have the same weight.
+    pub fn uniform(networks: Vec<N>, policy_mode: EnsemblePolicyMode) -> Self {
+        Self::new(networks.into_iter().map(|n| (1.0, n)).collect(), policy_mode)
+    }
+
+    pub fn networks(&self) -> &[(f32, N)] {
+        &self.networks
+    }
+}
+
+impl<N> EnsembleNetwork<N> {
+    /// Evaluate a batch, also returning the disagreement between the members for each board.
+    pub fn evaluate_batch_disagreement<B: Board>(
+        &mut self,
+        boards: &[impl Borrow<B>],
+    ) -> (Vec<ZeroEvaluation<'static>>, Vec<EnsembleDisagreement>)
+    where
+        N: Network<B>,
+    {
+        let member_evals = self
+            .networks
+            .iter_mut()
+            .map(|(_, network)| network.evaluate_batch(boards))
+            .collect_vec();
+        let weights = self.networks.iter().map(|&(w, _)| w).collect_vec();
+
+        (0..boards.len())
+            .map(|bi| {
+                let evals = member_evals.iter().map(|evals| &evals[bi]).collect_vec();
+                let eval = combine_evals(&weights, &evals, self.policy_mode);
+                let disagreement = disagreement(&weights, &evals, eval.values.value.value);
+                (eval, disagreement)
+            })
+            .unzip()
+    }
+}
+
+impl<B: Board, N: Network<B>> Network<B> for EnsembleNetwork<N> {
+    fn max_batch_size(&self) -> usize {
+        self.networks.iter().map(|(_, n)| n.max_batch_size()).min().unwrap()
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
+        self.evaluate_batch_disagreement(boards).0
+    }
+}
+
+fn combine_evals(weights: &[f32], evals: &[&ZeroEvaluation], mode: EnsemblePolicyMode) -> ZeroEvaluation<'static> {
+    let total_weight = weights.iter().sum::<f32>();
+    let policy_len = evals[0].policy.len();
+
+    let values = evals
+        .iter()
+        .zip(weights)
+        .map(|(eval, &w)| eval.values * w)
+        .fold(ZeroValuesPov::default(), |a, b| a + b)
+        / total_weight;
+
+    let policy = match mode {
+        EnsemblePolicyMode::Average => (0..policy_len)
+            .map(|i| evals.iter().zip(weights).map(|(e, &w)| w * e.policy[i]).sum::<f32>() / total_weight)
+            .collect_vec(),
+        EnsemblePolicyMode::ProductOfExperts => {
+            let mut policy = (0..policy_len)
+                .map(|i| {
+                    let log_p = evals
+                        .iter()
+                        .zip(weights)
+                        .filter(|&(_, &w)| w > 0.0)
+                        .map(|(e, &w)| w * e.policy[i].max(POLICY_EPSILON).ln())
+                        .sum::<f32>();
+                    (log_p / total_weight).exp()
+                })
+                .collect_vec();
+
+            let sum = policy.iter().sum::<f32>();
+            policy.iter_mut().for_each(|p| *p /= sum);
+            policy
+        }
+    };
+
+    ZeroEvaluation {
+        values,
+        policy: Cow::Owned(policy),
+        aux: None,
+    }
+}
+
+fn disagreement(weights: &[f32], evals: &[&ZeroEvaluation], mean_value: f32) -> EnsembleDisagreement {
+    let total_weight = weights.iter().sum::<f32>();
+
+    let value_var = evals
+        .iter()
+        .zip(weights)
+        .map(|(e, &w)| w * (e.values.value.value - mean_value).powi(2))
+        .sum::<f32>()
+        / total_weight;
+
+    let mut kl_sum = 0.0;
+    let mut kl_max = 0.0;
+    let mut pairs = 0;
+
+    if !evals[0].policy.is_empty() {
+        let policies = evals
+            .iter()
+            .map(|e| e.policy.iter().map(|&p| p.max(POLICY_EPSILON)).collect_vec())
+            .collect_vec();
+
+        for (i, a) in policies.iter().enumerate() {
+            for (j, b) in policies.iter().enumerate() {
+                if i != j {
+                    let kl = kdl_divergence(a, b);
+                    kl_sum += kl;
+                    kl_max = f32::max(kl_max, kl);
+                    pairs += 1;
+                }
+            }
+        }
+    }
+
+    EnsembleDisagreement {
+        policy_kl_mean: if pairs == 0 { 0.0 } else { kl_sum / pairs as f32 },
+        policy_kl_max: kl_max,
+        value_std: value_var.sqrt(),
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use board_game::board::Board;
+use itertools::Itertools;
+use kn_graph::graph::Graph;
+use serde::{Deserialize, Serialize};
+
+use kz_util::game::Game;
+
+use crate::mapping::BoardMapper;
+
+/// Information about how a network was trained, stored in a sidecar json file next to the onnx file.
+/// For `network.onnx` the metadata is stored in `network.json`, see `python/lib/save_onnx.py`.
+/// MuZero networks store it next to the prediction graph as `*_prediction.json`.
+///
+/// Shapes alone don't identify a network: different mappers can have identical input and policy shapes.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkMetadata {
+    /// The game string, as accepted by `Game::parse`.
+    pub game: String,
+    /// The id of the input mapper, see [InputMapper::input_mapper_id](crate::mapping::InputMapper::input_mapper_id).
+    pub input_mapper: String,
+    /// The id of the policy mapper, see [PolicyMapper::policy_mapper_id](crate::mapping::PolicyMapper::policy_mapper_id).
+    pub policy_mapper: String,
+    pub output_layout: OutputLayout,
+    /// The names of the auxiliary outputs following the standard outputs,
+    /// see [PolicyMapper::aux_outputs](crate::mapping::PolicyMapper::aux_outputs).
+    #[serde(default)]
+    pub aux_outputs: Vec<String>,
+}
+
+/// The outputs of the graph, see `decode_output`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum OutputLayout {
+    /// `(scalars, policy)`
+    #[serde(rename = "scalars_policy")]
+    ScalarsPolicy,
+    /// `(value, wdl, policy)`
+    #[serde(rename = "value_wdl_policy")]
+    ValueWdlPolicy,
+}
+
+#[derive(Debug)]
+pub enum MetadataError {
+    Io(PathBuf, std::io::Error),
+    Json(PathBuf, serde_json::Error),
+    GameMismatch {
+        expected: String,
+        actual: String,
+    },
+    InputMapperMismatch {
+        expected: String,
+        actual: String,
+    },
+    PolicyMapperMismatch {
+        expected: String,
+        actual: String,
+    },
+    OutputLayoutMismatch {
+        expected: OutputLayout,
+        aux_count: usize,
+        output_count: usize,
+    },
+    AuxOutputMismatch {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+}
+
+impl OutputLayout {
+    pub fn output_count(self) -> usize {
+        match self {
+            OutputLayout::ScalarsPolicy => 2,
+            OutputLayout::ValueWdlPolicy => 3,
+        }
+    }
+}
+
+impl NetworkMetadata {
+    pub fn new<B: Board>(game: &str, mapper: impl BoardMapper<B>, output_layout: OutputLayout) -> Self {
+        NetworkMetadata {
+            game: game.to_owned(),
+            input_mapper: mapper.input_mapper_id(),
+            policy_mapper: mapper.policy_mapper_id().to_owned(),
+            output_layout,
+            aux_outputs: vec![],
+        }
+    }
+
+    /// The path of the metadata file belonging to the given onnx file.
+    pub fn sidecar_path(onnx_path: impl AsRef<Path>) -> PathBuf {
+        onnx_path.as_ref().with_extension("json")
+    }
+
+    /// Load the metadata belonging to the given onnx file.
+    /// Returns `None` if there is no metadata file, which is the case for older networks.
+    pub fn load_for_onnx(onnx_path: impl AsRef<Path>) -> Result<Option<Self>, MetadataError> {
+        let path = Self::sidecar_path(onnx_path);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(&path).map_err(|e| MetadataError::Io(path.clone(), e))?;
+        let metadata = serde_json::from_reader(BufReader::new(file)).map_err(|e| MetadataError::Json(path, e))?;
+        Ok(Some(metadata))
+    }
+
+    pub fn check_game(&self, game: &str) -> Result<(), MetadataError> {
+        // compare normalized strings so aliases like "ataxx" and "ataxx-7" are considered equal
+        let normalize = |s: &str| Game::parse(s).map_or(s.to_owned(), |g| g.to_string());
+
+        if normalize(&self.game) != normalize(game) {
+            return Err(MetadataError::GameMismatch {
+                expected: game.to_owned(),
+                actual: self.game.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that the network was trained with `mapper` and that the graph outputs match the layout.
+    pub fn check<B: Board>(&self, mapper: impl BoardMapper<B>, graph: &Graph) -> Result<(), MetadataError> {
+        if self.input_mapper != mapper.input_mapper_id() {
+            return Err(MetadataError::InputMapperMismatch {
+                expected: mapper.input_mapper_id(),
+                actual: self.input_mapper.clone(),
+            });
+        }
+        if self.policy_mapper != mapper.policy_mapper_id() {
+            return Err(MetadataError::PolicyMapperMismatch {
+                expected: mapper.policy_mapper_id().to_owned(),
+                actual: self.policy_mapper.clone(),
+            });
+        }
+
+        if !self.aux_outputs.is_empty() {
+            let expected = mapper
+                .aux_outputs()
+                .iter()
+                .map(|spec| spec.name.to_owned())
+                .collect_vec();
+            if self.aux_outputs != expected {
+                return Err(MetadataError::AuxOutputMismatch {
+                    expected,
+                    actual: self.aux_outputs.clone(),
+                });
+            }
+        }
+
+        let output_count = graph.outputs().len();
+        let aux_count = self.aux_outputs.len();
+        if self.output_layout.output_count() + aux_count != output_count {
+            return Err(MetadataError::OutputLayoutMismatch {
+                expected: self.output_layout,
+                aux_count,
+                output_count,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Check the metadata, if any. Used by the network constructors.
+pub fn check_optional_metadata<B: Board>(
+    metadata: Option<&NetworkMetadata>,
+    mapper: impl BoardMapper<B>,
+    graph: &Graph,
+) -> Result<(), MetadataError> {
+    match metadata {
+        None => Ok(()),
+        Some(metadata) => metadata.check(mapper, graph),
+    }
+}
+
+impl Display for MetadataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataError::Io(path, e) => write!(f, "Failed to read network metadata {:?}: {}", path, e),
+            MetadataError::Json(path, e) => write!(f, "Failed to parse network metadata {:?}: {}", path, e),
+            MetadataError::GameMismatch { expected, actual } => {
+                write!(f, "Network was trained for game '{}', expected '{}'", actual, expected)
+            }
+            MetadataError::InputMapperMismatch { expected, actual } => {
+                write!(f, "Network uses input mapper '{}', expected '{}'", actual, expected)
+            }
+            MetadataError::PolicyMapperMismatch { expected, actual } => {
+                write!(f, "Network uses policy mapper '{}', expected '{}'", actual, expected)
+            }
+            MetadataError::OutputLayoutMismatch {
+                expected,
+                aux_count,
+                output_count,
+            } => {
+                write!(
+                    f,
+                    "Metadata claims output layout {:?} with {} auxiliary outputs, but graph has {} outputs",
+                    expected, aux_count, output_count
+                )
+            }
+            MetadataError::AuxOutputMismatch { expected, actual } => {
+                write!(f, "Network has auxiliary outputs {:?}, expected {:?}", actual, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MetadataError {}
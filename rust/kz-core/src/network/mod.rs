@@ -1,15 +1,25 @@
 use std::borrow::{Borrow, Cow};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::sync::Arc;
 
 use board_game::board::Board;
 
+use crate::error::NetworkError;
+use crate::mapping::AuxOutputSpec;
+use crate::network::common::check_evaluations_finite;
 use crate::network::job_channel::JobClient;
 use crate::zero::values::ZeroValuesPov;
 
+pub mod cache;
 pub mod common;
 pub mod dummy;
+pub mod ensemble;
+pub mod metadata;
 #[cfg(feature = "muzero")]
 pub mod muzero;
+pub mod remote;
+pub mod replay;
 pub mod symmetry;
 
 // TODO remove CPU/GPU separation, this is already handled by kn-runtime
@@ -29,13 +39,20 @@ pub struct ZeroEvaluation<'a> {
 
     /// The (normalized) policy "vector", only containing the available moves in the order they are yielded by `available_moves`.
     pub policy: Cow<'a, [f32]>,
+
+    /// The auxiliary outputs, only present if the network has them, see [AuxOutputSpec].
+    pub aux: Option<Arc<AuxOutputs>>,
 }
 
+/// Raw auxiliary network outputs for a single board, keyed by [AuxOutputSpec::name].
+pub type AuxOutputs = BTreeMap<&'static str, Vec<f32>>;
+
 impl ZeroEvaluation<'_> {
     pub fn shallow_clone(&self) -> ZeroEvaluation {
         ZeroEvaluation {
             values: self.values,
             policy: Cow::Borrowed(self.policy.borrow()),
+            aux: self.aux.clone(),
         }
     }
 
@@ -43,6 +60,7 @@ impl ZeroEvaluation<'_> {
         ZeroEvaluation {
             values: ZeroValuesPov::nan(),
             policy: Cow::Owned(vec![f32::NAN; policy_size]),
+            aux: None,
         }
     }
 }
@@ -55,6 +73,17 @@ pub trait Network<B: Board>: Debug {
     // TODO change arg type to impl exact size iterator instead?
     fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>>;
 
+    /// Fallible version of [Network::evaluate_batch], returns an error instead of panicking or
+    /// returning non-finite values for invalid network outputs.
+    ///
+    /// The default implementation only checks the outputs of `evaluate_batch` for non-finite values,
+    /// implementations that decode raw graph outputs should override this.
+    fn try_evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Result<Vec<ZeroEvaluation<'static>>, NetworkError> {
+        let result = self.evaluate_batch(boards);
+        check_evaluations_finite(&result)?;
+        Ok(result)
+    }
+
     fn evaluate(&mut self, board: &B) -> ZeroEvaluation<'static> {
         let mut result = self.evaluate_batch(&[board]);
         assert_eq!(result.len(), 1);
@@ -70,4 +99,8 @@ impl<B: Board, N: Network<B>> Network<B> for &mut N {
     fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
         (**self).evaluate_batch(boards)
     }
+
+    fn try_evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Result<Vec<ZeroEvaluation<'static>>, NetworkError> {
+        (**self).try_evaluate_batch(boards)
+    }
 }
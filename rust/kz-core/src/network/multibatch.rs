@@ -6,6 +6,12 @@ use itertools::Itertools;
 
 use crate::network::{Network, ZeroEvaluation};
 
+/// A network that combines multiple inner networks that each have a fixed batch size,
+/// for example [CudaNetwork](crate::network::cudnn::CudaNetwork) or
+/// [PreparedNetwork](crate::network::prepared::PreparedNetwork) compiled for different batch sizes.
+///
+/// Batches of any size are accepted, they're split into chunks that minimize the padding waste
+/// across the available sizes.
 #[derive(Debug)]
 pub struct MultiBatchNetwork<I> {
     networks: Vec<(usize, I)>,
@@ -13,16 +19,65 @@ pub struct MultiBatchNetwork<I> {
 
 impl<I> MultiBatchNetwork<I> {
     pub fn new(networks: Vec<(usize, I)>) -> Self {
+        assert!(!networks.is_empty(), "MultiBatchNetwork needs at least one network");
+        assert!(
+            networks.iter().all(|&(size, _)| size > 0),
+            "MultiBatchNetwork batch sizes must be positive"
+        );
         MultiBatchNetwork { networks }
     }
 
     pub fn build_sizes(sizes: &[usize], mut f: impl FnMut(usize) -> I) -> Self {
         let networks = sizes.iter().map(|&size| (size, f(size))).collect_vec();
-        MultiBatchNetwork { networks }
+        Self::new(networks)
+    }
+
+    /// The largest batch size that can be evaluated in a single chunk.
+    pub fn largest_batch_size(&self) -> usize {
+        self.networks.iter().map(|&(size, _)| size).max().unwrap()
+    }
+
+    /// Split a batch of `batch_size` items into chunks, such that the total padding is minimal.
+    /// Ties are broken by the number of chunks. The returned chunk sizes sum up to `batch_size`.
+    ///
+    /// This assumes the cost of evaluating a network is proportional to its batch size,
+    /// so full chunks of the largest size are always used for the bulk of the batch.
+    pub fn chunk_sizes(&self, batch_size: usize) -> Vec<usize> {
+        let largest = self.largest_batch_size();
+
+        let full_count = batch_size / largest;
+        let rest = batch_size % largest;
+
+        // dynamic programming over the remainder, best[n] = (padding, chunk count, last chunk)
+        let mut best: Vec<(usize, usize, usize)> = vec![(0, 0, 0)];
+        for n in 1..=rest {
+            let entry = self
+                .networks
+                .iter()
+                .map(|&(size, _)| {
+                    let take = size.min(n);
+                    let (padding, count, _) = best[n - take];
+                    (padding + size - take, count + 1, take)
+                })
+                .min()
+                .unwrap();
+            best.push(entry);
+        }
+
+        let mut chunks = vec![largest; full_count];
+        let mut n = rest;
+        while n > 0 {
+            let take = best[n].2;
+            chunks.push(take);
+            n -= take;
+        }
+        chunks
     }
 }
 
 impl<I: Debug> MultiBatchNetwork<I> {
+    /// The index of the network used for a single chunk of `batch_size` items.
+    /// Panics if the batch is larger than all networks, see [Self::chunk_sizes].
     pub fn used_network_index(&self, batch_size: usize) -> usize {
         (0..self.networks.len())
             .filter(|i| self.networks[*i].0 >= batch_size)
@@ -37,13 +92,23 @@ impl<I: Debug> MultiBatchNetwork<I> {
 
 impl<B: Board, I: Network<B>> Network<B> for MultiBatchNetwork<I> {
     fn max_batch_size(&self) -> usize {
-        self.networks.iter().map(|(size, _)| *size).max().unwrap_or(0)
+        // we automatically split larger batches
+        usize::MAX
     }
 
     fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
-        let index = self.used_network_index(boards.len());
-        let network = &mut self.networks[index].1;
+        let mut result = Vec::with_capacity(boards.len());
+        let mut start = 0;
+
+        for chunk_size in self.chunk_sizes(boards.len()) {
+            let chunk = &boards[start..start + chunk_size];
+            start += chunk_size;
+
+            let index = self.used_network_index(chunk_size);
+            result.extend(self.networks[index].1.evaluate_batch(chunk));
+        }
 
-        network.evaluate_batch(boards)
+        assert_eq!(start, boards.len());
+        result
     }
 }
@@ -21,6 +21,7 @@ use crate::mapping::BoardMapper;
 use crate::muzero::MuZeroEvaluation;
 use crate::network::common::zero_values_from_scalars;
 use crate::network::job_channel::JobClient;
+use crate::network::metadata::{MetadataError, NetworkMetadata};
 
 pub type RootClient<B> = JobClient<RootArgs<B>, MuZeroEvaluation<'static>>;
 pub type ExpandClient = JobClient<ExpandArgs, MuZeroEvaluation<'static>>;
@@ -35,13 +36,13 @@ pub struct MuZeroNetworkInfo {
 
 impl MuZeroNetworkInfo {
     pub fn state_shape<B: Board, M: BoardMapper<B>>(&self, mapper: M) -> Shape {
-        let b = mapper.state_board_size();
-        shape![Size::BATCH, self.state_channels, b, b]
+        let [h, w] = mapper.state_board_shape();
+        shape![Size::BATCH, self.state_channels, h, w]
     }
 
     pub fn state_saved_shape<B: Board, M: BoardMapper<B>>(&self, mapper: M) -> Shape {
-        let b = mapper.state_board_size();
-        shape![Size::BATCH, self.state_channels_saved, b, b]
+        let [h, w] = mapper.state_board_shape();
+        shape![Size::BATCH, self.state_channels_saved, h, w]
     }
 }
 
@@ -137,6 +138,18 @@ impl<B: Board, M: BoardMapper<B>> MuZeroGraphs<B, M> {
         })
     }
 
+    /// Check the sidecar metadata of the prediction graph against `game` and the mapper,
+    /// older networks without metadata are only checked through their shapes in [Self::load].
+    pub fn check_metadata(&self, path: &str, game: &str) -> Result<(), MetadataError> {
+        match NetworkMetadata::load_for_onnx(format!("{}prediction.onnx", path))? {
+            None => Ok(()),
+            Some(metadata) => {
+                metadata.check_game(game)?;
+                metadata.check(self.mapper, &self.prediction)
+            }
+        }
+    }
+
     pub fn optimize(&self, settings: OptimizerSettings) -> MuZeroGraphs<B, M> {
         MuZeroGraphs {
             mapper: self.mapper,
@@ -8,8 +8,10 @@ use kn_graph::graph::Graph;
 use kn_runtime::{Device, PreparedGraph};
 use ndarray::IxDyn;
 
+use crate::error::NetworkError;
 use crate::mapping::BoardMapper;
-use crate::network::common::decode_output;
+use crate::network::common::{check_graph_shapes, try_decode_output};
+use crate::network::metadata::{check_optional_metadata, NetworkMetadata};
 use crate::network::{Network, ZeroEvaluation};
 
 #[derive(Debug)]
@@ -21,13 +23,22 @@ pub struct PreparedNetwork<B: Board, M: BoardMapper<B>> {
 }
 
 impl<B: Board, M: BoardMapper<B>> PreparedNetwork<B, M> {
-    pub fn new(mapper: M, device: Device, graph: Graph, batch_size: usize) -> Self {
-        Self {
+    pub fn new(
+        mapper: M,
+        device: Device,
+        graph: Graph,
+        batch_size: usize,
+        metadata: Option<&NetworkMetadata>,
+    ) -> Result<Self, NetworkError> {
+        check_optional_metadata(metadata, mapper, &graph)?;
+        check_graph_shapes(mapper, &graph)?;
+
+        Ok(Self {
             mapper,
             graph: device.prepare(graph, batch_size),
             batch_size,
             ph: PhantomData,
-        }
+        })
     }
 }
 
@@ -37,6 +48,11 @@ impl<B: Board, M: BoardMapper<B>> Network<B> for PreparedNetwork<B, M> {
     }
 
     fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
+        self.try_evaluate_batch(boards)
+            .unwrap_or_else(|e| panic!("Failed to evaluate batch: {}", e))
+    }
+
+    fn try_evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Result<Vec<ZeroEvaluation<'static>>, NetworkError> {
         assert!(boards.len() <= self.batch_size);
 
         // encore the input
@@ -62,16 +78,16 @@ impl<B: Board, M: BoardMapper<B>> Network<B> for PreparedNetwork<B, M> {
         // evaluate the graph
         let outputs = self.graph.eval(&[DTensor::F32(input)]);
 
-        // unwrap the output types
+        // unwrap the output types and remove the padding again
         let batch_outputs = outputs
             .iter()
-            .map(|t| t.unwrap_f32().unwrap().as_slice().unwrap())
+            .map(|t| {
+                let x = t.unwrap_f32().unwrap().as_slice().unwrap();
+                let item_len = x.len() / self.batch_size;
+                &x[0..boards.len() * item_len]
+            })
             .collect_vec();
 
-        let mut result = decode_output(self.mapper, boards, &batch_outputs);
-
-        // remove padding again
-        result.truncate(boards.len());
-        result
+        try_decode_output(self.mapper, boards, &batch_outputs)
     }
 }
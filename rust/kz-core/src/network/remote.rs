@@ -0,0 +1,157 @@
+use std::borrow::Borrow;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::net::{TcpStream, ToSocketAddrs};
+
+use board_game::board::Board;
+use itertools::Itertools;
+
+use crate::error::NetworkError;
+use crate::mapping::BoardMapper;
+use crate::network::common::try_decode_output;
+use crate::network::{Network, ZeroEvaluation};
+
+// Protocol used between `RemoteNetwork` and the inference server, all numbers are little endian.
+//
+// Handshake:
+// * client: `u32` magic, `u32` rank, `[u32; rank]` input shape excluding the batch dimension
+// * server: `u32` status, `0` if the shape matches the graph, otherwise the server closes the connection
+// * server: `u32` max batch size, only if the status was ok
+//
+// Then any number of requests:
+// * client: `u32` batch size (at most the max batch size), `[f32; batch_size * input_len]` encoded inputs
+// * server: `u32` output count, then for each output `u32` item length, `[f32; batch_size * item_len]` outputs
+
+pub const REMOTE_MAGIC: u32 = 0x6B7A_6E31;
+pub const REMOTE_STATUS_OK: u32 = 0;
+pub const REMOTE_STATUS_SHAPE_MISMATCH: u32 = 1;
+
+/// A `Network` that sends encoded boards to an inference server and decodes the outputs locally.
+/// This allows multiple processes to share a single GPU and loaded network.
+pub struct RemoteNetwork<B: Board, M: BoardMapper<B>> {
+    mapper: M,
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+    max_batch_size: usize,
+    ph: PhantomData<B>,
+}
+
+impl<B: Board, M: BoardMapper<B>> RemoteNetwork<B, M> {
+    pub fn connect(mapper: M, addr: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+
+        // handshake
+        let shape = mapper.input_full_shape();
+        write_u32(&mut writer, REMOTE_MAGIC)?;
+        write_u32(&mut writer, shape.len() as u32)?;
+        for &d in &shape {
+            write_u32(&mut writer, d as u32)?;
+        }
+        writer.flush()?;
+
+        let status = read_u32(&mut reader)?;
+        if status != REMOTE_STATUS_OK {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Inference server rejected input shape {:?} for mapper {:?} with status {}",
+                    shape, mapper, status
+                ),
+            ));
+        }
+        let max_batch_size = read_u32(&mut reader)? as usize;
+
+        Ok(RemoteNetwork {
+            mapper,
+            reader,
+            writer,
+            max_batch_size,
+            ph: PhantomData,
+        })
+    }
+
+    fn evaluate_batch_raw(&mut self, boards: &[impl Borrow<B>]) -> std::io::Result<Vec<Vec<f32>>> {
+        // encode and send the input
+        let mut input = vec![];
+        for board in boards {
+            self.mapper.encode_input_full(&mut input, board.borrow());
+        }
+
+        write_u32(&mut self.writer, boards.len() as u32)?;
+        write_f32s(&mut self.writer, &input)?;
+        self.writer.flush()?;
+
+        // receive the outputs
+        let output_count = read_u32(&mut self.reader)?;
+        (0..output_count)
+            .map(|_| {
+                let item_len = read_u32(&mut self.reader)? as usize;
+                read_f32s(&mut self.reader, boards.len() * item_len)
+            })
+            .collect()
+    }
+}
+
+impl<B: Board, M: BoardMapper<B>> Network<B> for RemoteNetwork<B, M> {
+    fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
+        self.try_evaluate_batch(boards)
+            .unwrap_or_else(|e| panic!("Failed to evaluate batch: {}", e))
+    }
+
+    fn try_evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Result<Vec<ZeroEvaluation<'static>>, NetworkError> {
+        assert!(boards.len() <= self.max_batch_size);
+        if boards.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // connection errors are not recoverable, the stream is out of sync afterwards
+        let outputs = self.evaluate_batch_raw(boards)?;
+        let outputs = outputs.iter().map(|o| o.as_slice()).collect_vec();
+
+        try_decode_output(self.mapper, boards, &outputs)
+    }
+}
+
+impl<B: Board, M: BoardMapper<B>> std::fmt::Debug for RemoteNetwork<B, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteNetwork")
+            .field("mapper", &self.mapper)
+            .field("peer", &self.writer.get_ref().peer_addr().ok())
+            .field("max_batch_size", &self.max_batch_size)
+            .finish()
+    }
+}
+
+pub fn write_u32(writer: &mut impl Write, x: u32) -> std::io::Result<()> {
+    writer.write_all(&x.to_le_bytes())
+}
+
+pub fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+pub fn write_f32s(writer: &mut impl Write, data: &[f32]) -> std::io::Result<()> {
+    for &x in data {
+        writer.write_all(&x.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn read_f32s(reader: &mut impl Read, len: usize) -> std::io::Result<Vec<f32>> {
+    let mut bytes = vec![0; len * 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+        .collect())
+}
@@ -0,0 +1,277 @@
+use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+
+use board_game::board::Board;
+use board_game::pov::ScalarPov;
+use board_game::wdl::WDL;
+
+use crate::mapping::bit_buffer::BitBuffer;
+use crate::mapping::BoardMapper;
+use crate::network::{AuxOutputs, Network, ZeroEvaluation};
+use crate::zero::values::ZeroValuesPov;
+
+// The file format is a sequence of records, each consisting of (all little endian):
+// * `u32` input length in bytes, followed by the encoded input:
+//   the bool bits as stored in `BitBuffer` followed by the `f32` scalars
+// * `[f32; 7]` values: value, wdl, moves left, score and score weight
+// * `u32` policy length, followed by the `f32` policy
+// * `u32` aux output count, followed for each aux output by
+//   the `u32` name length, the utf8 name, the `u32` output length and the `f32` output
+//
+// Boards are identified by their encoded input, since that's all the network ever sees.
+
+/// Wrapper around a `Network` that appends every evaluation it returns to a file,
+/// so it can be served again later by a [ReplayNetwork] using the same mapper.
+pub struct RecordingNetwork<B: Board, M: BoardMapper<B>, N: Network<B>> {
+    inner: N,
+    mapper: M,
+    writer: BufWriter<File>,
+    ph: PhantomData<B>,
+}
+
+/// A `Network` that serves evaluations previously written by a [RecordingNetwork].
+/// Panics if it is asked to evaluate a board that was never recorded.
+#[derive(Debug)]
+pub struct ReplayNetwork<B: Board, M: BoardMapper<B>> {
+    mapper: M,
+    evals: HashMap<Vec<u8>, ZeroEvaluation<'static>>,
+    max_batch_size: usize,
+    ph: PhantomData<B>,
+}
+
+impl<B: Board, M: BoardMapper<B>, N: Network<B>> RecordingNetwork<B, M, N> {
+    pub fn new(inner: N, mapper: M, path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let writer = BufWriter::new(File::create(path)?);
+        Ok(RecordingNetwork {
+            inner,
+            mapper,
+            writer,
+            ph: PhantomData,
+        })
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(mut self) -> std::io::Result<N> {
+        self.writer.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<B: Board, M: BoardMapper<B>, N: Network<B>> Network<B> for RecordingNetwork<B, M, N> {
+    fn max_batch_size(&self) -> usize {
+        self.inner.max_batch_size()
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
+        let evals = self.inner.evaluate_batch(boards);
+
+        for (board, eval) in boards.iter().zip(&evals) {
+            let input = encoded_input(self.mapper, board.borrow());
+            write_record(&mut self.writer, &input, eval)
+                .unwrap_or_else(|e| panic!("Failed to write network recording: {:?}", e));
+        }
+
+        evals
+    }
+}
+
+impl<B: Board, M: BoardMapper<B>> ReplayNetwork<B, M> {
+    pub fn load(path: impl AsRef<Path>, mapper: M, max_batch_size: usize) -> std::io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        // aux output names are static strings in the evaluation, so we get them from the mapper
+        let aux_names = mapper.aux_outputs().iter().map(|spec| spec.name).collect::<Vec<_>>();
+
+        // later records overwrite earlier ones, they should be identical anyway
+        let mut evals = HashMap::new();
+        while let Some((input, eval)) = read_record(&mut reader, &aux_names)? {
+            evals.insert(input, eval);
+        }
+
+        Ok(ReplayNetwork {
+            mapper,
+            evals,
+            max_batch_size,
+            ph: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.evals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.evals.is_empty()
+    }
+}
+
+impl<B: Board, M: BoardMapper<B>> Network<B> for ReplayNetwork<B, M> {
+    fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<B>]) -> Vec<ZeroEvaluation<'static>> {
+        assert!(boards.len() <= self.max_batch_size);
+
+        boards
+            .iter()
+            .map(|board| {
+                let board = board.borrow();
+                self.evals
+                    .get(&encoded_input(self.mapper, board))
+                    .unwrap_or_else(|| panic!("Board was not recorded:\n{}", board))
+                    .clone()
+            })
+            .collect()
+    }
+}
+
+fn encoded_input<B: Board>(mapper: impl BoardMapper<B>, board: &B) -> Vec<u8> {
+    let mut bools = BitBuffer::new(mapper.input_bool_len());
+    let mut scalars = vec![];
+    mapper.encode_input(&mut bools, &mut scalars, board);
+
+    let mut result = bools.storage().to_vec();
+    for x in scalars {
+        result.extend_from_slice(&x.to_le_bytes());
+    }
+    result
+}
+
+fn write_record(writer: &mut impl Write, input: &[u8], eval: &ZeroEvaluation) -> std::io::Result<()> {
+    write_u32(writer, input.len() as u32)?;
+    writer.write_all(input)?;
+
+    let values = eval.values;
+    let values = [
+        values.value.value,
+        values.wdl.win,
+        values.wdl.draw,
+        values.wdl.loss,
+        values.moves_left,
+        values.score.value,
+        values.score_weight,
+    ];
+    for x in values {
+        write_f32(writer, x)?;
+    }
+
+    write_f32s(writer, &eval.policy)?;
+
+    match &eval.aux {
+        None => write_u32(writer, 0)?,
+        Some(aux) => {
+            write_u32(writer, aux.len() as u32)?;
+            for (name, output) in aux.iter() {
+                write_u32(writer, name.len() as u32)?;
+                writer.write_all(name.as_bytes())?;
+                write_f32s(writer, output)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_record(
+    reader: &mut impl Read,
+    aux_names: &[&'static str],
+) -> std::io::Result<Option<(Vec<u8>, ZeroEvaluation<'static>)>> {
+    let input_len = match read_u32(reader) {
+        Ok(len) => len as usize,
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let input = read_bytes(reader, input_len)?;
+
+    let values = ZeroValuesPov {
+        value: ScalarPov::new(read_f32(reader)?),
+        wdl: WDL::new(read_f32(reader)?, read_f32(reader)?, read_f32(reader)?),
+        moves_left: read_f32(reader)?,
+        score: ScalarPov::new(read_f32(reader)?),
+        score_weight: read_f32(reader)?,
+    };
+
+    let policy = read_f32s(reader)?;
+
+    let aux_count = read_u32(reader)?;
+    let aux = if aux_count == 0 {
+        None
+    } else {
+        let mut aux = AuxOutputs::new();
+        for _ in 0..aux_count {
+            let name_len = read_u32(reader)? as usize;
+            let name = read_bytes(reader, name_len)?;
+            let name = aux_names.iter().find(|n| n.as_bytes() == name).ok_or_else(|| {
+                let name = String::from_utf8_lossy(&name);
+                std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Recorded aux output {:?} is not declared by the mapper", name),
+                )
+            })?;
+            aux.insert(*name, read_f32s(reader)?);
+        }
+        Some(Arc::new(aux))
+    };
+
+    let eval = ZeroEvaluation {
+        values,
+        policy: Cow::Owned(policy),
+        aux,
+    };
+    Ok(Some((input, eval)))
+}
+
+fn write_u32(writer: &mut impl Write, x: u32) -> std::io::Result<()> {
+    writer.write_all(&x.to_le_bytes())
+}
+
+fn write_f32(writer: &mut impl Write, x: f32) -> std::io::Result<()> {
+    writer.write_all(&x.to_le_bytes())
+}
+
+fn write_f32s(writer: &mut impl Write, xs: &[f32]) -> std::io::Result<()> {
+    write_u32(writer, xs.len() as u32)?;
+    for &x in xs {
+        write_f32(writer, x)?;
+    }
+    Ok(())
+}
+
+fn read_bytes(reader: &mut impl Read, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f32(reader: &mut impl Read) -> std::io::Result<f32> {
+    read_u32(reader).map(f32::from_bits)
+}
+
+fn read_f32s(reader: &mut impl Read) -> std::io::Result<Vec<f32>> {
+    let len = read_u32(reader)? as usize;
+    (0..len).map(|_| read_f32(reader)).collect()
+}
+
+impl<B: Board, M: BoardMapper<B>, N: Network<B>> std::fmt::Debug for RecordingNetwork<B, M, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordingNetwork")
+            .field("mapper", &self.mapper)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
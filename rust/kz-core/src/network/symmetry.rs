@@ -1,6 +1,7 @@
 use std::borrow::{Borrow, Cow};
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use board_game::board::Board;
 use board_game::symmetry::{Symmetry, SymmetryDistribution};
@@ -11,7 +12,7 @@ use rand::Rng;
 
 use kz_util::sequence::IndexOf;
 
-use crate::network::{Network, ZeroEvaluation};
+use crate::network::{AuxOutputs, Network, ZeroEvaluation};
 use crate::zero::values::ZeroValuesPov;
 
 /// Wrapper around a `Network` that optionally first applies a random symmetry to evaluated boards.
@@ -123,7 +124,7 @@ impl<B: Board, N: Network<B>> Network<B> for AverageSymmetryNetwork<B, N> {
     }
 }
 
-fn unmap_eval<B: Board>(
+pub(crate) fn unmap_eval<B: Board>(
     board: &B,
     sym: B::Symmetry,
     mapped_board: B,
@@ -141,9 +142,16 @@ fn unmap_eval<B: Board>(
             .collect()
     });
 
+    let aux = if sym == B::Symmetry::default() {
+        mapped_eval.aux
+    } else {
+        mapped_eval.aux.and_then(|aux| invariant_aux(&aux))
+    };
+
     ZeroEvaluation {
         values: mapped_eval.values,
         policy: Cow::Owned(policy),
+        aux,
     }
 }
 
@@ -177,9 +185,39 @@ fn average_evals<B: Board>(
         }
     }
 
+    // average the invariant auxiliary outputs, if every evaluation has them
+    let aux = mapped_evals
+        .iter()
+        .map(|eval| eval.aux.as_deref().and_then(invariant_aux))
+        .collect::<Option<Vec<_>>>()
+        .map(|all_aux| {
+            let mut averaged = (*all_aux[0]).clone();
+            for (name, value) in averaged.iter_mut() {
+                value[0] = all_aux.iter().map(|aux| aux[name][0]).sum::<f32>() / all_aux.len() as f32;
+            }
+            Arc::new(averaged)
+        });
+
     ZeroEvaluation {
         values,
         policy: Cow::Owned(policy),
+        aux,
+    }
+}
+
+/// Auxiliary outputs can be spatial and we don't know how to unmap those, so only the single-value outputs
+/// (for example the score lead) are kept, they are invariant under symmetries.
+fn invariant_aux(aux: &AuxOutputs) -> Option<Arc<AuxOutputs>> {
+    let invariant: AuxOutputs = aux
+        .iter()
+        .filter(|(_, values)| values.len() == 1)
+        .map(|(&name, values)| (name, values.clone()))
+        .collect();
+
+    if invariant.is_empty() {
+        None
+    } else {
+        Some(Arc::new(invariant))
     }
 }
 
@@ -0,0 +1,264 @@
+//! The single place where each [Game] is declared together with its board type, mapper,
+//! start positions and fen parsing.
+//!
+//! Binaries implement [GameVisitor] and call [visit_game] to get the statically typed board and mapper,
+//! so adding a game only means adding it here and to [Game], including [Game::examples].
+
+use std::hash::Hash;
+use std::sync::Arc;
+
+use board_game::board::{AltBoard, Board};
+use board_game::games::arimaa::ArimaaBoard;
+use board_game::games::ataxx::AtaxxBoard;
+use board_game::games::chess::{ChessBoard, Rules};
+use board_game::games::go;
+use board_game::games::go::{GoBoard, Komi};
+use board_game::games::sttt::STTTBoard;
+use board_game::games::ttt::TTTBoard;
+use internal_iterator::InternalIterator;
+use rand::rngs::StdRng;
+use trictrac_bot::trictrac_board::TrictracBoard;
+
+use kz_util::game::Game;
+
+use crate::error::{parse_game, MappingError};
+use crate::games::connect4::Connect4Board;
+use crate::games::hex::HexBoard;
+use crate::games::othello::OthelloBoard;
+use crate::mapping::arimaa::ArimaaSplitMapper;
+use crate::mapping::ataxx::AtaxxStdMapper;
+use crate::mapping::chess::{ChessAttPolicyMapper, ChessHistoryMapper, ChessStdMapper};
+use crate::mapping::connect4::Connect4StdMapper;
+use crate::mapping::go::GoStdMapper;
+use crate::mapping::hex::HexStdMapper;
+use crate::mapping::history::{HistoryBoard, HistoryMapper};
+use crate::mapping::othello::OthelloStdMapper;
+use crate::mapping::sttt::STTTStdMapper;
+use crate::mapping::trictrac::{TrictracStdMapper, TrictracV2Mapper};
+use crate::mapping::ttt::TTTStdMapper;
+use crate::mapping::{BoardMapper, ComposedMapper};
+use crate::registry::start_pos::{ataxx_start_pos, go_start_pos, history_start_pos, othello_start_pos};
+
+pub mod start_pos;
+
+pub type StartPos<B> = Arc<dyn Fn(&mut StdRng) -> B + Send + Sync>;
+
+/// Everything the binaries need to know about a single game.
+pub struct GameEntry<B: Board, M> {
+    pub game: Game,
+    pub mapper: M,
+
+    default_board: Arc<dyn Fn() -> B + Send + Sync>,
+    start_pos: Box<dyn Fn(&str) -> StartPos<B>>,
+    from_fen: Option<Box<dyn Fn(&str) -> B>>,
+    example_boards: Option<Box<dyn Fn() -> Vec<(String, B)>>>,
+}
+
+impl<B: Board, M> GameEntry<B, M> {
+    /// An entry that only supports the `"default"` start position, which is `default_board`.
+    pub fn new(game: Game, mapper: M, default_board: impl Fn() -> B + Send + Sync + 'static) -> Self {
+        let default_board: Arc<dyn Fn() -> B + Send + Sync> = Arc::new(default_board);
+
+        let default_board_clone = default_board.clone();
+        let start_pos = move |start_pos: &str| -> StartPos<B> {
+            assert_eq!(
+                start_pos, "default",
+                "Game {} only supports the default start_pos",
+                game
+            );
+            let default_board = default_board_clone.clone();
+            Arc::new(move |_: &mut StdRng| default_board())
+        };
+
+        GameEntry {
+            game,
+            mapper,
+            default_board,
+            start_pos: Box::new(start_pos),
+            from_fen: None,
+            example_boards: None,
+        }
+    }
+
+    /// Replace the start position parser, `start_pos` should panic for unknown specifications.
+    pub fn with_start_pos<F: Fn(&mut StdRng) -> B + Send + Sync + 'static>(
+        mut self,
+        start_pos: impl Fn(&str) -> F + 'static,
+    ) -> Self {
+        self.start_pos = Box::new(move |spec: &str| -> StartPos<B> { Arc::new(start_pos(spec)) });
+        self
+    }
+
+    /// Add a fen parser, `from_fen` should panic for invalid fens.
+    pub fn with_fen(mut self, from_fen: impl Fn(&str) -> B + 'static) -> Self {
+        self.from_fen = Some(Box::new(from_fen));
+        self
+    }
+
+    /// Replace the example boards, they should cover every board-dependent part of the mapping.
+    pub fn with_example_boards(mut self, example_boards: impl Fn() -> Vec<(String, B)> + 'static) -> Self {
+        self.example_boards = Some(Box::new(example_boards));
+        self
+    }
+
+    pub fn default_board(&self) -> B {
+        (self.default_board)()
+    }
+
+    /// Build the start position sampler for the given specification, panics if it's unknown.
+    pub fn start_pos(&self, start_pos: &str) -> impl Fn(&mut StdRng) -> B + Send + Sync + Clone + 'static {
+        let start_pos = (self.start_pos)(start_pos);
+        move |rng| start_pos(rng)
+    }
+
+    /// Named boards that together cover the board-dependent parts of the mapping, see [export_mapping](crate::mapping::export::export_mapping).
+    /// Defaults to the default board and the board after its first move, so both players are included.
+    pub fn example_boards(&self) -> Vec<(String, B)> {
+        match &self.example_boards {
+            Some(example_boards) => example_boards(),
+            None => with_first_move("default", self.default_board()),
+        }
+    }
+
+    pub fn supports_fen(&self) -> bool {
+        self.from_fen.is_some()
+    }
+
+    /// Parse the given fen, for some games this is a move sequence instead.
+    /// Panics if the fen is invalid or the game doesn't support fens.
+    pub fn from_fen(&self, fen: &str) -> B {
+        let from_fen = self
+            .from_fen
+            .as_ref()
+            .unwrap_or_else(|| panic!("Game {} does not support fens", self.game));
+        from_fen(fen)
+    }
+
+    /// Use [Self::from_fen] if a fen is given, otherwise [Self::default_board].
+    pub fn board_from_optional_fen(&self, fen: Option<&str>) -> B {
+        match fen {
+            None => self.default_board(),
+            Some(fen) => self.from_fen(fen),
+        }
+    }
+}
+
+/// Callback for [visit_game], receives the entry of the selected game.
+pub trait GameVisitor: Sized {
+    type Output;
+
+    fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> Self::Output;
+
+    /// Called instead of [Self::visit] for alternating boards.
+    fn visit_alt<B: AltBoard + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> Self::Output {
+        self.visit(entry)
+    }
+}
+
+/// Fallible version of [visit_game] that looks up the game by name.
+pub fn visit_game_str<V: GameVisitor>(game: &str, visitor: V) -> Result<V::Output, MappingError> {
+    Ok(visit_game(parse_game(game)?, visitor))
+}
+
+/// Call `visitor` with the entry for `game`, using [GameVisitor::visit_alt] for alternating boards.
+pub fn visit_game<V: GameVisitor>(game: Game, visitor: V) -> V::Output {
+    //TODO static dispatch this early means we're generating a lot of code N times
+    //  is it actually that much? -> investigate with objdump or similar
+    //  would it be relatively easy to delay this dispatch some more?
+    match game {
+        Game::TTT => visitor.visit_alt(GameEntry::new(game, TTTStdMapper, TTTBoard::default)),
+        Game::STTT => visitor.visit_alt(GameEntry::new(game, STTTStdMapper, STTTBoard::default)),
+        Game::Connect4 => visitor.visit_alt(
+            GameEntry::new(game, Connect4StdMapper, Connect4Board::default)
+                .with_fen(|moves| Connect4Board::from_moves(moves).expect("Invalid move sequence")),
+        ),
+        Game::Othello => visitor.visit_alt(
+            GameEntry::new(game, OthelloStdMapper, OthelloBoard::default)
+                .with_start_pos(othello_start_pos)
+                .with_fen(|fen| OthelloBoard::from_fen(fen).expect("Invalid fen")),
+        ),
+        Game::Hex { size } => visitor.visit_alt(
+            GameEntry::new(game, HexStdMapper::new(size), move || HexBoard::new(size))
+                .with_fen(move |moves| HexBoard::from_moves(size, moves).expect("Invalid move sequence")),
+        ),
+        Game::Chess => {
+            visitor.visit_alt(GameEntry::new(game, ChessStdMapper, ChessBoard::default).with_fen(chess_from_fen))
+        }
+        Game::ChessAtt => visitor.visit_alt(
+            GameEntry::new(
+                game,
+                ComposedMapper::new(ChessStdMapper, ChessAttPolicyMapper),
+                ChessBoard::default,
+            )
+            .with_fen(chess_from_fen),
+        ),
+        Game::ChessHist { length } => visitor.visit_alt(
+            GameEntry::new(game, ChessHistoryMapper::new(length), ChessBoard::default).with_fen(chess_from_fen),
+        ),
+        Game::Trictrac => {
+            visitor.visit(GameEntry::new(game, TrictracStdMapper, TrictracBoard::default).with_fen(trictrac_from_fen))
+        }
+        Game::TrictracV2 => {
+            visitor.visit(GameEntry::new(game, TrictracV2Mapper, TrictracBoard::default).with_fen(trictrac_from_fen))
+        }
+        Game::TrictracHist { length } => visitor.visit(
+            GameEntry::new(game, HistoryMapper::new(TrictracStdMapper, length), move || {
+                HistoryBoard::new(TrictracBoard::default(), length)
+            })
+            .with_fen(move |fen| HistoryBoard::new(trictrac_from_fen(fen), length)),
+        ),
+        Game::Ataxx { size } => visitor.visit_alt(
+            GameEntry::new(game, AtaxxStdMapper::new(size), move || AtaxxBoard::diagonal(size))
+                .with_start_pos(move |start_pos| ataxx_start_pos(size, start_pos))
+                .with_fen(move |fen| ataxx_from_fen(size, fen)),
+        ),
+        Game::AtaxxHist { size, length } => visitor.visit_alt(
+            GameEntry::new(game, HistoryMapper::new(AtaxxStdMapper::new(size), length), move || {
+                HistoryBoard::new(AtaxxBoard::diagonal(size), length)
+            })
+            .with_start_pos(move |start_pos| history_start_pos(length, ataxx_start_pos(size, start_pos)))
+            .with_fen(move |fen| HistoryBoard::new(ataxx_from_fen(size, fen), length)),
+        ),
+        Game::ArimaaSplit => visitor.visit(GameEntry::new(game, ArimaaSplitMapper, ArimaaBoard::default)),
+        Game::Go { min_size, max_size } => visitor.visit(
+            GameEntry::new(game, GoStdMapper::new(max_size, true), move || {
+                GoBoard::new(max_size, Komi::try_from(7.5).unwrap(), go::Rules::tromp_taylor())
+            })
+            .with_start_pos(move |start_pos| go_start_pos(min_size, max_size, start_pos))
+            .with_example_boards(move || {
+                (min_size..=max_size)
+                    .flat_map(|size| {
+                        let board = GoBoard::new(size, Komi::try_from(7.5).unwrap(), go::Rules::tromp_taylor());
+                        with_first_move(&format!("{}x{}", size, size), board)
+                    })
+                    .collect()
+            })
+            .with_fen(move |fen| {
+                let board = GoBoard::from_fen(fen, go::Rules::tromp_taylor()).expect("Invalid fen");
+                assert!((min_size..=max_size).contains(&board.size()), "Fen has wrong size");
+                board
+            }),
+        ),
+    }
+}
+
+/// `board` and the board after its first available move, if any.
+fn with_first_move<B: Board>(name: &str, board: B) -> Vec<(String, B)> {
+    let first_move = board.available_moves().ok().and_then(|moves| moves.next());
+    let next = first_move.map(|mv| (format!("{}+1", name), board.clone_and_play(mv).unwrap()));
+    std::iter::once((name.to_owned(), board)).chain(next).collect()
+}
+
+fn chess_from_fen(fen: &str) -> ChessBoard {
+    ChessBoard::new_without_history_fen(fen, Rules::default())
+}
+
+fn trictrac_from_fen(fen: &str) -> TrictracBoard {
+    TrictracBoard::from_fen(fen).expect("Invalid fen")
+}
+
+fn ataxx_from_fen(size: u8, fen: &str) -> AtaxxBoard {
+    let board = AtaxxBoard::from_fen(fen).expect("Invalid fen");
+    assert_eq!(board.size(), size, "Fen has wrong size");
+    board
+}
@@ -0,0 +1,216 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use board_game::board::{Board, BoardSymmetry, Player};
+use board_game::games::ataxx::AtaxxBoard;
+use board_game::games::go::{GoBoard, Komi, Rules};
+use board_game::symmetry::SymmetryDistribution;
+use board_game::util::bitboard::BitBoard8;
+use itertools::Itertools;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::games::othello::OthelloBoard;
+use crate::mapping::history::HistoryBoard;
+
+pub fn ataxx_start_pos(
+    size: u8,
+    start_pos: &str,
+) -> impl Fn(&mut StdRng) -> AtaxxBoard + Send + Sync + Clone + 'static {
+    let mut options: Vec<(f32, Box<dyn Fn(&mut StdRng) -> AtaxxBoard + Send + Sync>)> = vec![];
+
+    match start_pos {
+        "default" => {
+            options.push((1.0, Box::new(move |_| AtaxxBoard::diagonal(size))));
+        }
+        "random-gaps-v1" => {
+            options.push((
+                1.0,
+                Box::new(move |rng| AtaxxBoard::diagonal(size).map(SymmetryDistribution.sample(rng))),
+            ));
+            options.push((0.9, Box::new(move |rng| ataxx_gen_gap_board(rng, size, 0.0..=0.4))));
+            options.push((0.1, Box::new(move |rng| ataxx_gen_gap_board(rng, size, 0.4..=1.0))));
+        }
+        _ => panic!("Unknown ataxx start_pos specification '{start_pos}'"),
+    }
+
+    let weighed_index = WeightedIndex::new(options.iter().map(|x| x.0)).unwrap();
+    let options = Arc::new(options);
+
+    move |rng| {
+        let index = weighed_index.sample(rng);
+        (options[index].1)(rng)
+    }
+}
+
+pub fn ataxx_gen_gap_board(rng: &mut impl Rng, size: u8, gap_range: RangeInclusive<f32>) -> AtaxxBoard {
+    let size = size as usize;
+
+    let tile_count_a = 2.clamp(0, (size * size) / 2);
+    let tile_count_b = 2.clamp(0, (size * size) / 2);
+    let max_gap_count = (size * size) - tile_count_a - tile_count_b;
+
+    let gap_count_start = (max_gap_count as f32 * gap_range.start()) as usize;
+    let gap_count_end = (max_gap_count as f32 * gap_range.end()) as usize;
+    let gap_count = rng.gen_range(gap_count_start..=gap_count_end);
+
+    let mut all_coords = BitBoard8::FULL_FOR_SIZE[size].into_iter().collect_vec();
+    let (sampled, _) = all_coords.partial_shuffle(rng, tile_count_a + tile_count_b + gap_count);
+
+    let tiles_a = BitBoard8::from_coords(sampled[..tile_count_a].iter().copied());
+    let tiles_b = BitBoard8::from_coords(sampled[tile_count_a..][..tile_count_b].iter().copied());
+    let gaps = BitBoard8::from_coords(sampled[tile_count_a + tile_count_b..].iter().copied());
+
+    let next_player = if rng.gen() { Player::A } else { Player::B };
+    let board = AtaxxBoard::from_parts(size as u8, tiles_a, tiles_b, gaps, 0, next_player);
+
+    board
+}
+
+/// Sample go boards with a size in `min_size..=max_size`.
+///
+/// `start_pos` is either `"default"`, which samples all sizes with equal weight,
+/// or `"size-weights:9=4,13=2,19=1"`, which samples sizes proportionally to the given weights.
+/// Sizes that are not listed get weight zero.
+pub fn go_start_pos(
+    min_size: u8,
+    max_size: u8,
+    start_pos: &str,
+) -> impl Fn(&mut StdRng) -> GoBoard + Send + Sync + Clone + 'static {
+    let sizes = (min_size..=max_size).collect_vec();
+    let size_weights = go_size_weights(min_size, max_size, start_pos);
+    let size_index = WeightedIndex::new(size_weights).unwrap();
+    let komi_index = WeightedIndex::new([4, 4, 2]).unwrap();
+
+    move |rng| {
+        let size = sizes[size_index.sample(rng)];
+        let komi_2 = match komi_index.sample(rng) {
+            0 => 15,
+            1 => rng.gen_range(10..20),
+            2 => rng.gen_range(-30..30),
+            _ => unreachable!(),
+        };
+        let rules = match rng.gen() {
+            true => Rules::cgos(),
+            false => Rules::tromp_taylor(),
+        };
+
+        GoBoard::new(size, Komi::new(komi_2), rules)
+    }
+}
+
+fn go_size_weights(min_size: u8, max_size: u8, start_pos: &str) -> Vec<f32> {
+    if start_pos == "default" {
+        return vec![1.0; (max_size - min_size + 1) as usize];
+    }
+
+    let spec = start_pos
+        .strip_prefix("size-weights:")
+        .unwrap_or_else(|| panic!("Unknown go start_pos specification '{start_pos}'"));
+
+    let mut weights = vec![0.0; (max_size - min_size + 1) as usize];
+    for entry in spec.split(',') {
+        let (size, weight) = entry
+            .split_once('=')
+            .unwrap_or_else(|| panic!("Invalid go size weight '{entry}', expected 'size=weight'"));
+        let size: u8 = size.trim().parse().expect("Invalid go size");
+        let weight: f32 = weight.trim().parse().expect("Invalid go size weight");
+
+        assert!(
+            (min_size..=max_size).contains(&size),
+            "Go size {size} is outside of the game range {min_size}..={max_size}"
+        );
+        assert!(
+            weight.is_finite() && weight >= 0.0,
+            "Go size weights must be finite and non-negative, got {weight}"
+        );
+        weights[(size - min_size) as usize] = weight;
+    }
+
+    // WeightedIndex would fail later with a much less helpful message
+    assert!(
+        weights.iter().any(|&w| w > 0.0),
+        "Go size weights '{spec}' must give at least one size a positive weight"
+    );
+
+    weights
+}
+
+/// Sample othello start positions.
+///
+/// `start_pos` is either `"default"` for the standard position,
+/// or `"random-v1"` to play up to 6 random moves from the standard position first.
+pub fn othello_start_pos(start_pos: &str) -> impl Fn(&mut StdRng) -> OthelloBoard + Send + Sync + Clone + 'static {
+    let max_moves = match start_pos {
+        "default" => 0,
+        "random-v1" => 6,
+        _ => panic!("Unknown othello start_pos specification '{start_pos}'"),
+    };
+
+    move |rng| {
+        let mut board = OthelloBoard::default();
+        let moves = rng.gen_range(0..=max_moves);
+
+        for _ in 0..moves {
+            if board.is_done() {
+                break;
+            }
+            board.play(board.random_available_move(rng).unwrap()).unwrap();
+        }
+
+        board
+    }
+}
+
+/// Wrap the boards generated by `start_pos` in a [HistoryBoard] that keeps the last `length` positions.
+pub fn history_start_pos<B: Board>(
+    length: usize,
+    start_pos: impl Fn(&mut StdRng) -> B + Send + Sync + Clone + 'static,
+) -> impl Fn(&mut StdRng) -> HistoryBoard<B> + Send + Sync + Clone + 'static {
+    move |rng| HistoryBoard::new(start_pos(rng), length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn go_size_weights_default() {
+        assert_eq!(go_size_weights(9, 13, "default"), vec![1.0; 5]);
+        assert_eq!(go_size_weights(19, 19, "default"), vec![1.0]);
+    }
+
+    #[test]
+    fn go_size_weights_spec() {
+        assert_eq!(
+            go_size_weights(9, 13, "size-weights:9=4, 11=0.5,13=1"),
+            vec![4.0, 0.0, 0.5, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one size a positive weight")]
+    fn go_size_weights_all_zero() {
+        go_size_weights(9, 19, "size-weights:9=0,19=0");
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of the game range")]
+    fn go_size_weights_out_of_range() {
+        go_size_weights(9, 19, "size-weights:7=1");
+    }
+
+    #[test]
+    #[should_panic(expected = "non-negative")]
+    fn go_size_weights_negative() {
+        go_size_weights(9, 19, "size-weights:9=-1");
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown go start_pos")]
+    fn go_size_weights_unknown() {
+        go_size_weights(9, 19, "random");
+    }
+}
@@ -42,6 +42,9 @@ pub struct Uct {
     /// moves left delta, range -inf..inf
     ///   positive means this node has more moves left than its siblings
     pub m: f32,
+    /// score lead delta in points, range -inf..inf
+    ///   positive means the current player leads by more in this node than in the parent
+    pub s: f32,
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
@@ -51,6 +54,15 @@ pub struct UctWeights {
     pub moves_left_weight: f32,
     pub moves_left_clip: f32,
     pub moves_left_sharpness: f32,
+
+    /// Weight of the score utility, zero disables it. Only has an effect for networks with a score lead output.
+    pub score_weight: f32,
+    /// The score delta at which the score utility reaches half of its maximum.
+    pub score_scale: f32,
+
+    /// The constant `k` for forced playouts at the root, each child gets at least `sqrt(k * P * N)` visits.
+    /// Zero disables forced playouts.
+    pub forced_playouts_k: f32,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -71,6 +83,9 @@ impl Default for UctWeights {
             moves_left_weight: 0.03,
             moves_left_clip: 20.0,
             moves_left_sharpness: 0.5,
+            score_weight: 0.0,
+            score_scale: 20.0,
+            forced_playouts_k: 0.0,
         }
     }
 }
@@ -81,11 +96,12 @@ impl Uct {
             q: f32::NAN,
             u: f32::NAN,
             m: f32::NAN,
+            s: f32::NAN,
         }
     }
 
     pub fn total(self, weights: UctWeights) -> f32 {
-        let Uct { q, u, m } = self;
+        let Uct { q, u, m, s } = self;
 
         let m_unit = if weights.moves_left_weight == 0.0 {
             0.0
@@ -94,7 +110,13 @@ impl Uct {
             (weights.moves_left_sharpness * m_clipped * -q).clamp(-1.0, 1.0)
         };
 
-        q + weights.exploration_weight * u + weights.moves_left_weight * m_unit
+        let s_unit = if weights.score_weight == 0.0 {
+            0.0
+        } else {
+            (s / weights.score_scale).atan() * std::f32::consts::FRAC_2_PI
+        };
+
+        q + weights.exploration_weight * u + weights.moves_left_weight * m_unit + weights.score_weight * s_unit
     }
 }
 
@@ -202,6 +224,114 @@ impl<N> Node<N> {
             self.values().moves_left - (parent.values.moves_left - 1.0)
         };
 
-        Uct { q, u, m }
+        let s = if self.complete_visits == 0 {
+            0.0
+        } else {
+            // the score lead is unknown for networks without a score head and for games without a score
+            let delta = self.values().pov(pov).score_lead() - parent.values.pov(pov).score_lead();
+            if delta.is_nan() {
+                0.0
+            } else {
+                delta
+            }
+        };
+
+        Uct { q, u, m, s }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board_game::board::Player;
+    use board_game::pov::{NonPov, Pov};
+
+    use crate::zero::values::ZeroValuesPov;
+
+    use super::*;
+
+    fn score_weights() -> UctWeights {
+        UctWeights {
+            exploration_weight: 0.0,
+            moves_left_weight: 0.0,
+            score_weight: 1.0,
+            score_scale: 20.0,
+            ..UctWeights::default()
+        }
+    }
+
+    fn score_total(s: f32, weights: UctWeights) -> f32 {
+        Uct {
+            q: 0.0,
+            u: 0.0,
+            m: 0.0,
+            s,
+        }
+        .total(weights)
+    }
+
+    #[test]
+    fn score_utility_value() {
+        let weights = score_weights();
+
+        assert_eq!(score_total(0.0, weights), 0.0);
+        // half of the maximum at the scale, symmetric around zero
+        assert!((score_total(20.0, weights) - 0.5).abs() < 1e-6);
+        assert!((score_total(-20.0, weights) + 0.5).abs() < 1e-6);
+        // bounded by the weight
+        let large = score_total(1e6, weights);
+        assert!(0.99 < large && large <= 1.0, "{}", large);
+
+        let disabled = UctWeights {
+            score_weight: 0.0,
+            ..weights
+        };
+        assert_eq!(score_total(100.0, disabled), 0.0);
+    }
+
+    #[test]
+    fn score_lead_pov() {
+        let values = ZeroValuesPov::default().with_score_lead(Some(3.0));
+        assert_eq!(values.score_lead(), 3.0);
+        assert_eq!(values.parent_flip().score_lead(), -3.0);
+
+        let abs = values.un_pov(Player::B);
+        assert_eq!(abs.score_lead_abs(), -3.0);
+        assert_eq!(abs.pov(Player::A).score_lead(), -3.0);
+        assert_eq!(abs.pov(Player::B).score_lead(), 3.0);
+
+        assert!(ZeroValuesPov::default().score_lead().is_nan());
+    }
+
+    #[test]
+    fn score_delta_pov() {
+        // player A leads by 2 in the parent and by 5 after this move
+        let parent = UctContext {
+            complete_visits: 2,
+            virtual_visits: 0,
+            total_visits: 2,
+            values: ZeroValuesPov::default().with_score_lead(Some(2.0)).un_pov(Player::A),
+            visited_policy_mass: 1.0,
+        };
+
+        let mut node = Node::<u8>::new(Some(0), Some(0), 0.5);
+        node.complete_visits = 1;
+        node.sum_values = ZeroValuesPov::default().with_score_lead(Some(5.0)).un_pov(Player::A);
+
+        let uct_a = node.uct(parent, FpuMode::Fixed(0.0), QMode::Value, 1.0, Player::A);
+        let uct_b = node.uct(parent, FpuMode::Fixed(0.0), QMode::Value, 1.0, Player::B);
+        assert_eq!(uct_a.s, 3.0);
+        assert_eq!(uct_b.s, -3.0);
+
+        let weights = score_weights();
+        assert!(score_total(uct_a.s, weights) > 0.0);
+        assert_eq!(score_total(uct_a.s, weights), -score_total(uct_b.s, weights));
+
+        // unknown score leads don't contribute
+        let mut unknown = node.clone();
+        unknown.sum_values = ZeroValuesPov::default().un_pov(Player::A);
+        assert_eq!(
+            unknown.uct(parent, FpuMode::Fixed(0.0), QMode::Value, 1.0, Player::A).s,
+            0.0
+        );
     }
 }
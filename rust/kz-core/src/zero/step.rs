@@ -4,7 +4,7 @@ use std::num::ParseFloatError;
 use std::str::FromStr;
 
 use board_game::board::Board;
-use board_game::pov::{Pov, ScalarPov};
+use board_game::pov::{NonPov, Pov, ScalarPov};
 use board_game::wdl::WDL;
 use decorum::N32;
 use internal_iterator::InternalIterator;
@@ -58,8 +58,11 @@ pub enum QMode {
 /// The reached node and its board is returned in a [ZeroRequest],
 /// and all involved nodes end up with their `virtual_visits` counter incremented.
 ///
+/// `final_score` gives the score lead of terminal boards for the next player,
+/// typically [PolicyMapper::final_score](crate::mapping::PolicyMapper::final_score).
 pub fn zero_step_gather<B: Board>(
     tree: &mut Tree<B>,
+    final_score: impl Fn(&B) -> Option<f32>,
     weights: UctWeights,
     q_mode: QMode,
     fpu_root: FpuMode,
@@ -76,7 +79,11 @@ pub fn zero_step_gather<B: Board>(
 
         // if the board is done backpropagate the real value
         if let Some(outcome) = curr_board.outcome() {
-            tree_propagate_values(tree, curr_node, ZeroValuesAbs::from_outcome(outcome, 0.0));
+            let player = curr_board.next_player();
+            let values = ZeroValuesPov::from_outcome(outcome.pov(player), 0.0)
+                .with_score_lead(final_score(&curr_board))
+                .un_pov(player);
+            tree_propagate_values(tree, curr_node, values);
             return None;
         }
 
@@ -111,6 +118,9 @@ pub fn zero_step_gather<B: Board>(
         let selected = if tree[curr_node].complete_visits == 0 {
             // pick a random least-visited child
             choose_max_by_key(children, |&child| Reverse(tree[child].total_visits()), rng)
+        } else if let Some(forced) = forced_playout_child(tree, curr_node, children, weights, rng) {
+            // this root child has not yet received its forced playouts
+            Some(forced)
         } else {
             // pick the best child
             let fpu_mode = if curr_node == 0 { fpu_root } else { fpu_child };
@@ -134,6 +144,27 @@ pub fn zero_step_gather<B: Board>(
     }
 }
 
+/// Pick a root child that has fewer visits than its forced playout count `sqrt(k * P * N)`, if there is any.
+/// This ensures moves that only get a high policy from the noise still get visited enough to judge them.
+fn forced_playout_child<B: Board>(
+    tree: &Tree<B>,
+    curr_node: usize,
+    children: IdxRange,
+    weights: UctWeights,
+    rng: &mut impl Rng,
+) -> Option<usize> {
+    if curr_node != 0 || weights.forced_playouts_k == 0.0 {
+        return None;
+    }
+
+    let parent_visits = tree[curr_node].complete_visits as f32;
+    let below_forced = children.iter().filter(|&child| {
+        let forced = (weights.forced_playouts_k * tree[child].net_policy * parent_visits).sqrt();
+        (tree[child].total_visits() as f32) < forced
+    });
+    choose_max_by_key(below_forced, |&child| N32::from_inner(tree[child].net_policy), rng)
+}
+
 /// The second half of a step. Applies a network evaluation to the given node,
 /// by setting the child policies and propagating the wdl back to the root.
 /// Along the way `virtual_visits` is decremented and `visits` is incremented.
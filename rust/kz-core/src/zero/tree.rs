@@ -10,8 +10,9 @@ use itertools::Itertools;
 use kz_util::display::display_option;
 
 use crate::network::ZeroEvaluation;
-use crate::zero::node::{Node, UctContext};
+use crate::zero::node::{Node, UctContext, UctWeights};
 use crate::zero::range::IdxRange;
+use crate::zero::step::QMode;
 use crate::zero::values::{ZeroValuesAbs, ZeroValuesPov};
 
 /// The result of a zero search.
@@ -138,10 +139,62 @@ impl<B: Board> Tree<B> {
             .map(move |c| (self[c].complete_visits as f32) / (self[0].complete_visits as f32 - 1.0).max(0.0))
     }
 
+    /// Return the policy vector for the root node, with the visits caused by forced playouts removed again.
+    /// Visits are only removed from a child as long as that doesn't make it look better than the best child,
+    /// children that end up with a single visit are pruned entirely.
+    /// This is the policy target pruning from the KataGo paper.
+    pub fn policy_pruned(&self, weights: UctWeights, q_mode: QMode) -> Vec<f32> {
+        assert!(self.len() > 1, "Must have run for at least 1 iteration");
+
+        let root = &self[0];
+        let children = root.children.unwrap();
+        let best = self.best_child(0).unwrap();
+
+        let pov = self.root_board.next_player();
+        let parent_visits = root.complete_visits;
+        let exploration_factor = ((parent_visits - 1) as f32).sqrt();
+
+        // same as the q and u terms used in the uct formula, ignoring moves left
+        let uct_total = |node: &Node<B::Move>, visits: u64| {
+            let values = node.values().pov(pov);
+            let q = q_mode.select(values.value, values.wdl).value;
+            let u = node.net_policy * exploration_factor / (1 + visits) as f32;
+            q + weights.exploration_weight * u
+        };
+        let best_uct = uct_total(&self[best], self[best].complete_visits);
+
+        let visits = children
+            .iter()
+            .map(|c| {
+                let node = &self[c];
+                let visits = node.complete_visits;
+                if c == best || visits == 0 {
+                    return visits as f32;
+                }
+
+                let forced = (weights.forced_playouts_k * node.net_policy * parent_visits as f32).sqrt();
+                let mut pruned = visits;
+                while pruned > 0 && ((visits - pruned + 1) as f32) <= forced && uct_total(node, pruned - 1) < best_uct {
+                    pruned -= 1;
+                }
+
+                if pruned <= 1 {
+                    0.0
+                } else {
+                    pruned as f32
+                }
+            })
+            .collect_vec();
+
+        let total = visits.iter().sum::<f32>();
+        visits.iter().map(|&v| v / total).collect()
+    }
+
     pub fn eval(&self) -> ZeroEvaluation<'static> {
         ZeroEvaluation {
             values: self.values(),
             policy: self.policy().collect(),
+            aux: None,
         }
     }
 
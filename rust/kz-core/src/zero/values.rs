@@ -3,18 +3,29 @@ use board_game::pov::{NonPov, Pov, ScalarAbs, ScalarPov};
 use board_game::wdl::{OutcomeWDL, WDLAbs, WDL};
 use std::fmt::{Display, Formatter};
 
+/// The values of a node or network evaluation.
+///
+/// The score lead is stored as a weighted sum together with its total weight,
+/// since not all values have a score: networks without a score head and terminal boards of games without a score
+/// only have a zero weight.
+/// Use [ZeroValuesAbs::score_lead_abs] to get the actual score lead.
 #[derive(Debug, Copy, Clone, Default)]
 pub struct ZeroValuesAbs {
     pub value_abs: ScalarAbs<f32>,
     pub wdl_abs: WDLAbs<f32>,
     pub moves_left: f32,
+    pub score_abs: ScalarAbs<f32>,
+    pub score_weight: f32,
 }
 
+/// The pov version of [ZeroValuesAbs].
 #[derive(Debug, Copy, Clone, Default)]
 pub struct ZeroValuesPov {
     pub value: ScalarPov<f32>,
     pub wdl: WDL<f32>,
     pub moves_left: f32,
+    pub score: ScalarPov<f32>,
+    pub score_weight: f32,
 }
 
 impl NonPov for ZeroValuesAbs {
@@ -25,6 +36,8 @@ impl NonPov for ZeroValuesAbs {
             value: self.value_abs.pov(pov),
             wdl: self.wdl_abs.pov(pov),
             moves_left: self.moves_left,
+            score: self.score_abs.pov(pov),
+            score_weight: self.score_weight,
         }
     }
 }
@@ -37,6 +50,8 @@ impl Pov for ZeroValuesPov {
             value_abs: self.value.un_pov(pov),
             wdl_abs: self.wdl.un_pov(pov),
             moves_left: self.moves_left,
+            score_abs: self.score.un_pov(pov),
+            score_weight: self.score_weight,
         }
     }
 }
@@ -47,6 +62,8 @@ impl ZeroValuesAbs {
             value_abs: outcome.sign(),
             wdl_abs: outcome.to_wdl_abs(),
             moves_left,
+            score_abs: ScalarAbs::new(0.0),
+            score_weight: 0.0,
         }
     }
 
@@ -55,6 +72,8 @@ impl ZeroValuesAbs {
             value_abs: ScalarAbs::new(f32::NAN),
             wdl_abs: WDLAbs::nan(),
             moves_left: f32::NAN,
+            score_abs: ScalarAbs::new(f32::NAN),
+            score_weight: f32::NAN,
         }
     }
 
@@ -64,8 +83,15 @@ impl ZeroValuesAbs {
             value_abs: self.value_abs,
             wdl_abs: self.wdl_abs,
             moves_left: self.moves_left + 1.0,
+            score_abs: self.score_abs,
+            score_weight: self.score_weight,
         }
     }
+
+    /// The score lead for player A, `NaN` if unknown.
+    pub fn score_lead_abs(&self) -> f32 {
+        score_lead(self.score_abs.value_a, self.score_weight)
+    }
 }
 
 impl ZeroValuesPov {
@@ -74,6 +100,8 @@ impl ZeroValuesPov {
             value: ScalarPov::new(outcome.sign()),
             wdl: outcome.to_wdl(),
             moves_left,
+            score: ScalarPov::new(0.0),
+            score_weight: 0.0,
         }
     }
 
@@ -82,6 +110,8 @@ impl ZeroValuesPov {
             value: ScalarPov::new(f32::NAN),
             wdl: WDL::nan(),
             moves_left: f32::NAN,
+            score: ScalarPov::new(f32::NAN),
+            score_weight: f32::NAN,
         }
     }
 
@@ -90,9 +120,29 @@ impl ZeroValuesPov {
             value: self.value.flip(),
             wdl: self.wdl.flip(),
             moves_left: self.moves_left + 1.0,
+            score: self.score.flip(),
+            score_weight: self.score_weight,
         }
     }
 
+    /// Set the score lead, with weight 1 if it's known.
+    pub fn with_score_lead(self, score_lead: Option<f32>) -> Self {
+        let (score, score_weight) = match score_lead {
+            Some(score_lead) => (score_lead, 1.0),
+            None => (0.0, 0.0),
+        };
+        ZeroValuesPov {
+            score: ScalarPov::new(score),
+            score_weight,
+            ..self
+        }
+    }
+
+    /// The score lead for the pov player, `NaN` if unknown.
+    pub fn score_lead(&self) -> f32 {
+        score_lead(self.score.value, self.score_weight)
+    }
+
     pub fn to_slice(self) -> [f32; 5] {
         [
             self.value.value,
@@ -112,6 +162,8 @@ impl std::ops::Add<Self> for ZeroValuesAbs {
             value_abs: self.value_abs + rhs.value_abs,
             wdl_abs: self.wdl_abs + rhs.wdl_abs,
             moves_left: self.moves_left + rhs.moves_left,
+            score_abs: self.score_abs + rhs.score_abs,
+            score_weight: self.score_weight + rhs.score_weight,
         }
     }
 }
@@ -124,6 +176,8 @@ impl std::ops::Add<Self> for ZeroValuesPov {
             value: self.value + rhs.value,
             wdl: self.wdl + rhs.wdl,
             moves_left: self.moves_left + rhs.moves_left,
+            score: self.score + rhs.score,
+            score_weight: self.score_weight + rhs.score_weight,
         }
     }
 }
@@ -140,6 +194,34 @@ impl std::ops::AddAssign<Self> for ZeroValuesPov {
     }
 }
 
+impl std::ops::Mul<f32> for ZeroValuesAbs {
+    type Output = ZeroValuesAbs;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        ZeroValuesAbs {
+            value_abs: self.value_abs * rhs,
+            wdl_abs: self.wdl_abs * rhs,
+            moves_left: self.moves_left * rhs,
+            score_abs: self.score_abs * rhs,
+            score_weight: self.score_weight * rhs,
+        }
+    }
+}
+
+impl std::ops::Mul<f32> for ZeroValuesPov {
+    type Output = ZeroValuesPov;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        ZeroValuesPov {
+            value: self.value * rhs,
+            wdl: self.wdl * rhs,
+            moves_left: self.moves_left * rhs,
+            score: self.score * rhs,
+            score_weight: self.score_weight * rhs,
+        }
+    }
+}
+
 impl std::ops::Div<f32> for ZeroValuesAbs {
     type Output = ZeroValuesAbs;
 
@@ -148,6 +230,8 @@ impl std::ops::Div<f32> for ZeroValuesAbs {
             value_abs: self.value_abs / rhs,
             wdl_abs: self.wdl_abs / rhs,
             moves_left: self.moves_left / rhs,
+            score_abs: self.score_abs / rhs,
+            score_weight: self.score_weight / rhs,
         }
     }
 }
@@ -160,10 +244,20 @@ impl std::ops::Div<f32> for ZeroValuesPov {
             value: self.value / rhs,
             wdl: self.wdl / rhs,
             moves_left: self.moves_left / rhs,
+            score: self.score / rhs,
+            score_weight: self.score_weight / rhs,
         }
     }
 }
 
+fn score_lead(score_sum: f32, score_weight: f32) -> f32 {
+    if score_weight > 0.0 {
+        score_sum / score_weight
+    } else {
+        f32::NAN
+    }
+}
+
 impl ZeroValuesAbs {
     pub const FORMAT_SUMMARY: &'static str = "v a/d/b m";
 }
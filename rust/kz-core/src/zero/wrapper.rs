@@ -147,8 +147,10 @@ impl ZeroSettings {
             let mut terminal_gathers = 0;
 
             while requests.len() < self.batch_size && terminal_gathers < self.batch_size {
+                // the bots are not tied to a mapper, so terminal nodes don't get a score lead here
                 match zero_step_gather(
                     tree,
+                    |_| None,
                     self.weights,
                     self.q_mode,
                     self.fpu_root,
@@ -0,0 +1,108 @@
+use std::borrow::Borrow;
+
+use board_game::board::{Board, BoardMoves};
+use board_game::pov::ScalarPov;
+use internal_iterator::InternalIterator;
+
+use kz_core::games::connect4::Connect4Board;
+use kz_core::network::cache::{CacheStats, CachedNetwork};
+use kz_core::network::{Network, ZeroEvaluation};
+use kz_core::zero::values::ZeroValuesPov;
+
+/// Network that encodes the board in the value and remembers the size of each batch it evaluated.
+#[derive(Debug, Default)]
+struct RecordingNetwork {
+    batch_sizes: Vec<usize>,
+}
+
+/// A value that is different for all boards used in these tests.
+fn board_value(board: &Connect4Board) -> f32 {
+    let key: u32 = (0..7).map(|c| board.column_height(c) as u32 * 7u32.pow(c as u32)).sum();
+    key as f32 / 7u32.pow(7) as f32
+}
+
+impl Network<Connect4Board> for RecordingNetwork {
+    fn max_batch_size(&self) -> usize {
+        16
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<Connect4Board>]) -> Vec<ZeroEvaluation<'static>> {
+        self.batch_sizes.push(boards.len());
+
+        boards
+            .iter()
+            .map(|board| {
+                let board = board.borrow();
+                let count = board.available_moves().unwrap().count();
+                ZeroEvaluation {
+                    values: ZeroValuesPov {
+                        value: ScalarPov::new(board_value(board)),
+                        ..Default::default()
+                    },
+                    policy: vec![1.0 / count as f32; count].into(),
+                    aux: None,
+                }
+            })
+            .collect()
+    }
+}
+
+fn assert_evals_match(boards: &[Connect4Board], evals: &[ZeroEvaluation]) {
+    assert_eq!(boards.len(), evals.len());
+    for (board, eval) in boards.iter().zip(evals) {
+        assert_eq!(
+            eval.values.value.value,
+            board_value(board),
+            "Wrong eval for board\n{}",
+            board
+        );
+    }
+}
+
+#[test]
+fn mixed_hits_keep_batch_order() {
+    let boards = ["", "1", "12", "4"].map(|moves| Connect4Board::from_moves(moves).unwrap());
+    let mut network = CachedNetwork::new(RecordingNetwork::default(), 16, false);
+
+    let first = boards[..2].to_vec();
+    let evals = network.evaluate_batch(&first);
+    assert_evals_match(&first, &evals);
+    assert_eq!(network.stats(), CacheStats { hits: 0, misses: 2 });
+
+    // hit, miss, hit, miss
+    let mixed = vec![
+        boards[1].clone(),
+        boards[2].clone(),
+        boards[0].clone(),
+        boards[3].clone(),
+    ];
+    let evals = network.evaluate_batch(&mixed);
+    assert_evals_match(&mixed, &evals);
+    assert_eq!(network.stats(), CacheStats { hits: 2, misses: 4 });
+
+    // only the misses were forwarded to the inner network
+    assert_eq!(network.inner().batch_sizes, vec![2, 2]);
+
+    // everything is cached now
+    let evals = network.evaluate_batch(&mixed);
+    assert_evals_match(&mixed, &evals);
+    assert_eq!(network.stats(), CacheStats { hits: 6, misses: 4 });
+    assert_eq!(network.stats().hit_rate(), 0.6);
+    assert_eq!(network.inner().batch_sizes, vec![2, 2]);
+
+    network.reset_stats();
+    assert_eq!(network.stats(), CacheStats::default());
+}
+
+#[test]
+fn clear_forgets_evals() {
+    let board = Connect4Board::default();
+    let mut network = CachedNetwork::new(RecordingNetwork::default(), 16, false);
+
+    network.evaluate(&board);
+    network.clear();
+    network.evaluate(&board);
+
+    assert_eq!(network.stats(), CacheStats { hits: 0, misses: 2 });
+    assert_eq!(network.inner().batch_sizes, vec![1, 1]);
+}
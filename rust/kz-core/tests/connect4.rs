@@ -0,0 +1,50 @@
+use board_game::board::{Board, Outcome, Player};
+use internal_iterator::InternalIterator;
+
+use kz_core::games::connect4::{Connect4Board, Connect4Move, HEIGHT};
+
+#[test]
+fn vertical_win() {
+    let board = Connect4Board::from_moves("1212121").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+}
+
+#[test]
+fn horizontal_win() {
+    let board = Connect4Board::from_moves("1122334").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+}
+
+#[test]
+fn diagonal_win() {
+    let board = Connect4Board::from_moves("12233434544").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+
+    let board = Connect4Board::from_moves("76655454344").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+}
+
+#[test]
+fn no_wrap_around() {
+    // the top of column 1 and the bottom of column 2 would be adjacent in the bitboard without padding
+    let board = Connect4Board::from_moves("112117161").unwrap();
+    assert_eq!(board.outcome(), None);
+}
+
+#[test]
+fn full_column() {
+    let board = Connect4Board::from_moves("121212343434").unwrap();
+    assert_eq!(board.column_height(0), 3);
+
+    let board = Connect4Board::from_moves("112211221122").unwrap();
+    assert_eq!(board.column_height(0), HEIGHT);
+    assert!(!board.is_available_move(Connect4Move::new(0)).unwrap());
+    assert_eq!(board.available_moves().unwrap().count(), 6);
+}
+
+#[test]
+fn parse_invalid() {
+    assert!(Connect4Board::from_moves("8").is_none());
+    assert!(Connect4Board::from_moves("0").is_none());
+    assert!(Connect4Board::from_moves("1111111").is_none());
+}
@@ -0,0 +1,101 @@
+use board_game::games::ttt::TTTBoard;
+use board_game::util::coord::Coord3;
+
+use kz_core::error::{NetworkError, ShapeError};
+use kz_core::mapping::ttt::TTTStdMapper;
+use kz_core::mapping::{AuxOutputSpec, PolicyMapper};
+use kz_core::network::common::try_decode_output;
+
+#[test]
+fn decode_valid() {
+    let boards = [TTTBoard::default()];
+    let scalars = [0.0, 0.0, 0.0, 0.0, f32::NAN];
+    let policy = [0.0; 9];
+
+    let evals = try_decode_output(TTTStdMapper, &boards, &[&scalars, &policy]).unwrap();
+    assert_eq!(evals.len(), 1);
+    assert_eq!(evals[0].policy.len(), 9);
+    assert!(evals[0].values.moves_left.is_nan());
+}
+
+#[test]
+fn decode_nan_policy() {
+    let boards = [TTTBoard::default()];
+    let scalars = [0.0; 5];
+    let mut policy = [0.0; 9];
+    policy[4] = f32::NAN;
+
+    let result = try_decode_output(TTTStdMapper, &boards, &[&scalars, &policy]);
+    match result {
+        Err(NetworkError::NanOutput(e)) => {
+            assert_eq!(e.batch_index, 0);
+            assert_eq!(e.output, "policy");
+        }
+        _ => panic!("Expected NaN error, got {:?}", result),
+    }
+}
+
+#[test]
+fn decode_wrong_output_count() {
+    let boards = [TTTBoard::default()];
+    let scalars = [0.0; 5];
+
+    let result = try_decode_output(TTTStdMapper, &boards, &[&scalars]);
+    assert!(matches!(
+        result,
+        Err(NetworkError::Shape(ShapeError::OutputCount { actual: 1 }))
+    ));
+}
+
+/// TTT policy mapper with an additional ownership head.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct AuxMapper;
+
+impl PolicyMapper<TTTBoard> for AuxMapper {
+    fn policy_mapper_id(&self) -> &'static str {
+        "ttt-aux-test"
+    }
+
+    fn policy_shape(&self) -> &[usize] {
+        &[9]
+    }
+
+    fn move_to_index(&self, board: &TTTBoard, mv: Coord3) -> usize {
+        TTTStdMapper.move_to_index(board, mv)
+    }
+
+    fn index_to_move(&self, board: &TTTBoard, index: usize) -> Option<Coord3> {
+        TTTStdMapper.index_to_move(board, index)
+    }
+
+    fn aux_outputs(&self) -> Vec<AuxOutputSpec> {
+        vec![AuxOutputSpec::new("ownership", vec![3, 3])]
+    }
+}
+
+#[test]
+fn decode_aux() {
+    let boards = [TTTBoard::default(), TTTBoard::default()];
+    let scalars = [0.0; 10];
+    let policy = [0.0; 18];
+    let ownership = (0..18).map(|i| i as f32).collect::<Vec<_>>();
+
+    // without the auxiliary outputs
+    let evals = try_decode_output(AuxMapper, &boards, &[&scalars, &policy]).unwrap();
+    assert!(evals[0].aux.is_none());
+
+    // with the auxiliary outputs
+    let evals = try_decode_output(AuxMapper, &boards, &[&scalars, &policy, &ownership]).unwrap();
+    let aux = evals[1].aux.as_ref().unwrap();
+    assert_eq!(aux["ownership"], ownership[9..]);
+
+    // wrong auxiliary shape
+    let result = try_decode_output(AuxMapper, &boards, &[&scalars, &policy, &ownership[..9]]);
+    assert!(matches!(
+        result,
+        Err(NetworkError::Shape(ShapeError::Output {
+            output: "ownership",
+            ..
+        }))
+    ));
+}
@@ -0,0 +1,110 @@
+use std::borrow::Borrow;
+
+use board_game::pov::ScalarPov;
+
+use kz_core::games::connect4::Connect4Board;
+use kz_core::network::ensemble::{EnsembleNetwork, EnsemblePolicyMode};
+use kz_core::network::{Network, ZeroEvaluation};
+use kz_core::zero::values::ZeroValuesPov;
+
+/// Network that returns the same evaluation for every board.
+#[derive(Debug, Clone)]
+struct ConstNetwork {
+    value: f32,
+    policy: Vec<f32>,
+}
+
+impl ConstNetwork {
+    fn new(value: f32, policy: &[f32]) -> Self {
+        ConstNetwork {
+            value,
+            policy: policy.to_vec(),
+        }
+    }
+}
+
+impl Network<Connect4Board> for ConstNetwork {
+    fn max_batch_size(&self) -> usize {
+        16
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<Connect4Board>]) -> Vec<ZeroEvaluation<'static>> {
+        let eval = ZeroEvaluation {
+            values: ZeroValuesPov {
+                value: ScalarPov::new(self.value),
+                ..Default::default()
+            },
+            policy: self.policy.clone().into(),
+            aux: None,
+        };
+        vec![eval; boards.len()]
+    }
+}
+
+const FIRST: [f32; 7] = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+const SECOND: [f32; 7] = [0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-5,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+fn disjoint_ensemble(mode: EnsemblePolicyMode) -> EnsembleNetwork<ConstNetwork> {
+    EnsembleNetwork::new(
+        vec![
+            (1.0, ConstNetwork::new(1.0, &FIRST)),
+            (3.0, ConstNetwork::new(-1.0, &SECOND)),
+        ],
+        mode,
+    )
+}
+
+#[test]
+fn weighted_average() {
+    let mut network = disjoint_ensemble(EnsemblePolicyMode::Average);
+    let (evals, disagreements) = network.evaluate_batch_disagreement::<Connect4Board>(&[Connect4Board::default()]);
+
+    let eval = &evals[0];
+    assert_close(eval.values.value.value, -0.5);
+    assert_close(eval.policy[0], 0.25);
+    assert_close(eval.policy[1], 0.75);
+    assert!(eval.policy[2..].iter().all(|&p| p == 0.0));
+
+    // sqrt((1 * 1.5^2 + 3 * 0.5^2) / 4)
+    let disagreement = disagreements[0];
+    assert_close(disagreement.value_std, 0.75f32.sqrt());
+    assert!(disagreement.policy_kl_mean.is_finite() && disagreement.policy_kl_mean > 0.0);
+    assert!(disagreement.policy_kl_max.is_finite() && disagreement.policy_kl_max >= disagreement.policy_kl_mean);
+}
+
+#[test]
+fn product_of_experts_zero_probabilities() {
+    let mut network = disjoint_ensemble(EnsemblePolicyMode::ProductOfExperts);
+    let eval = network.evaluate(&Connect4Board::default());
+
+    // the members have no move in common, the heavier member still wins
+    assert!(eval.policy.iter().all(|p| p.is_finite()));
+    assert_close(eval.policy.iter().sum(), 1.0);
+    assert!(eval.policy[1] > eval.policy[0]);
+    assert!(eval.policy[0] > eval.policy[2]);
+}
+
+#[test]
+fn identical_members_agree() {
+    let policy = [0.5, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0];
+    let member = ConstNetwork::new(0.2, &policy);
+    let mut network = EnsembleNetwork::uniform(vec![member.clone(), member], EnsemblePolicyMode::ProductOfExperts);
+
+    let (evals, disagreements) = network.evaluate_batch_disagreement::<Connect4Board>(&[Connect4Board::default()]);
+
+    assert_close(evals[0].values.value.value, 0.2);
+    for (&actual, &expected) in evals[0].policy.iter().zip(&policy) {
+        assert_close(actual, expected);
+    }
+    assert_close(disagreements[0].value_std, 0.0);
+    assert_close(disagreements[0].policy_kl_max, 0.0);
+}
@@ -0,0 +1,89 @@
+use board_game::board::{Board, Outcome, Player};
+use internal_iterator::InternalIterator;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::games::hex::{HexBoard, HexMove, MAX_SIZE};
+use kz_util::game::HEX_MAX_SIZE;
+
+#[test]
+fn swap_only_second_move() {
+    let board = HexBoard::new(3);
+    assert!(!board.can_swap());
+    assert!(!board.is_available_move(HexMove::Swap).unwrap());
+
+    let board = HexBoard::from_moves(3, "b1").unwrap();
+    assert!(board.can_swap());
+    assert_eq!(board.available_moves().unwrap().count(), 1 + 8);
+
+    let board = HexBoard::from_moves(3, "b1 a1").unwrap();
+    assert!(!board.can_swap());
+}
+
+#[test]
+fn swap_mirrors_stone() {
+    let board = HexBoard::from_moves(3, "b1 swap").unwrap();
+
+    assert_eq!(board.tile(1, 0), None);
+    assert_eq!(board.tile(0, 1), Some(Player::B));
+    assert_eq!(board.next_player(), Player::A);
+    assert!(!board.can_swap());
+    assert_eq!(board.available_moves().unwrap().count(), 8);
+}
+
+#[test]
+fn win_top_bottom() {
+    let board = HexBoard::from_moves(3, "a1 c1 a2 c2 a3").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+}
+
+#[test]
+fn win_left_right_diagonal() {
+    // (0, 2), (1, 1) and (2, 0) are neighbors on the rhombus
+    let board = HexBoard::from_moves(3, "a1 a3 c3 b2 a2 c1").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::B)));
+}
+
+#[test]
+fn other_diagonal_not_connected() {
+    // (0, 0) and (1, 1) are not neighbors
+    let board = HexBoard::from_moves(3, "a1 c1 b2 c2 b3").unwrap();
+    assert_eq!(board.outcome(), None);
+}
+
+#[test]
+fn random_games_have_winner() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for size in [1, 2, 5, 11] {
+        for _ in 0..20 {
+            let mut board = HexBoard::new(size);
+            while !board.is_done() {
+                board.play(board.random_available_move(&mut rng).unwrap()).unwrap();
+            }
+            assert!(matches!(board.outcome(), Some(Outcome::WonBy(_))), "{}", board);
+        }
+    }
+}
+
+#[test]
+fn move_roundtrip() {
+    for mv in [HexMove::Swap, HexMove::Place(0, 0), HexMove::Place(3, 10)] {
+        assert_eq!(mv.to_string().parse(), Ok(mv));
+    }
+    assert_eq!(HexMove::Place(3, 10).to_string(), "d11");
+}
+
+#[test]
+fn parse_invalid() {
+    assert!(HexBoard::from_moves(3, "d1").is_none());
+    assert!(HexBoard::from_moves(3, "a0").is_none());
+    assert!(HexBoard::from_moves(3, "swap").is_none());
+    assert!(HexBoard::from_moves(3, "a1 swap swap").is_none());
+    assert!(HexBoard::from_moves(3, "a1 a1").is_none());
+}
+
+#[test]
+fn max_size_matches_game() {
+    assert_eq!(MAX_SIZE, HEX_MAX_SIZE);
+}
@@ -2,7 +2,10 @@ use board_game::board::Board;
 use board_game::chess::{ChessMove, Piece, Square};
 use board_game::games::chess::{ChessBoard, Rules};
 
-use kz_core::mapping::chess::{ChessHistoryMapper, ChessLegacyConvPolicyMapper, ChessStdMapper, ClassifiedPovMove};
+use kz_core::mapping::chess::{
+    generate_all_flat_moves_pov, ChessAttPolicyMapper, ChessHistoryMapper, ChessLegacyConvPolicyMapper, ChessStdMapper,
+    ClassifiedPovMove,
+};
 use kz_core::mapping::PolicyMapper;
 use kz_util::display::display_option;
 
@@ -355,6 +358,20 @@ fn castles() {
     );
 }
 
+#[test]
+fn att_flat_moves() {
+    for mv in generate_all_flat_moves_pov() {
+        let index = ChessAttPolicyMapper::pov_move_to_index(mv);
+        let moving_pawn = mv.get_promotion().is_some();
+        assert_eq!(
+            ChessAttPolicyMapper::index_to_pov_move(index, moving_pawn),
+            Some(mv),
+            "Failed roundtrip for {}",
+            mv
+        );
+    }
+}
+
 fn board(fen: &str) -> ChessBoard {
     ChessBoard::new_without_history_fen(fen, Rules::default())
 }
@@ -363,6 +380,7 @@ fn test_pairs(board: &ChessBoard, pairs: &[(usize, Option<ChessMove>)]) {
     // test other mapper with a variety of chess boards
     test_valid_mapping(ChessStdMapper, board);
     test_valid_policy_mapping(ChessLegacyConvPolicyMapper, board);
+    test_valid_policy_mapping(ChessAttPolicyMapper, board);
     for length in [0, 1, 8] {
         test_valid_mapping(ChessHistoryMapper::new(length), board);
     }
@@ -3,7 +3,7 @@ use board_game::games::chess::ChessBoard;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
-use kz_core::mapping::chess::{ChessHistoryMapper, ChessLegacyConvPolicyMapper, ChessStdMapper};
+use kz_core::mapping::chess::{ChessAttPolicyMapper, ChessHistoryMapper, ChessLegacyConvPolicyMapper, ChessStdMapper};
 
 use crate::mapper::{test_valid_mapping, test_valid_policy_mapping};
 
@@ -27,6 +27,12 @@ fn conv() {
     random_impl(|board| test_valid_policy_mapping(ChessLegacyConvPolicyMapper, board));
 }
 
+#[test]
+#[ignore]
+fn att() {
+    random_impl(|board| test_valid_policy_mapping(ChessAttPolicyMapper, board));
+}
+
 fn random_impl(f: impl Fn(&ChessBoard)) {
     let mut rng = StdRng::seed_from_u64(0);
 
@@ -0,0 +1,28 @@
+use board_game::board::Board;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::games::connect4::Connect4Board;
+use kz_core::mapping::connect4::Connect4StdMapper;
+
+use crate::mapper::test_valid_mapping;
+
+#[test]
+fn start() {
+    test_valid_mapping(Connect4StdMapper, &Connect4Board::default());
+}
+
+#[test]
+fn random_games() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..100 {
+        let mut board = Connect4Board::default();
+        test_valid_mapping(Connect4StdMapper, &board);
+
+        while !board.is_done() {
+            board.play(board.random_available_move(&mut rng).unwrap()).unwrap();
+            test_valid_mapping(Connect4StdMapper, &board);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use board_game::board::Board;
+
+use kz_core::mapping::export::{export_mapping, MappingExport};
+use kz_core::mapping::BoardMapper;
+use kz_core::registry::{visit_game_str, GameEntry, GameVisitor};
+use kz_util::game::Game;
+
+struct ExportVisitor;
+
+impl GameVisitor for ExportVisitor {
+    type Output = MappingExport;
+
+    fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> MappingExport {
+        export_mapping(&entry.game.to_string(), entry.mapper, &entry.example_boards())
+    }
+}
+
+fn export(game: &str) -> MappingExport {
+    visit_game_str(game, ExportVisitor).unwrap()
+}
+
+#[test]
+fn registered_games() {
+    for game in Game::examples() {
+        let game = game.to_string();
+        println!("Exporting {}", game);
+        let export = export(&game);
+
+        assert_eq!(export.game, game);
+        assert_eq!(export.input_bool_names.len(), export.input_bool_shape[0]);
+        let names: HashSet<&String> = export
+            .input_bool_names
+            .iter()
+            .chain(&export.input_scalar_names)
+            .collect();
+        assert_eq!(
+            names.len(),
+            export.input_bool_names.len() + export.input_scalar_names.len(),
+            "Duplicate input names"
+        );
+
+        let policy_len: usize = export.policy_shape.iter().product();
+        assert!(!export.boards.is_empty());
+
+        for board in &export.boards {
+            assert_eq!(board.index_to_move.len(), policy_len);
+
+            for sym in &board.symmetries {
+                assert_eq!(sym.map_mv.len(), policy_len);
+
+                let mapped: HashSet<isize> = sym.map_mv.iter().copied().filter(|&i| i != -1).collect();
+                let valid = sym.map_mv.iter().filter(|&&i| i != -1).count();
+                assert_eq!(mapped.len(), valid, "Symmetry {} is not a permutation", sym.name);
+                assert!(mapped.iter().all(|&i| 0 <= i && (i as usize) < policy_len));
+
+                for (i, mv) in board.index_to_move.iter().enumerate() {
+                    assert_eq!(mv.is_some(), sym.map_mv[i] != -1);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn othello() {
+    let export = export("othello");
+    assert_eq!(export.input_bool_names, vec!["stones_us", "stones_them", "placeable"]);
+    assert!(export.input_scalar_names.is_empty());
+    let board = &export.boards[0];
+    assert_eq!(board.index_to_move[0].as_deref(), Some("pass"));
+    assert_eq!(board.index_to_move[1].as_deref(), Some("a1"));
+
+    // all 8 symmetries, including the identity
+    assert_eq!(board.symmetries.len(), 8);
+    assert!(board
+        .symmetries
+        .iter()
+        .any(|sym| sym.map_mv.iter().enumerate().all(|(i, &j)| i as isize == j)));
+}
+
+#[test]
+fn hex() {
+    let export = export("hex-11");
+    assert_eq!(export.input_scalar_names, vec!["can_swap"]);
+    let names: Vec<&str> = export.boards.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, vec!["default", "default+1"]);
+
+    for board in &export.boards {
+        assert_eq!(board.index_to_move[0].as_deref(), Some("swap"));
+        assert_eq!(board.index_to_move[1].as_deref(), Some("a1"));
+        assert!(board.symmetries.is_empty());
+    }
+
+    // moves are encoded from the POV of the next player
+    assert_ne!(export.boards[0].index_to_move[2], export.boards[1].index_to_move[2]);
+}
+
+#[test]
+fn go_sizes() {
+    let export = export("go-9..19");
+    assert_eq!(export.boards.len(), 2 * (19 - 9 + 1));
+    assert_eq!(export.boards[0].name, "9x9");
+    assert_eq!(export.boards.last().unwrap().name, "19x19+1");
+
+    // smaller boards don't have moves for the padding tiles, including under symmetries
+    for board in export.boards.iter().filter(|b| b.name.starts_with("9x9")) {
+        let moves = board.index_to_move.iter().filter(|mv| mv.is_some()).count();
+        assert_eq!(moves, 1 + 9 * 9, "Board {}", board.name);
+    }
+    let board_19 = export.boards.iter().find(|b| b.name == "19x19").unwrap();
+    assert!(board_19.index_to_move.iter().all(|mv| mv.is_some()));
+}
+
+#[test]
+fn history() {
+    let export = export("ataxx-7-hist-2");
+    assert_eq!(export.input_bool_names.len(), 3 * 3);
+    assert_eq!(export.input_bool_names[3], "tiles_us_prev_1");
+    assert_eq!(export.input_scalar_names.last().unwrap(), "prev_2_present");
+}
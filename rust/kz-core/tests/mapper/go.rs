@@ -1,11 +1,14 @@
 use crate::mapper::test_valid_mapping;
 use board_game::games::go::{GoBoard, Komi, Rules};
 use kz_core::mapping::go::GoStdMapper;
+use kz_core::mapping::InputMapper;
 
 #[test]
 fn go_empty_basic() {
     let board = GoBoard::new(9, Komi::zero(), Rules::tromp_taylor());
     let mapper = GoStdMapper::new(board.size(), false);
+    assert_eq!(mapper.input_mapper_id(), "go-std-v1");
+    assert_eq!(mapper.input_bool_shape()[0], 4);
     test_valid_mapping(mapper, &board);
 }
 
@@ -13,5 +16,7 @@ fn go_empty_basic() {
 fn go_empty_territory() {
     let board = GoBoard::new(9, Komi::zero(), Rules::tromp_taylor());
     let mapper = GoStdMapper::new(board.size(), true);
+    assert_eq!(mapper.input_mapper_id(), "go-std-territory-v1");
+    assert_eq!(mapper.input_bool_shape()[0], 7);
     test_valid_mapping(mapper, &board);
 }
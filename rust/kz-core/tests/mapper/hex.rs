@@ -0,0 +1,45 @@
+use board_game::board::Board;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::games::hex::{HexBoard, HexMove};
+use kz_core::mapping::hex::HexStdMapper;
+use kz_core::mapping::PolicyMapper;
+
+use crate::mapper::test_valid_mapping;
+
+#[test]
+fn swap_available() {
+    let board = HexBoard::from_moves(5, "c2").unwrap();
+    test_valid_mapping(HexStdMapper::new(5), &board);
+}
+
+#[test]
+fn pov_transposed() {
+    let mapper = HexStdMapper::new(5);
+    let board_a = HexBoard::new(5);
+    let board_b = HexBoard::from_moves(5, "a1").unwrap();
+
+    let index_a = mapper.move_to_index(&board_a, HexMove::Place(1, 3));
+    let index_b = mapper.move_to_index(&board_b, HexMove::Place(3, 1));
+    assert_eq!(index_a, index_b);
+}
+
+#[test]
+fn random_games() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for size in [1, 5, 11] {
+        let mapper = HexStdMapper::new(size);
+
+        for _ in 0..10 {
+            let mut board = HexBoard::new(size);
+            test_valid_mapping(mapper, &board);
+
+            while !board.is_done() {
+                board.play(board.random_available_move(&mut rng).unwrap()).unwrap();
+                test_valid_mapping(mapper, &board);
+            }
+        }
+    }
+}
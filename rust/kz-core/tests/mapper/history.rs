@@ -0,0 +1,82 @@
+use board_game::board::{Board, BoardMoves};
+use board_game::games::ataxx::AtaxxBoard;
+use internal_iterator::InternalIterator;
+
+use kz_core::mapping::ataxx::AtaxxStdMapper;
+use kz_core::mapping::bit_buffer::BitBuffer;
+use kz_core::mapping::history::{HistoryBoard, HistoryMapper};
+use kz_core::mapping::InputMapper;
+
+use crate::mapper::test_valid_mapping;
+
+#[test]
+fn ataxx_history_game() {
+    let length = 2;
+    let mapper = HistoryMapper::new(AtaxxStdMapper::new(7), length);
+    let mut board = HistoryBoard::new(AtaxxBoard::diagonal(7), length);
+
+    for i in 0..4 {
+        test_valid_mapping(mapper, &board);
+        assert_eq!(board.history().count(), i.min(length));
+
+        // the present flags are the last scalars
+        let mut bools = BitBuffer::new(mapper.input_bool_len());
+        let mut scalars = vec![];
+        mapper.encode_input(&mut bools, &mut scalars, &board);
+        let present = &scalars[scalars.len() - length..];
+        let expected = (0..length).map(|h| (h < i) as u8 as f32).collect::<Vec<_>>();
+        assert_eq!(present, expected);
+
+        let mv = board.available_moves().unwrap().next().unwrap();
+        board.play(mv).unwrap();
+    }
+}
+
+#[test]
+fn ataxx_history_planes() {
+    let length = 2;
+    let inner = AtaxxStdMapper::new(7);
+    let mapper = HistoryMapper::new(inner, length);
+    let mut board = HistoryBoard::new(AtaxxBoard::diagonal(7), length);
+
+    // previous positions tracked independently of the board, most recent first
+    let mut prev_boards: Vec<AtaxxBoard> = vec![];
+
+    for _ in 0..4 {
+        let mut bools = BitBuffer::new(mapper.input_bool_len());
+        let mut scalars = vec![];
+        mapper.encode_input(&mut bools, &mut scalars, &board);
+
+        // the inner encoding of the current board followed by the previous boards, zero padded
+        let mut expected_bools = BitBuffer::new(mapper.input_bool_len());
+        let mut expected_scalars = vec![];
+        inner.encode_input(&mut expected_bools, &mut expected_scalars, board.current());
+        for i in 0..length {
+            match prev_boards.get(i) {
+                Some(prev) => inner.encode_input(&mut expected_bools, &mut expected_scalars, prev),
+                None => {
+                    for _ in 0..inner.input_bool_len() {
+                        expected_bools.push(false);
+                    }
+                    expected_scalars.extend(std::iter::repeat(0.0).take(inner.input_scalar_count()));
+                }
+            }
+        }
+        expected_scalars.extend((0..length).map(|i| (i < prev_boards.len()) as u8 as f32));
+
+        assert_eq!(bools.len(), expected_bools.len());
+        assert_eq!(bools.storage(), expected_bools.storage());
+        assert_eq!(scalars, expected_scalars);
+
+        prev_boards.insert(0, board.current().clone());
+        let mv = board.available_moves().unwrap().next().unwrap();
+        board.play(mv).unwrap();
+    }
+}
+
+#[test]
+fn history_mapper_id() {
+    let id = HistoryMapper::new(AtaxxStdMapper::new(7), 2).input_mapper_id();
+    assert_eq!(id, format!("hist-v1-{}-2", AtaxxStdMapper::new(7).input_mapper_id()));
+    assert_ne!(id, HistoryMapper::new(AtaxxStdMapper::new(7), 3).input_mapper_id());
+}
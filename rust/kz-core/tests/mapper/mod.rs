@@ -8,7 +8,14 @@ use kz_util::display::display_option;
 mod arimaa;
 mod ataxx;
 mod chess;
+mod connect4;
+mod export;
 mod go;
+mod hex;
+mod history;
+mod othello;
+mod registry;
+mod trictrac;
 
 pub fn test_valid_mapping<B: Board, M: BoardMapper<B>>(mapper: M, board: &B) {
     if !board.is_done() {
@@ -24,6 +31,9 @@ pub fn test_valid_input_mapping<B: Board, M: InputMapper<B>>(mapper: M, board: &
     mapper.encode_input(&mut bools, &mut scalars, board);
     assert_eq!(bools.len(), mapper.input_bool_len());
     assert_eq!(scalars.len(), mapper.input_scalar_count());
+
+    assert_eq!(mapper.input_bool_names().len(), mapper.input_bool_shape()[0]);
+    assert_eq!(mapper.input_scalar_names().len(), mapper.input_scalar_count());
 }
 
 pub fn test_valid_policy_mapping<B: Board, M: PolicyMapper<B>>(mapper: M, board: &B) {
@@ -0,0 +1,34 @@
+use board_game::board::Board;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::games::othello::OthelloBoard;
+use kz_core::mapping::othello::OthelloStdMapper;
+
+use crate::mapper::test_valid_mapping;
+
+#[test]
+fn start() {
+    test_valid_mapping(OthelloStdMapper, &OthelloBoard::default());
+}
+
+#[test]
+fn forced_pass() {
+    let board = OthelloBoard::from_fen("8/8/8/8/8/8/8/xoo5 o").unwrap();
+    test_valid_mapping(OthelloStdMapper, &board);
+}
+
+#[test]
+fn random_games() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..20 {
+        let mut board = OthelloBoard::default();
+        test_valid_mapping(OthelloStdMapper, &board);
+
+        while !board.is_done() {
+            board.play(board.random_available_move(&mut rng).unwrap()).unwrap();
+            test_valid_mapping(OthelloStdMapper, &board);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::hash::Hash;
+
+use board_game::board::{AltBoard, Board};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::games::hex::HexBoard;
+use kz_core::mapping::BoardMapper;
+use kz_core::registry::{visit_game, visit_game_str, GameEntry, GameVisitor};
+use kz_util::game::Game;
+
+use crate::mapper::test_valid_mapping;
+
+/// Use the entry like the binaries do: selfplay start positions, default board and random games.
+struct TestVisitor<'a> {
+    name: &'a str,
+}
+
+impl GameVisitor for TestVisitor<'_> {
+    type Output = bool;
+
+    fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> bool {
+        assert_eq!(entry.game.to_string(), self.name);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let start_pos = entry.start_pos("default");
+
+        for mut board in [entry.default_board(), start_pos(&mut rng)] {
+            test_valid_mapping(entry.mapper, &board);
+
+            for _ in 0..20 {
+                if board.is_done() {
+                    break;
+                }
+                board.play(board.random_available_move(&mut rng).unwrap()).unwrap();
+                test_valid_mapping(entry.mapper, &board);
+            }
+        }
+
+        false
+    }
+
+    fn visit_alt<B: AltBoard + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> bool {
+        self.visit(entry);
+        true
+    }
+}
+
+#[test]
+fn registered_games() {
+    for game in Game::examples() {
+        let name = game.to_string();
+        println!("Testing game {}", name);
+
+        visit_game(game, TestVisitor { name: &name });
+    }
+}
+
+#[test]
+fn alternating() {
+    let alt = |name| visit_game_str(name, TestVisitor { name }).unwrap();
+
+    assert!(alt("ttt"));
+    assert!(alt("ataxx-7-hist-2"));
+    assert!(!alt("trictrac"));
+    assert!(!alt("go-9"));
+}
+
+#[test]
+fn unknown_game() {
+    assert!(visit_game_str("not-a-game", TestVisitor { name: "" }).is_err());
+}
+
+#[test]
+#[should_panic]
+fn unknown_start_pos() {
+    struct StartPosVisitor;
+
+    impl GameVisitor for StartPosVisitor {
+        type Output = ();
+
+        fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) {
+            entry.start_pos("random-v1");
+        }
+    }
+
+    visit_game_str("connect4", StartPosVisitor).unwrap();
+}
+
+#[test]
+fn fen() {
+    struct FenVisitor;
+
+    impl GameVisitor for FenVisitor {
+        type Output = String;
+
+        fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> String {
+            assert!(entry.supports_fen());
+            entry.from_fen("c3 swap").to_string()
+        }
+    }
+
+    let expected = HexBoard::from_moves(5, "c3 swap").unwrap().to_string();
+    assert_eq!(visit_game_str("hex-5", FenVisitor).unwrap(), expected);
+}
@@ -0,0 +1,103 @@
+use board_game::board::Board;
+use internal_iterator::InternalIterator;
+use trictrac_bot::training_common::TrictracAction;
+use trictrac_bot::trictrac_board::TrictracBoard;
+
+use kz_core::mapping::bit_buffer::BitBuffer;
+use kz_core::mapping::trictrac::{TrictracStdMapper, TrictracV2Mapper};
+use kz_core::mapping::{InputMapper, MuZeroMapper, PolicyMapper};
+
+use crate::mapper::test_valid_mapping;
+
+const FIELD_COUNT: usize = 24;
+
+#[test]
+fn game() {
+    // play the first available move a bunch of times, which covers all turn stages
+    let mut board = TrictracBoard::default();
+
+    for _ in 0..200 {
+        if board.is_done() {
+            break;
+        }
+
+        test_valid_mapping(TrictracStdMapper, &board);
+        test_valid_mapping(TrictracV2Mapper, &board);
+        test_encode_mv(&board);
+
+        let mv = board.available_moves().unwrap().next().unwrap();
+        board.play(mv).unwrap();
+    }
+}
+
+fn test_encode_mv(board: &TrictracBoard) {
+    let mapper = TrictracStdMapper;
+
+    let mut bools = BitBuffer::new(TrictracV2Mapper.input_bool_len());
+    let mut scalars = vec![];
+    TrictracV2Mapper.encode_input(&mut bools, &mut scalars, board);
+    let movable_from = 2 * 15 * FIELD_COUNT;
+    let movable_to = movable_from + FIELD_COUNT;
+
+    board.available_moves().unwrap().for_each(|mv| {
+        let index = mapper.move_to_index(board, mv);
+
+        let mut encoded = vec![];
+        mapper.encode_mv(&mut encoded, index);
+        assert_eq!(encoded.len(), mapper.encoded_mv_len());
+
+        // the board-dependent encoding starts with the board-independent one
+        let mut encoded_on_board = vec![];
+        mapper.encode_mv_on_board(&mut encoded_on_board, board, index);
+        assert_eq!(
+            encoded_on_board.len(),
+            mapper.encoded_move_on_board_shape().iter().product::<usize>()
+        );
+        assert_eq!(encoded, encoded_on_board[..encoded.len()]);
+
+        let plane = |i: usize| &encoded_on_board[i * FIELD_COUNT..(i + 1) * FIELD_COUNT];
+        let constant = |i: usize, value: f32| plane(i).iter().all(|&x| x == value);
+        let one_hot = |i: usize, index: usize| (0..FIELD_COUNT).all(|j| plane(i)[j] == (j == index) as u8 as f32);
+
+        // same layout as `encode_trictrac_mv` in python
+        match mv {
+            TrictracAction::Roll => {
+                assert!(constant(0, 1.0) && constant(1, 0.0));
+                assert!((2..7).all(|i| constant(i, 0.0)));
+            }
+            TrictracAction::Go => {
+                assert!(constant(0, 0.0) && constant(1, 1.0));
+                assert!((2..7).all(|i| constant(i, 0.0)));
+            }
+            TrictracAction::Move {
+                dice_order,
+                checker1,
+                checker2,
+            } => {
+                assert!(constant(0, 0.0) && constant(1, 0.0));
+                assert!(constant(2, ((index - 2) / (16 * 16)) as f32));
+                assert_eq!(dice_order, (index - 2) / (16 * 16) == 1);
+
+                assert!(one_hot(3, checker1) && one_hot(4, checker2));
+                assert_eq!(checker1, (index - 2) / 16 % 16);
+                assert_eq!(checker2, (index - 2) % 16);
+
+                // at most two checkers move, and they can only disappear by being borne off
+                let from = plane(5).iter().sum::<f32>();
+                let to = plane(6).iter().sum::<f32>();
+                assert!(
+                    to <= from && from <= 2.0,
+                    "Invalid fields for {}: {:?}",
+                    mv,
+                    encoded_on_board
+                );
+
+                // the fields must also show up in the movable input planes
+                for i in 0..FIELD_COUNT {
+                    assert!(plane(5)[i] == 0.0 || bools[movable_from + i]);
+                    assert!(plane(6)[i] == 0.0 || bools[movable_to + i]);
+                }
+            }
+        }
+    });
+}
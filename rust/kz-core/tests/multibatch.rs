@@ -0,0 +1,51 @@
+use std::borrow::Borrow;
+
+use board_game::games::ttt::TTTBoard;
+
+use kz_core::network::dummy::DummyNetwork;
+use kz_core::network::multibatch::MultiBatchNetwork;
+use kz_core::network::{Network, ZeroEvaluation};
+
+/// Network that only accepts batches up to a fixed size.
+#[derive(Debug)]
+struct FixedNetwork {
+    size: usize,
+}
+
+impl Network<TTTBoard> for FixedNetwork {
+    fn max_batch_size(&self) -> usize {
+        self.size
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<TTTBoard>]) -> Vec<ZeroEvaluation<'static>> {
+        assert!(boards.len() <= self.size);
+        Network::<TTTBoard>::evaluate_batch(&mut DummyNetwork, boards)
+    }
+}
+
+fn build(sizes: &[usize]) -> MultiBatchNetwork<FixedNetwork> {
+    MultiBatchNetwork::build_sizes(sizes, |size| FixedNetwork { size })
+}
+
+#[test]
+fn chunk_sizes_minimize_padding() {
+    let network = build(&[4, 16]);
+
+    assert_eq!(network.chunk_sizes(0), Vec::<usize>::new());
+    assert_eq!(network.chunk_sizes(3), vec![3]);
+    assert_eq!(network.chunk_sizes(16), vec![16]);
+    assert_eq!(network.chunk_sizes(8), vec![4, 4]);
+    assert_eq!(network.chunk_sizes(14), vec![14]);
+    assert_eq!(network.chunk_sizes(36), vec![16, 16, 4]);
+}
+
+#[test]
+fn split_oversized_batch() {
+    let mut network = build(&[4, 16]);
+
+    let boards = vec![TTTBoard::default(); 37];
+    let evals = network.evaluate_batch(&boards);
+
+    assert_eq!(evals.len(), boards.len());
+    assert!(evals.iter().all(|eval| eval.policy.len() == 9));
+}
@@ -0,0 +1,88 @@
+use board_game::board::{Board, BoardSymmetry, Outcome, Player};
+use board_game::symmetry::{D4Symmetry, Symmetry};
+use internal_iterator::InternalIterator;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::games::othello::{OthelloBoard, OthelloMove};
+
+fn play_moves(moves: &str) -> OthelloBoard {
+    let mut board = OthelloBoard::default();
+    for mv in moves.split_whitespace() {
+        board.play(mv.parse().unwrap()).unwrap();
+    }
+    board
+}
+
+fn available_moves(board: &OthelloBoard) -> Vec<String> {
+    let mut moves: Vec<String> = board.available_moves().unwrap().map(|mv| mv.to_string()).collect();
+    moves.sort();
+    moves
+}
+
+#[test]
+fn start() {
+    let board = OthelloBoard::default();
+    assert_eq!(board.next_player(), Player::A);
+    assert_eq!(available_moves(&board), vec!["c4", "d3", "e6", "f5"]);
+}
+
+#[test]
+fn shortest_win() {
+    let board = play_moves("e6 f4 e3 f6 g5 d6 e7 f5 c5");
+    assert_eq!(board.outcome(), Some(Outcome::WonBy(Player::A)));
+    assert_eq!(board.tiles(Player::A).count_ones(), 13);
+    assert_eq!(board.tiles(Player::B), 0);
+}
+
+#[test]
+fn forced_pass() {
+    // B has no moves but A does, so B has to pass
+    let board = OthelloBoard::from_fen("8/8/8/8/8/8/8/xoo5 o").unwrap();
+    assert_eq!(board.outcome(), None);
+    assert!(board.must_pass());
+    assert_eq!(available_moves(&board), vec!["pass"]);
+
+    let mut board = board;
+    board.play(OthelloMove::Pass).unwrap();
+    assert_eq!(board.next_player(), Player::A);
+    assert_eq!(available_moves(&board), vec!["d1"]);
+}
+
+#[test]
+fn fen() {
+    let board = OthelloBoard::from_fen("8/8/8/3xo3/3ox3/8/8/8 x").unwrap();
+    assert_eq!(board, OthelloBoard::default());
+
+    assert!(OthelloBoard::from_fen("8/8/8/3xo3/3ox3/8/8 x").is_none());
+    assert!(OthelloBoard::from_fen("8/8/8/3xo3/3ox4/8/8/8 x").is_none());
+    assert!(OthelloBoard::from_fen("8/8/8/3xo3/3ox3/8/8/8 y").is_none());
+}
+
+#[test]
+fn symmetry_maps_moves() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..20 {
+        let mut board = OthelloBoard::default();
+
+        while !board.is_done() {
+            for &sym in D4Symmetry::all() {
+                let mapped = board.map(sym);
+
+                let mut expected: Vec<OthelloMove> = board
+                    .available_moves()
+                    .unwrap()
+                    .map(|mv| board.map_move(sym, mv))
+                    .collect();
+                let mut actual: Vec<OthelloMove> = mapped.available_moves().unwrap().collect();
+                expected.sort();
+                actual.sort();
+
+                assert_eq!(expected, actual);
+            }
+
+            board.play(board.random_available_move(&mut rng).unwrap()).unwrap();
+        }
+    }
+}
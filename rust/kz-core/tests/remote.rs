@@ -0,0 +1,38 @@
+use std::io::Write;
+use std::net::TcpListener;
+
+use board_game::games::ttt::TTTBoard;
+
+use kz_core::error::NetworkError;
+use kz_core::mapping::ttt::TTTStdMapper;
+use kz_core::network::remote::{read_u32, write_u32, RemoteNetwork, REMOTE_MAGIC, REMOTE_STATUS_OK};
+use kz_core::network::Network;
+
+#[test]
+fn remote_disconnect_is_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // fake server that accepts the handshake and then immediately disconnects
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        assert_eq!(read_u32(&mut stream).unwrap(), REMOTE_MAGIC);
+        let rank = read_u32(&mut stream).unwrap();
+        for _ in 0..rank {
+            read_u32(&mut stream).unwrap();
+        }
+        write_u32(&mut stream, REMOTE_STATUS_OK).unwrap();
+        write_u32(&mut stream, 8).unwrap();
+        stream.flush().unwrap();
+    });
+
+    let mut network = RemoteNetwork::connect(TTTStdMapper, addr).unwrap();
+    server.join().unwrap();
+
+    let result = network.try_evaluate_batch(&[TTTBoard::default()]);
+    assert!(
+        matches!(result, Err(NetworkError::Io(_))),
+        "Expected IO error, got {:?}",
+        result
+    );
+}
@@ -0,0 +1,107 @@
+use std::borrow::Borrow;
+use std::sync::Arc;
+
+use board_game::board::{Board, BoardMoves};
+use board_game::games::go::{GoBoard, Komi, Rules};
+use board_game::games::sttt::STTTBoard;
+use board_game::pov::ScalarPov;
+use internal_iterator::InternalIterator;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::mapping::go::GoStdMapper;
+use kz_core::mapping::sttt::STTTStdMapper;
+use kz_core::mapping::{PolicyMapper, SCORE_LEAD_OUTPUT};
+use kz_core::network::dummy::DummyNetwork;
+use kz_core::network::replay::{RecordingNetwork, ReplayNetwork};
+use kz_core::network::{AuxOutputs, Network, ZeroEvaluation};
+use kz_core::zero::node::UctWeights;
+use kz_core::zero::step::{FpuMode, QMode};
+use kz_core::zero::values::ZeroValuesPov;
+use kz_core::zero::wrapper::ZeroSettings;
+
+#[test]
+fn record_replay_tree() {
+    let path = std::env::temp_dir().join(format!("kz_record_replay_tree_{}.bin", std::process::id()));
+
+    let board = STTTBoard::default();
+    let settings = ZeroSettings::simple(4, UctWeights::default(), QMode::wdl(), FpuMode::Relative(0.0));
+    let visits = 200;
+
+    let mut recording = RecordingNetwork::new(DummyNetwork, STTTStdMapper, &path).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    let expected = settings.build_tree(&board, &mut recording, &mut rng, |tree| tree.root_visits() >= visits);
+    recording.into_inner().unwrap();
+
+    let mut replay = ReplayNetwork::load(&path, STTTStdMapper, usize::MAX).unwrap();
+    assert!(!replay.is_empty());
+    let mut rng = StdRng::seed_from_u64(0);
+    let actual = settings.build_tree(&board, &mut replay, &mut rng, |tree| tree.root_visits() >= visits);
+
+    std::fs::remove_file(&path).unwrap();
+
+    let expected = expected.display(100, true, usize::MAX, true).to_string();
+    let actual = actual.display(100, true, usize::MAX, true).to_string();
+    assert_eq!(expected, actual);
+}
+
+/// Network with a distinct score and score lead aux output for every board.
+#[derive(Debug)]
+struct ScoreNetwork;
+
+impl Network<GoBoard> for ScoreNetwork {
+    fn max_batch_size(&self) -> usize {
+        usize::MAX
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<GoBoard>]) -> Vec<ZeroEvaluation<'static>> {
+        boards
+            .iter()
+            .map(|board| {
+                let board = board.borrow();
+                let move_count = board.available_moves().unwrap().count();
+
+                let mut aux = AuxOutputs::new();
+                aux.insert(SCORE_LEAD_OUTPUT, vec![-(move_count as f32) - 0.5]);
+
+                ZeroEvaluation {
+                    values: ZeroValuesPov {
+                        value: ScalarPov::new(0.25),
+                        score: ScalarPov::new(move_count as f32 + 0.5),
+                        score_weight: 0.75,
+                        ..Default::default()
+                    },
+                    policy: vec![1.0 / move_count as f32; move_count].into(),
+                    aux: Some(Arc::new(aux)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn record_replay_score_aux() {
+    let path = std::env::temp_dir().join(format!("kz_record_replay_score_aux_{}.bin", std::process::id()));
+    let mapper = GoStdMapper::new(9, true);
+    assert!(mapper.aux_outputs().iter().any(|spec| spec.name == SCORE_LEAD_OUTPUT));
+
+    let mut boards = vec![GoBoard::new(9, Komi::zero(), Rules::tromp_taylor())];
+    for _ in 0..4 {
+        let board = boards.last().unwrap();
+        let mv = board.available_moves().unwrap().next().unwrap();
+        boards.push(board.clone_and_play(mv).unwrap());
+    }
+
+    let mut recording = RecordingNetwork::new(ScoreNetwork, mapper, &path).unwrap();
+    let expected = recording.evaluate_batch(&boards);
+    recording.into_inner().unwrap();
+
+    let mut replay = ReplayNetwork::load(&path, mapper, usize::MAX).unwrap();
+    assert_eq!(replay.len(), boards.len());
+    let actual = replay.evaluate_batch(&boards);
+
+    std::fs::remove_file(&path).unwrap();
+
+    // evaluations don't implement PartialEq, but the debug format includes every field
+    assert_eq!(format!("{:?}", expected), format!("{:?}", actual));
+}
@@ -0,0 +1,78 @@
+use std::borrow::Borrow;
+use std::sync::Arc;
+
+use board_game::board::{Board, BoardMoves};
+use board_game::games::ttt::TTTBoard;
+use internal_iterator::InternalIterator;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::mapping::SCORE_LEAD_OUTPUT;
+use kz_core::network::symmetry::{AverageSymmetryNetwork, RandomSymmetryNetwork};
+use kz_core::network::{AuxOutputs, Network, ZeroEvaluation};
+
+/// Network with a symmetry-invariant score lead and a spatial output that depends on the exact board.
+#[derive(Debug)]
+struct AuxNetwork;
+
+impl Network<TTTBoard> for AuxNetwork {
+    fn max_batch_size(&self) -> usize {
+        usize::MAX
+    }
+
+    fn evaluate_batch(&mut self, boards: &[impl Borrow<TTTBoard>]) -> Vec<ZeroEvaluation<'static>> {
+        boards
+            .iter()
+            .map(|board| {
+                let board = board.borrow();
+                let move_count = board.available_moves().unwrap().count();
+
+                let mut aux = AuxOutputs::new();
+                aux.insert(SCORE_LEAD_OUTPUT, vec![move_count as f32]);
+                aux.insert("spatial", (0..9).map(|i| i as f32).collect());
+
+                ZeroEvaluation {
+                    values: Default::default(),
+                    policy: vec![1.0 / move_count as f32; move_count].into(),
+                    aux: Some(Arc::new(aux)),
+                }
+            })
+            .collect()
+    }
+}
+
+fn test_board() -> TTTBoard {
+    let mut board = TTTBoard::default();
+    let mv = board.available_moves().unwrap().next().unwrap();
+    board.play(mv).unwrap();
+    board
+}
+
+#[test]
+fn random_symmetry_keeps_scalar_aux() {
+    let board = test_board();
+    let mut network = RandomSymmetryNetwork::new(AuxNetwork, StdRng::seed_from_u64(0), true);
+
+    for _ in 0..16 {
+        let eval = network.evaluate(&board);
+        let aux = eval.aux.expect("scalar aux should be kept");
+
+        assert_eq!(aux.get(SCORE_LEAD_OUTPUT), Some(&vec![8.0]));
+        // spatial outputs are either correct (identity) or dropped, never left in the wrong orientation
+        if let Some(spatial) = aux.get("spatial") {
+            assert_eq!(spatial, &(0..9).map(|i| i as f32).collect::<Vec<_>>());
+        }
+    }
+}
+
+#[test]
+fn average_symmetry_keeps_scalar_aux() {
+    let board = test_board();
+    let mut network = AverageSymmetryNetwork::new(AuxNetwork);
+
+    let eval = network.evaluate(&board);
+    let aux = eval.aux.expect("scalar aux should be kept");
+
+    assert_eq!(aux.get(SCORE_LEAD_OUTPUT), Some(&vec![8.0]));
+    assert_eq!(aux.get("spatial"), None);
+}
@@ -1,16 +1,21 @@
 use std::str::FromStr;
 
-use board_game::board::{Board, BoardMoves};
+use board_game::board::{Board, BoardMoves, Player};
 use board_game::games::dummy::DummyGame;
 use board_game::games::sttt::STTTBoard;
+use board_game::games::ttt::TTTBoard;
+use board_game::pov::ScalarPov;
 use internal_iterator::InternalIterator;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
+use kz_core::games::connect4::Connect4Board;
 use kz_core::network::dummy::DummyNetwork;
+use kz_core::network::ZeroEvaluation;
 use kz_core::zero::node::UctWeights;
-use kz_core::zero::step::{FpuMode, QMode};
+use kz_core::zero::step::{zero_step_apply, zero_step_gather, FpuMode, QMode};
 use kz_core::zero::tree::Tree;
+use kz_core::zero::values::ZeroValuesPov;
 use kz_core::zero::wrapper::ZeroSettings;
 
 #[test]
@@ -66,3 +71,150 @@ fn keep_tree() {
 
     assert_eq!(actual_string, expected_string);
 }
+
+const SKEWED_ROOT_POLICY: [f32; 7] = [0.88, 0.02, 0.02, 0.02, 0.02, 0.02, 0.02];
+
+/// The network likes column 0 a lot at the root and that move is fine, all other columns are clearly losing.
+fn skewed_eval(board: &Connect4Board) -> ZeroEvaluation<'static> {
+    let policy = if board.move_count() == 0 {
+        SKEWED_ROOT_POLICY.to_vec()
+    } else {
+        let count = board.available_moves().unwrap().count();
+        vec![1.0 / count as f32; count]
+    };
+
+    let value_a = if board.tile(0, 0) == Some(Player::A) { 0.0 } else { -0.8 };
+    let value = if board.next_player() == Player::A {
+        value_a
+    } else {
+        -value_a
+    };
+
+    ZeroEvaluation {
+        values: ZeroValuesPov {
+            value: ScalarPov::new(value),
+            ..Default::default()
+        },
+        policy: policy.into(),
+        aux: None,
+    }
+}
+
+fn skewed_search(forced_playouts_k: f32, visits: u64) -> (Tree<Connect4Board>, UctWeights) {
+    let weights = UctWeights {
+        forced_playouts_k,
+        ..UctWeights::default()
+    };
+    let fpu = FpuMode::Relative(0.0);
+
+    let mut tree = Tree::new(Connect4Board::default());
+    let mut rng = StdRng::seed_from_u64(0);
+
+    while tree.root_visits() < visits {
+        if let Some(request) = zero_step_gather(&mut tree, |_| None, weights, QMode::Value, fpu, fpu, 1.0, &mut rng) {
+            let eval = skewed_eval(&request.board);
+            zero_step_apply(&mut tree, request.respond(eval));
+        }
+    }
+
+    (tree, weights)
+}
+
+fn root_child_visits(tree: &Tree<Connect4Board>) -> Vec<u64> {
+    tree[0]
+        .children
+        .unwrap()
+        .iter()
+        .map(|c| tree[c].complete_visits)
+        .collect()
+}
+
+#[test]
+fn forced_playouts() {
+    let visits = 200;
+    let k = 2.0;
+
+    let (tree_plain, _) = skewed_search(0.0, visits);
+    let (tree_forced, _) = skewed_search(k, visits);
+
+    let plain = root_child_visits(&tree_plain);
+    let forced = root_child_visits(&tree_forced);
+    println!("plain: {:?}, forced: {:?}", plain, forced);
+
+    // every child got at least its forced playouts, up to the one it's still waiting for
+    for (&p, &v) in SKEWED_ROOT_POLICY.iter().zip(&forced) {
+        let target = (k * p * visits as f32).sqrt();
+        assert!(
+            v as f32 + 1.0 >= target,
+            "visits {} below forced playouts {}",
+            v,
+            target
+        );
+    }
+
+    // the losing moves only got their extra visits because of the forced playouts
+    let plain_bad: u64 = plain[1..].iter().sum();
+    let forced_bad: u64 = forced[1..].iter().sum();
+    assert!(forced_bad > plain_bad, "plain {:?}, forced {:?}", plain, forced);
+}
+
+#[test]
+fn policy_target_pruning() {
+    let (tree, weights) = skewed_search(2.0, 200);
+
+    let policy = tree.policy().collect::<Vec<_>>();
+    let pruned = tree.policy_pruned(weights, QMode::Value);
+    println!("policy: {:?}, pruned: {:?}", policy, pruned);
+
+    // the raw policy still contains the forced visits
+    assert_eq!(policy.len(), pruned.len());
+    assert!(policy[1..].iter().all(|&p| p > 0.0));
+
+    // all forced visits on the losing moves are removed again, leaving only the best move
+    assert_eq!(pruned[0], 1.0);
+    assert!(pruned[1..].iter().all(|&p| p == 0.0));
+}
+
+#[test]
+fn terminal_final_score() {
+    let mut board = TTTBoard::default();
+    while !board.is_done() {
+        board.play(board.available_moves().unwrap().next().unwrap()).unwrap();
+    }
+
+    let fpu = FpuMode::Relative(0.0);
+    let mut rng = StdRng::seed_from_u64(0);
+
+    // terminal boards get the score lead from the final score
+    let mut tree = Tree::new(board.clone());
+    for _ in 0..3 {
+        let request = zero_step_gather(
+            &mut tree,
+            |_| Some(3.5),
+            UctWeights::default(),
+            QMode::Value,
+            fpu,
+            fpu,
+            1.0,
+            &mut rng,
+        );
+        assert!(request.is_none());
+    }
+    assert_eq!(tree.root_visits(), 3);
+    assert_eq!(tree.values().score_lead(), 3.5);
+
+    // without a final score the score lead stays unknown
+    let mut tree = Tree::new(board);
+    let request = zero_step_gather(
+        &mut tree,
+        |_| None,
+        UctWeights::default(),
+        QMode::Value,
+        fpu,
+        fpu,
+        1.0,
+        &mut rng,
+    );
+    assert!(request.is_none());
+    assert!(tree.values().score_lead().is_nan());
+}
@@ -1,5 +1,7 @@
 use std::time::Duration;
 
+use shakmaty::fen::Fen;
+use shakmaty::{CastlingMode, Chess};
 use tokio_stream::StreamExt;
 
 use licorice::client::{Lichess, LichessResult};
@@ -48,14 +50,34 @@ async fn main_impl() -> LichessResult<()> {
 }
 
 fn decline_reason(challenge: &Challenge) -> Option<&str> {
-    // TODO reject Chess960 starting positions somehow
     if challenge.variant.key != "standard" && challenge.variant.key != "fromPosition" {
         return Some("This bot does not play variants");
     }
 
+    // the underlying move generator only supports standard castling
+    // TODO accept chess960 once we switch to shakmaty, see the workspace Cargo.toml
+    if let Some(fen) = &challenge.initial_fen {
+        if !has_standard_castling(fen) {
+            return Some("This bot does not play Chess960 positions");
+        }
+    }
+
     if challenge.time_control.increment.unwrap_or(0) == 0 {
         return Some("This bot only works with nonzero increment");
     }
 
     None
 }
+
+/// Whether the position can be played with standard castling rules.
+/// Chess960 positions with castling rights for rooks that are not in the corners are rejected.
+fn has_standard_castling(fen: &str) -> bool {
+    if fen == "startpos" {
+        return true;
+    }
+
+    match fen.parse::<Fen>() {
+        Ok(fen) => fen.into_position::<Chess>(CastlingMode::Standard).is_ok(),
+        Err(_) => false,
+    }
+}
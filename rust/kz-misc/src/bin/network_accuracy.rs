@@ -0,0 +1,74 @@
+use std::hash::Hash;
+
+use board_game::board::Board;
+use clap::Parser;
+use kn_graph::onnx::load_graph_from_onnx_path;
+use kn_graph::optimizer::optimize_graph;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use kz_core::error::parse_game;
+use kz_core::mapping::BoardMapper;
+use kz_core::network::cpu::CPUNetwork;
+use kz_core::network::metadata::NetworkMetadata;
+use kz_core::registry::{visit_game, GameEntry, GameVisitor};
+use kz_misc::eval::network_accuracy::{network_accuracy, random_solved_challenges};
+use kz_util::game::Game;
+
+/// Compare the network evaluations of random solvable positions against the solver.
+#[derive(Debug, clap::Parser)]
+struct Args {
+    #[clap(long)]
+    game: String,
+    #[clap(long, default_value_t = 100)]
+    count: usize,
+    /// Play up to this many random moves from the start position to get the challenge positions.
+    #[clap(long)]
+    max_moves: usize,
+    /// Positions the solver can't solve within this many moves are skipped.
+    #[clap(long)]
+    depth: u32,
+
+    path: String,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let game = parse_game(&args.game).unwrap_or_else(|e| panic!("{}", e));
+    visit_game(game, AccuracyVisitor { args: &args }).unwrap_or_else(|e| panic!("{}", e));
+}
+
+struct AccuracyVisitor<'a> {
+    args: &'a Args,
+}
+
+impl GameVisitor for AccuracyVisitor<'_> {
+    type Output = Result<(), String>;
+
+    fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> Self::Output {
+        // the solver only finishes in a reasonable time for the smallest games
+        match entry.game {
+            Game::TTT | Game::Connect4 => {
+                main_impl(self.args, entry.mapper, entry.default_board());
+                Ok(())
+            }
+            game => Err(format!("Game {} is too large to solve", game)),
+        }
+    }
+}
+
+fn main_impl<B: Board, M: BoardMapper<B>>(args: &Args, mapper: M, start: B) {
+    let graph = optimize_graph(
+        &load_graph_from_onnx_path(&args.path, false).unwrap(),
+        Default::default(),
+    );
+    let metadata = NetworkMetadata::load_for_onnx(&args.path).unwrap();
+    let mut network = CPUNetwork::new(mapper, graph, metadata.as_ref()).unwrap();
+
+    let mut rng = StdRng::from_entropy();
+    let challenges = random_solved_challenges(&start, args.count, args.max_moves, args.depth, &mut rng);
+    println!("Solved {}/{} challenges", challenges.len(), args.count);
+
+    network_accuracy(&mut network, &challenges);
+}
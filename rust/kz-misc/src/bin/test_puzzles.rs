@@ -15,6 +15,7 @@ use rand::SeedableRng;
 
 use kz_core::mapping::chess::ChessStdMapper;
 use kz_core::network::cudnn::CudaNetwork;
+use kz_core::network::metadata::NetworkMetadata;
 use kz_core::zero::node::UctWeights;
 use kz_core::zero::step::{FpuMode, QMode};
 use kz_core::zero::wrapper::ZeroSettings;
@@ -23,13 +24,15 @@ use kz_misc::eval::lichess_puzzle::for_each_lichess_puzzle;
 fn main() {
     let path = read_to_string("ignored/network_path.txt").unwrap();
 
-    let graph = optimize_graph(&load_graph_from_onnx_path(path, false).unwrap(), Default::default());
+    let graph = optimize_graph(&load_graph_from_onnx_path(&path, false).unwrap(), Default::default());
+    let metadata = NetworkMetadata::load_for_onnx(&path).unwrap();
 
     let settings = ZeroSettings::simple(100, UctWeights::default(), QMode::wdl(), FpuMode::Relative(0.0));
     let visits = 10_000;
 
     let mapper = ChessStdMapper;
-    let mut network = CudaNetwork::new(mapper, &graph, settings.batch_size, CudaDevice::new(0).unwrap());
+    let device = CudaDevice::new(0).unwrap();
+    let mut network = CudaNetwork::new(mapper, &graph, settings.batch_size, device, metadata.as_ref()).unwrap();
     let mut rng = StdRng::from_entropy();
 
     let puzzle_path = "../data/lichess/lichess_db_puzzle.csv";
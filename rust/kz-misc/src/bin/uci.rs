@@ -16,6 +16,7 @@ use vampirc_uci::{UciMessage, UciTimeControl, UciSearchControl};
 
 use kz_core::mapping::chess::ChessStdMapper;
 use kz_core::network::cudnn::CudaNetwork;
+use kz_core::network::metadata::NetworkMetadata;
 use kz_core::zero::node::UctWeights;
 use kz_core::zero::step::{FpuMode, QMode};
 use kz_core::zero::tree::Tree;
@@ -37,7 +38,9 @@ fn main() -> std::io::Result<()> {
     let settings = ZeroSettings::simple(batch_size, UctWeights::default(), QMode::wdl(), FpuMode::Relative(0.0));
 
     let graph = load_graph_from_onnx_path(path, false).unwrap();
-    let mut network = CudaNetwork::new(ChessStdMapper, &graph, batch_size, CudaDevice::new(0).unwrap());
+    let metadata = NetworkMetadata::load_for_onnx(path).unwrap();
+    let device = CudaDevice::new(0).unwrap();
+    let mut network = CudaNetwork::new(ChessStdMapper, &graph, batch_size, device, metadata.as_ref()).unwrap();
     let mut rng = StdRng::from_entropy();
 
     // state
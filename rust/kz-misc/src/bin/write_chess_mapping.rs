@@ -2,12 +2,13 @@ use std::cmp::Reverse;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::io::Write;
-use std::unreachable;
 
 use board_game::chess::{Piece, Square};
 use board_game::games::chess::ChessBoard;
 
-use kz_core::mapping::chess::{generate_all_flat_moves_pov, ChessLegacyConvPolicyMapper};
+use kz_core::mapping::chess::{
+    generate_all_flat_moves_pov, ChessAttPolicyMapper, ChessLegacyConvPolicyMapper, ATT_TO_COUNT,
+};
 use kz_core::mapping::PolicyMapper;
 
 #[allow(dead_code)]
@@ -47,23 +48,9 @@ fn main() -> std::io::Result<()> {
         // also queen promotion doesn't matter, so just keep none for that
         let conv_i = ChessLegacyConvPolicyMapper.move_to_index(&dummy_board, mv_pov);
 
-        let att_from = mv_pov.get_source().to_index();
-        let att_to = match mv_pov.get_promotion() {
-            None => mv_pov.get_dest().to_index(),
-            Some(p) => {
-                let p_i = match p {
-                    Piece::Queen => 0,
-                    Piece::Rook => 1,
-                    Piece::Bishop => 2,
-                    Piece::Knight => 3,
-                    _ => unreachable!(),
-                };
-
-                64 + mv_pov.get_dest().get_file().to_index() * 3 + p_i
-            }
-        };
-
-        let att_i = att_from * (64 + 8 * 3) + att_to;
+        let att_i = ChessAttPolicyMapper::pov_move_to_index(mv_pov);
+        let att_from = att_i / ATT_TO_COUNT;
+        let att_to = att_i % ATT_TO_COUNT;
 
         writeln!(
             output,
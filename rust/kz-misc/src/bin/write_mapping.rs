@@ -0,0 +1,38 @@
+use std::fs::File;
+use std::hash::Hash;
+use std::io::BufWriter;
+
+use board_game::board::Board;
+
+use kz_core::mapping::export::export_mapping;
+use kz_core::mapping::BoardMapper;
+use kz_core::registry::{visit_game, GameEntry, GameVisitor};
+use kz_util::game::Game;
+
+/// Write a json description of the mapper of every example game, see [export_mapping].
+fn main() -> std::io::Result<()> {
+    std::fs::create_dir_all("ignored/mapping")?;
+
+    for game in Game::examples() {
+        visit_game(game, ExportVisitor)?;
+    }
+
+    Ok(())
+}
+
+struct ExportVisitor;
+
+impl GameVisitor for ExportVisitor {
+    type Output = std::io::Result<()>;
+
+    fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> Self::Output {
+        let game = entry.game.to_string();
+        let export = export_mapping(&game, entry.mapper, &entry.example_boards());
+
+        let path = format!("ignored/mapping/{}.json", game);
+        serde_json::to_writer_pretty(BufWriter::new(File::create(&path)?), &export)?;
+        println!("Wrote {}", path);
+
+        Ok(())
+    }
+}
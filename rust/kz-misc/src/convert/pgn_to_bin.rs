@@ -233,6 +233,8 @@ fn build_position(
             value: ScalarPov::new(win * 2.0 - 1.0),
             wdl: WDL::new(win, 0.0, 1.0 - win),
             moves_left: moves_left as f32,
+            score: ScalarPov::new(0.0),
+            score_weight: 0.0,
         }
     });
 
@@ -244,10 +246,13 @@ fn build_position(
         net_evaluation: ZeroEvaluation {
             values: ZeroValuesPov::nan(),
             policy: Cow::Owned(vec![f32::NAN; policy.len()]),
+            aux: None,
         },
         zero_evaluation: ZeroEvaluation {
             values: zero_values,
             policy: Cow::Owned(policy),
+            aux: None,
         },
+        child_stats: None,
     }
 }
@@ -59,11 +59,14 @@ pub fn append_random_games_to_bin<B: Board, M: BoardMapper<B>>(
                 net_evaluation: ZeroEvaluation {
                     values: ZeroValuesPov::nan(),
                     policy: Cow::Owned(vec![f32::NAN; mv_count]),
+                    aux: None,
                 },
                 zero_evaluation: ZeroEvaluation {
                     values: ZeroValuesPov::nan(),
                     policy: Cow::Owned(policy),
+                    aux: None,
                 },
+                child_stats: None,
             });
 
             board.play(mv).unwrap();
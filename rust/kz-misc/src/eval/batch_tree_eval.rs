@@ -104,7 +104,7 @@ pub fn batch_tree_eval<B: Board>(
                     RunCondition::Any,
                     graph_receiver,
                     job_server,
-                    |graph| CudaNetwork::new(mapper, &graph, network_batch_size, device),
+                    |graph| CudaNetwork::new(mapper, &graph, network_batch_size, device, None).unwrap(),
                     move |network, batch_x| {
                         let result = network.evaluate_batch(&batch_x);
 
@@ -1,9 +1,15 @@
+use std::borrow::Cow;
+
+use board_game::ai::solver::solve_all_moves;
 use board_game::board::Board;
 use decorum::N32;
 use internal_iterator::InternalIterator;
 use itertools::Itertools;
+use rand::Rng;
 
+use kz_core::network::ensemble::EnsembleNetwork;
 use kz_core::network::{Network, ZeroEvaluation};
+use kz_core::zero::values::ZeroValuesPov;
 use kz_util::math::kdl_divergence;
 use kz_util::sequence::zip_eq_exact;
 
@@ -28,39 +34,127 @@ pub struct ProbDistrStats {
     pub kdl_solution: f32,
 }
 
+impl<B: Board> Challenge<B> {
+    /// Use the solver to build a challenge with the exact value and optimal moves for `board`.
+    /// Returns `None` if the board is done or the solver can't find the outcome within `depth` moves.
+    pub fn solve(board: B, depth: u32) -> Option<Self> {
+        if board.is_done() {
+            return None;
+        }
+
+        let solution = solve_all_moves(&board, depth);
+        let outcome = solution.value.to_outcome_wdl()?;
+        let best_moves = solution.best_move?;
+
+        let is_optimal: Vec<bool> = board
+            .available_moves()
+            .unwrap()
+            .map(|mv: B::Move| best_moves.contains(&mv))
+            .collect();
+        let policy = is_optimal
+            .iter()
+            .map(|&optimal| optimal as u8 as f32 / best_moves.len() as f32)
+            .collect();
+
+        Some(Challenge {
+            board,
+            solution: ZeroEvaluation {
+                values: ZeroValuesPov::from_outcome(outcome, f32::NAN),
+                policy: Cow::Owned(policy),
+                aux: None,
+            },
+            is_optimal: Some(is_optimal),
+        })
+    }
+}
+
+/// The number of random positions to try per requested challenge before giving up.
+const ATTEMPTS_PER_CHALLENGE: usize = 16;
+
+/// Generate `count` solved challenges from positions reached by playing up to `max_moves` random moves from `start`.
+/// Positions the solver can't fully solve within `depth` moves are skipped,
+/// so this is mostly useful for small games like TTT and Connect4.
+/// Gives up after `count * ATTEMPTS_PER_CHALLENGE` positions, so fewer than `count` challenges can be returned.
+pub fn random_solved_challenges<B: Board>(
+    start: &B,
+    count: usize,
+    max_moves: usize,
+    depth: u32,
+    rng: &mut impl Rng,
+) -> Vec<Challenge<B>> {
+    let mut challenges = vec![];
+
+    for _ in 0..count * ATTEMPTS_PER_CHALLENGE {
+        if challenges.len() >= count {
+            break;
+        }
+
+        let mut board = start.clone();
+        let moves = rng.gen_range(0..=max_moves);
+
+        for _ in 0..moves {
+            if board.is_done() {
+                break;
+            }
+            board.play(board.random_available_move(rng).unwrap()).unwrap();
+        }
+
+        challenges.extend(Challenge::solve(board, depth));
+    }
+
+    challenges
+}
+
 pub fn network_accuracy<B: Board>(network: &mut impl Network<B>, challenges: &[Challenge<B>]) {
-    for Challenge {
+    for challenge in challenges {
+        let eval = network.evaluate(&challenge.board);
+        print_challenge_eval(challenge, &eval);
+    }
+}
+
+/// Same as [network_accuracy], but also print how much the members of the ensemble disagree.
+pub fn ensemble_accuracy<B: Board, N: Network<B>>(network: &mut EnsembleNetwork<N>, challenges: &[Challenge<B>]) {
+    for challenge in challenges {
+        let (mut evals, mut disagreements) = network.evaluate_batch_disagreement(&[&challenge.board]);
+        let eval = evals.pop().unwrap();
+        let disagreement = disagreements.pop().unwrap();
+
+        print_challenge_eval(challenge, &eval);
+        println!("Disagreement:");
+        println!("  {:?}", disagreement);
+    }
+}
+
+fn print_challenge_eval<B: Board>(challenge: &Challenge<B>, eval: &ZeroEvaluation) {
+    let Challenge {
         board,
         solution,
         is_optimal,
-    } in challenges
-    {
-        println!("{}", board);
-        println!(
-            "Number of available moves: {}",
-            board.available_moves().unwrap().count()
-        );
-
-        let eval = network.evaluate(board);
-
-        println!("WDL:");
-        println!("  solution: {:?}", solution.values.wdl);
-        println!("  actual: {:?}", eval.values.wdl);
-        let wdl_stats = prob_distr_stats(&eval.values.wdl.to_slice(), &solution.values.wdl.to_slice());
-        println!("  stats: {:?}", wdl_stats);
-
-        println!("Policy:");
-        println!("  solution: {:?}", solution.policy);
-        println!("  actual: {:?}", eval.policy);
-        let policy_stats = prob_distr_stats(&eval.policy, &solution.policy);
-        println!("  stats: {:?}", policy_stats);
-
-        if let Some(optimal_moves) = is_optimal {
-            let optimal_p = zip_eq_exact(&*eval.policy, optimal_moves)
-                .map(|(&p, &w)| if w { p } else { 0.0 })
-                .sum::<f32>();
-            println!("  optimal_p: {:?}", optimal_p);
-        }
+    } = challenge;
+
+    println!("{}", board);
+    println!(
+        "Number of available moves: {}",
+        board.available_moves().unwrap().count()
+    );
+
+    println!("WDL:");
+    println!("  solution: {:?}", solution.values.wdl);
+    println!("  actual: {:?}", eval.values.wdl);
+    let wdl_stats = prob_distr_stats(&eval.values.wdl.to_slice(), &solution.values.wdl.to_slice());
+    println!("  stats: {:?}", wdl_stats);
+
+    println!("Policy:");
+    println!("  solution: {:?}", solution.policy);
+    println!("  actual: {:?}", eval.policy);
+    let policy_stats = prob_distr_stats(&eval.policy, &solution.policy);
+    println!("  stats: {:?}", policy_stats);
+
+    if let Some(optimal_moves) = is_optimal {
+        let optimal_p = zip_eq_exact(&*eval.policy, optimal_moves)
+            .map(|(&p, &w)| if w { p } else { 0.0 })
+            .sum::<f32>();
+        println!("  optimal_p: {:?}", optimal_p);
     }
 }
 
@@ -84,3 +178,56 @@ fn prob_distr_stats(actual: &[f32], solution: &[f32]) -> ProbDistrStats {
 fn argmax(data: &[f32]) -> usize {
     data.iter().position_max_by_key(|&&f| N32::from_inner(f)).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use board_game::games::ttt::TTTBoard;
+    use board_game::util::coord::Coord3;
+    use board_game::wdl::WDL;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn ttt_board(moves: &[u8]) -> TTTBoard {
+        let mut board = TTTBoard::default();
+        for &mv in moves {
+            board.play(Coord3::from_index(mv)).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    fn ttt_start_all_draws() {
+        let challenge = Challenge::solve(TTTBoard::default(), 9).unwrap();
+        assert_eq!(challenge.solution.values.wdl, WDL::new(0.0, 1.0, 0.0));
+        assert_eq!(challenge.is_optimal, Some(vec![true; 9]));
+        assert_eq!(challenge.solution.policy.as_ref(), &[1.0 / 9.0; 9]);
+    }
+
+    #[test]
+    fn ttt_single_winning_move() {
+        // x x .
+        // o o .
+        // . . .
+        // x to move, only completing the top row wins immediately
+        let challenge = Challenge::solve(ttt_board(&[0, 3, 1, 4]), 9).unwrap();
+        assert_eq!(challenge.solution.values.wdl, WDL::new(1.0, 0.0, 0.0));
+        // available moves are 2, 5, 6, 7, 8
+        assert_eq!(challenge.is_optimal, Some(vec![true, false, false, false, false]));
+        assert_eq!(challenge.solution.policy.as_ref(), &[1.0, 0.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn ttt_done() {
+        assert!(Challenge::solve(ttt_board(&[0, 3, 1, 4, 2]), 9).is_none());
+    }
+
+    #[test]
+    fn random_challenges_give_up() {
+        // the solver can't solve anything with depth 0, this used to loop forever
+        let mut rng = StdRng::seed_from_u64(0);
+        let challenges = random_solved_challenges(&TTTBoard::default(), 4, 2, 0, &mut rng);
+        assert!(challenges.is_empty());
+    }
+}
@@ -1,7 +1,8 @@
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::time::Instant;
 
-use board_game::board::{Board, Outcome};
+use async_trait::async_trait;
+use board_game::board::{Board, BoardDone, Outcome};
 use board_game::pov::{NonPov, Pov};
 use board_game::util::rating::elo_from_wdl;
 use board_game::wdl::{OutcomeWDL, WDL};
@@ -10,11 +11,14 @@ use futures::executor::{block_on, ThreadPoolBuilder};
 use futures::task::SpawnExt;
 use futures::StreamExt;
 use itertools::Itertools;
+use rand::Rng;
 use tabled::builder::Builder;
 use tabled::settings::{Margin, Style};
 use tabled::Table;
 
 use kz_core::bot::AsyncBot;
+use kz_core::zero::wrapper::AsyncZeroBot;
+use kz_selfplay::move_selector::MoveSelector;
 
 pub type BoxBotFn<B> = Box<dyn Fn() -> BoxBot<B>>;
 pub type BoxBot<B> = Box<dyn AsyncBot<B> + Send>;
@@ -66,6 +70,53 @@ pub fn box_bot<B: Board, T: AsyncBot<B> + Send + 'static>(
     Box::new(move || Box::new(f()))
 }
 
+/// Wrapper around [AsyncZeroBot] that samples moves using a [MoveSelector] instead of always picking the best move.
+/// This is useful to get more diverse games in a tournament, even when starting from a small set of positions.
+///
+/// The move count passed to the selector is the number of moves played by this bot so far.
+pub struct SelectorZeroBot<B: Board, R: Rng + Send> {
+    inner: AsyncZeroBot<B, R>,
+    selector: MoveSelector,
+    rng: R,
+    move_count: u32,
+}
+
+impl<B: Board, R: Rng + Send> SelectorZeroBot<B, R> {
+    pub fn new(inner: AsyncZeroBot<B, R>, selector: MoveSelector, rng: R) -> Self {
+        SelectorZeroBot {
+            inner,
+            selector,
+            rng,
+            move_count: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl<B: Board, R: Rng + Send> AsyncBot<B> for SelectorZeroBot<B, R> {
+    async fn select_move(&mut self, board: &B) -> Result<B::Move, BoardDone> {
+        board.check_done()?;
+
+        let tree = self.inner.build_tree(board).await;
+        let policy = tree.policy().collect_vec();
+        let index = self.selector.select(self.move_count, &policy, &mut self.rng);
+        self.move_count += 1;
+
+        let child = tree[0].children.unwrap().get(index);
+        Ok(tree[child].last_move.unwrap())
+    }
+}
+
+impl<B: Board, R: Rng + Send> Debug for SelectorZeroBot<B, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SelectorZeroBot")
+            .field("inner", &self.inner)
+            .field("selector", &self.selector)
+            .field("move_count", &self.move_count)
+            .finish()
+    }
+}
+
 pub fn run_tournament<S: Display, B: Board, F: FnMut() + Send + 'static>(
     bots: Vec<(S, BoxBotFn<B>)>,
     start_positions: Vec<B>,
@@ -397,3 +448,73 @@ fn display_format_wdl_elo(wdl: WDL<usize>) -> String {
         format!("{:.2}/{:.2}/{:.2} => {:.2}", wdl.win, wdl.draw, wdl.loss, elo)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use board_game::games::ttt::TTTBoard;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use kz_core::network::dummy::DummyNetwork;
+    use kz_core::network::job_channel::job_pair;
+    use kz_core::network::{EvalClient, Network};
+    use kz_core::zero::node::UctWeights;
+    use kz_core::zero::step::{FpuMode, QMode};
+    use kz_core::zero::wrapper::ZeroSettings;
+
+    use super::*;
+
+    fn dummy_eval_client() -> EvalClient<TTTBoard> {
+        let (client, server) = job_pair(4);
+        // stops once all clients are dropped
+        std::thread::spawn(move || {
+            for job in server.into_receiver() {
+                let evals = <DummyNetwork as Network<TTTBoard>>::evaluate_batch(&mut DummyNetwork, &job.x);
+                let _ = job.sender.send(evals);
+            }
+        });
+        client
+    }
+
+    fn selector_bot(selector: MoveSelector, seed: u64) -> SelectorZeroBot<TTTBoard, StdRng> {
+        let settings = ZeroSettings::simple(1, UctWeights::default(), QMode::wdl(), FpuMode::Relative(0.0));
+        let inner = AsyncZeroBot::new(dummy_eval_client(), settings, 20, StdRng::seed_from_u64(seed));
+        SelectorZeroBot::new(inner, selector, StdRng::seed_from_u64(seed))
+    }
+
+    #[test]
+    fn selector_bot_diverse_moves() {
+        let board = TTTBoard::default();
+        let mut bot = selector_bot(MoveSelector::constant_temp(f32::INFINITY), 0);
+
+        let moves: HashSet<_> = (0..20).map(|_| block_on(bot.select_move(&board)).unwrap()).collect();
+        assert!(moves.len() > 1, "Expected different moves, got {:?}", moves);
+        assert!(moves.iter().all(|&mv| board.is_available_move(mv).unwrap()));
+        assert_eq!(bot.move_count, 20);
+    }
+
+    #[test]
+    fn selector_bot_done() {
+        let mut board = TTTBoard::default();
+        while !board.is_done() {
+            board.play(board.available_moves().unwrap().next().unwrap()).unwrap();
+        }
+
+        let mut bot = selector_bot(MoveSelector::zero_temp(), 0);
+        assert!(block_on(bot.select_move(&board)).is_err());
+    }
+
+    #[test]
+    fn selector_bot_tournament() {
+        let bot = |seed| {
+            let selector = MoveSelector::constant_temp(1.0);
+            box_bot(move || selector_bot(selector.clone(), seed))
+        };
+        let bots = vec![("a", bot(0)), ("b", bot(1))];
+
+        let tournament = run_tournament(bots, vec![TTTBoard::default()], Some(1), false, true, || {});
+        assert_eq!(tournament.rounds.len(), 2);
+    }
+}
@@ -0,0 +1,45 @@
+use std::net::TcpListener;
+use std::path::PathBuf;
+
+use clap::Parser;
+use kn_graph::onnx::load_graph_from_onnx_path;
+use kn_graph::optimizer::optimize_graph;
+use kn_runtime::{compiled_with_cuda_support, Device};
+
+use kz_selfplay::inference_server::inference_server_main;
+
+#[derive(Debug, clap::Parser)]
+struct Args {
+    #[clap(long)]
+    network: PathBuf,
+    #[clap(long, default_value = "127.0.0.1:63106")]
+    address: String,
+    #[clap(long, default_value_t = 256)]
+    batch_size: usize,
+    #[clap(long)]
+    cpu: bool,
+}
+
+/// Host a single network for multiple local clients, see `RemoteNetwork`.
+fn main() -> std::io::Result<()> {
+    let args: Args = Args::parse();
+
+    println!("Loading graph...");
+    let graph = load_graph_from_onnx_path(&args.network, true).unwrap();
+    let graph = optimize_graph(&graph, Default::default());
+
+    let device = if args.cpu {
+        Device::Cpu
+    } else {
+        if !compiled_with_cuda_support() {
+            eprintln!("Warning: Not compiled with cuda support");
+        }
+        Device::best()
+    };
+    println!("Using device {:?}", device);
+
+    let listener = TcpListener::bind(&args.address)?;
+    inference_server_main(graph, device, args.batch_size, listener);
+
+    Ok(())
+}
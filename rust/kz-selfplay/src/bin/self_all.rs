@@ -41,6 +41,7 @@ fn visit<B: Board, M: BoardMapper<B>>(
     let eval = ZeroEvaluation {
         values: uniform_values(),
         policy: Cow::Owned(uniform_policy(board.available_moves().unwrap().count())),
+        aux: None,
     };
 
     board.available_moves().unwrap().for_each(|mv: B::Move| {
@@ -51,6 +52,7 @@ fn visit<B: Board, M: BoardMapper<B>>(
             zero_visits: 0,
             zero_evaluation: eval.clone(),
             net_evaluation: eval.clone(),
+            child_stats: None,
         };
 
         positions.push(position);
@@ -53,6 +53,8 @@ fn map_values(value: Option<f32>, wdl: [Option<f32>; 3], moves_left: Option<f32>
             wdl[2].unwrap_or(f32::NAN),
         ),
         moves_left: moves_left.unwrap_or(f32::NAN),
+        score: ScalarPov::new(0.0),
+        score_weight: 0.0,
     }
 }
 
@@ -110,6 +112,7 @@ fn append_simulation(
                 data_pos.zero_eval_moves_left,
             ),
             policy: Cow::Owned(policy.clone()),
+            aux: None,
         };
         let net_eval = ZeroEvaluation {
             values: map_values(
@@ -119,6 +122,7 @@ fn append_simulation(
             ),
             // use wrong policy here, just to have something reasonable
             policy: Cow::Owned(policy),
+            aux: None,
         };
 
         let new_pos = Position {
@@ -128,6 +132,7 @@ fn append_simulation(
             zero_visits: data_pos.zero_visits,
             zero_evaluation: zero_eval,
             net_evaluation: net_eval,
+            child_stats: None,
         };
 
         positions.push(new_pos);
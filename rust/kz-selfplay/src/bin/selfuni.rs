@@ -1,36 +1,25 @@
 use std::borrow::Cow;
+use std::hash::Hash;
 use std::path::PathBuf;
 
 use board_game::ai::solver::solve_all_moves;
 use board_game::board::Board;
-use board_game::games::arimaa::ArimaaBoard;
-use board_game::games::ataxx::AtaxxBoard;
-use board_game::games::chess::ChessBoard;
-use board_game::games::go::{GoBoard, Komi, Rules};
-use board_game::games::sttt::STTTBoard;
-use board_game::games::ttt::TTTBoard;
 use board_game::wdl::OutcomeWDL;
 use clap::Parser;
 use flume::{Receiver, Sender};
 use internal_iterator::InternalIterator;
-use kz_core::mapping::arimaa::ArimaaSplitMapper;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use trictrac_bot::trictrac_board::TrictracBoard;
-
-use kz_core::mapping::ataxx::AtaxxStdMapper;
-use kz_core::mapping::chess::{ChessHistoryMapper, ChessStdMapper};
-use kz_core::mapping::go::GoStdMapper;
-use kz_core::mapping::sttt::STTTStdMapper;
-use kz_core::mapping::trictrac::TrictracStdMapper;
-use kz_core::mapping::ttt::TTTStdMapper;
+use rand::SeedableRng;
+
+use kz_core::error::parse_game;
 use kz_core::mapping::BoardMapper;
 use kz_core::network::dummy::{uniform_policy, uniform_values};
 use kz_core::network::ZeroEvaluation;
+use kz_core::registry::{visit_game, GameEntry, GameVisitor};
 use kz_core::zero::values::ZeroValuesPov;
 use kz_selfplay::binary_output::BinaryOutput;
 use kz_selfplay::simulation::{Position, Simulation};
-use kz_util::game::Game;
 use kz_util::throughput::PrintThroughput;
 
 #[derive(Debug, Parser)]
@@ -46,6 +35,8 @@ struct Args {
     solver_depth: Option<u32>,
     #[clap(long)]
     thread_count: Option<u32>,
+    #[clap(long, default_value = "default")]
+    start_pos: String,
 
     bin_path: PathBuf,
 }
@@ -53,26 +44,26 @@ struct Args {
 fn main() {
     let mut args: Args = Args::parse();
 
-    let game = Game::parse(&args.game).unwrap();
+    let game = parse_game(&args.game).unwrap_or_else(|e| panic!("{}", e));
     args.game = game.to_string();
 
-    match game {
-        Game::TTT => main_impl(&args, TTTBoard::default, TTTStdMapper),
-        Game::STTT => main_impl(&args, STTTBoard::default, STTTStdMapper),
-        Game::Chess => main_impl(&args, ChessBoard::default, ChessStdMapper),
-        Game::Trictrac => main_impl(&args, TrictracBoard::default, TrictracStdMapper),
-        Game::Ataxx { size } => main_impl(&args, || AtaxxBoard::diagonal(size), AtaxxStdMapper::new(size)),
-        Game::ChessHist { length } => main_impl(&args, ChessBoard::default, ChessHistoryMapper::new(length)),
-        Game::ArimaaSplit => main_impl(&args, ArimaaBoard::default, ArimaaSplitMapper),
-        Game::Go { size } => main_impl(
-            &args,
-            || GoBoard::new(size, Komi::new(15), Rules::cgos()),
-            GoStdMapper::new(size, true),
-        ),
+    visit_game(game, SelfUniVisitor { args: &args });
+}
+
+struct SelfUniVisitor<'a> {
+    args: &'a Args,
+}
+
+impl GameVisitor for SelfUniVisitor<'_> {
+    type Output = ();
+
+    fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) {
+        let start_pos = entry.start_pos(&self.args.start_pos);
+        main_impl(self.args, start_pos, entry.mapper)
     }
 }
 
-fn main_impl<B: Board, M: BoardMapper<B>>(args: &Args, start_pos: impl Fn() -> B + Sync, mapper: M) {
+fn main_impl<B: Board, M: BoardMapper<B>>(args: &Args, start_pos: impl Fn(&mut StdRng) -> B + Sync, mapper: M) {
     let thread_count = args.thread_count.unwrap_or(1) as usize;
 
     crossbeam::scope(|s| {
@@ -110,12 +101,12 @@ fn main_collector<B: Board, M: BoardMapper<B>>(args: &Args, mapper: M, receiver:
     drop(receiver);
 }
 
-fn main_generator<B: Board>(args: &Args, start_pos: impl Fn() -> B, sender: Sender<Simulation<B>>) {
-    let mut rng = thread_rng();
+fn main_generator<B: Board>(args: &Args, start_pos: impl Fn(&mut StdRng) -> B, sender: Sender<Simulation<B>>) {
+    let mut rng = StdRng::from_entropy();
     let solver_depth = args.solver_depth.unwrap_or(0);
 
     loop {
-        let mut board = start_pos();
+        let mut board = start_pos(&mut rng);
         let mut positions = vec![];
 
         for _ in 0..args.max_game_length {
@@ -126,6 +117,7 @@ fn main_generator<B: Board>(args: &Args, start_pos: impl Fn() -> B, sender: Send
             let net_eval = ZeroEvaluation {
                 values: uniform_values(),
                 policy: Cow::Owned(uniform_policy(board.available_moves().unwrap().count())),
+                aux: None,
             };
 
             let solution = solve_all_moves(&board, solver_depth);
@@ -140,6 +132,7 @@ fn main_generator<B: Board>(args: &Args, start_pos: impl Fn() -> B, sender: Send
                 let zero_eval = ZeroEvaluation {
                     values: ZeroValuesPov::from_outcome(outcome, 0.0),
                     policy: Cow::Owned(policy),
+                    aux: None,
                 };
 
                 let mv = *moves.choose(&mut rng).unwrap();
@@ -156,6 +149,7 @@ fn main_generator<B: Board>(args: &Args, start_pos: impl Fn() -> B, sender: Send
                 zero_visits: 0,
                 zero_evaluation: zero_eval,
                 net_evaluation: net_eval,
+                child_stats: None,
             };
             positions.push(position);
 
@@ -173,3 +167,32 @@ fn main_generator<B: Board>(args: &Args, start_pos: impl Fn() -> B, sender: Send
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use kz_util::game::Game;
+
+    use super::*;
+
+    #[test]
+    fn visitor_all_games() {
+        for game in Game::examples() {
+            println!("Visiting {}", game);
+
+            // the output path can't have an extension, so get rid of the dots in "go-9..19"
+            let name = format!("kz-selfuni-test-{}", game.to_string().replace('.', "_"));
+            let args = Args {
+                game: game.to_string(),
+                game_count: 1,
+                max_game_length: 4,
+                solver_depth: None,
+                thread_count: None,
+                start_pos: "default".to_owned(),
+                bin_path: std::env::temp_dir().join(name),
+            };
+
+            visit_game(game, SelfUniVisitor { args: &args });
+            assert!(args.bin_path.with_extension("json").exists());
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use std::cmp::{max, min};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io;
 use std::io::{BufWriter, Seek, Write};
@@ -6,7 +7,7 @@ use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 
 use board_game::board::{Board, BoardDone, Outcome};
-use board_game::pov::NonPov;
+use board_game::pov::{NonPov, Pov};
 use board_game::wdl::WDL;
 use bytemuck::cast_slice;
 use internal_iterator::InternalIterator;
@@ -14,10 +15,10 @@ use serde::Serialize;
 
 use kz_core::mapping::bit_buffer::BitBuffer;
 use kz_core::mapping::BoardMapper;
-use kz_core::zero::values::ZeroValuesPov;
+use kz_core::zero::values::{ZeroValuesAbs, ZeroValuesPov};
 use kz_util::math::kdl_divergence;
 
-use crate::simulation::{Position, Simulation};
+use crate::simulation::{ChildStats, Position, Simulation};
 
 #[derive(Serialize)]
 struct MetaData<'a> {
@@ -31,11 +32,14 @@ struct MetaData<'a> {
     position_count: usize,
     includes_terminal_positions: bool,
     includes_game_start_indices: bool,
+    includes_child_stats: bool,
 
     max_game_length: i32,
     min_game_length: i32,
     root_wdl: [f32; 3],
     hit_move_limit: f32,
+    /// For mappers that support multiple board sizes, the number of positions for each size.
+    board_sizes: &'a BTreeMap<usize, u64>,
 
     scalar_names: &'static [&'static str],
 }
@@ -57,16 +61,23 @@ pub struct BinaryOutput<B: Board, M: BoardMapper<B>> {
 
     total_root_wdl: WDL<u64>,
     hit_move_limit_count: u64,
+    board_sizes: BTreeMap<usize, u64>,
 
     next_offset: u64,
     game_start_indices: Vec<u64>,
 
+    // decided by the first position that has available moves, all others must match
+    includes_child_stats: Option<bool>,
+
     finished: bool,
 
     mapper: M,
     ph: PhantomData<B>,
 }
 
+/// The horizons (in moves) of the short-term value targets.
+const SHORT_TERM_HORIZONS: [usize; 3] = [6, 16, 50];
+
 #[derive(Debug)]
 struct Scalars {
     game_id: usize,
@@ -83,6 +94,11 @@ struct Scalars {
     final_values: ZeroValuesPov,
     zero_values: ZeroValuesPov,
     net_values: ZeroValuesPov,
+    short_term_values: [ZeroValuesPov; SHORT_TERM_HORIZONS.len()],
+    /// The final score difference from the POV of the next player, NaN for games without a score.
+    final_score: f32,
+    /// The actual board size, NaN if the mapper only supports a single size.
+    board_size: f32,
 }
 
 impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
@@ -115,10 +131,13 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
 
             total_root_wdl: WDL::default(),
             hit_move_limit_count: 0,
+            board_sizes: BTreeMap::new(),
 
             next_offset: 0,
             game_start_indices: vec![],
 
+            includes_child_stats: None,
+
             finished: false,
             mapper,
             ph: PhantomData,
@@ -144,6 +163,19 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
         self.total_root_wdl += outcome.pov(simulation.start_board().next_player()).to_wdl();
         self.hit_move_limit_count += final_board.outcome().is_none() as u8 as u64;
 
+        let short_term_values = SHORT_TERM_HORIZONS.map(|horizon| short_term_values(positions, outcome, horizon));
+
+        // only finished games have a meaningful final score
+        let final_score = final_board
+            .is_done()
+            .then(|| self.mapper.final_score(final_board))
+            .flatten();
+        let final_score_pov = |board: &B| match final_score {
+            None => f32::NAN,
+            Some(score) if board.next_player() == final_board.next_player() => score,
+            Some(score) => -score,
+        };
+
         // write the positions
         for (pos_index, position) in positions.iter().enumerate() {
             let &Position {
@@ -153,6 +185,7 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
                 zero_visits,
                 ref zero_evaluation,
                 ref net_evaluation,
+                ref child_stats,
             } = position;
 
             let (available_mv_count, policy_indices) = collect_policy_indices(board, self.mapper);
@@ -164,6 +197,17 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
             let moves_left = game_length + 1 - pos_index;
             let stored_policy = &zero_evaluation.policy;
 
+            let child_stats = child_stats.as_deref();
+            if let Some(child_stats) = child_stats {
+                assert_eq!(available_mv_count, child_stats.len());
+            }
+            let includes_child_stats = *self.includes_child_stats.get_or_insert(child_stats.is_some());
+            assert_eq!(
+                includes_child_stats,
+                child_stats.is_some(),
+                "Either all or none of the positions in a file must include child stats"
+            );
+
             let scalars = Scalars {
                 game_id,
                 pos_index,
@@ -179,9 +223,14 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
                 final_values: ZeroValuesPov::from_outcome(outcome.pov(board.next_player()), moves_left as f32),
                 zero_values: zero_evaluation.values,
                 net_values: net_evaluation.values,
+                short_term_values: short_term_values
+                    .each_ref()
+                    .map(|v| v[pos_index].pov(board.next_player())),
+                final_score: final_score_pov(board),
+                board_size: self.board_size(board),
             };
 
-            self.append_position(board, &scalars, &policy_indices, stored_policy)?;
+            self.append_position(board, &scalars, &policy_indices, stored_policy, child_stats)?;
         }
 
         let scalars = Scalars {
@@ -200,9 +249,13 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
             zero_values: ZeroValuesPov::nan(),
             //TODO in theory we could ask the network, but this is only really meaningful for muzero
             net_values: ZeroValuesPov::nan(),
+            short_term_values: [ZeroValuesPov::from_outcome(outcome.pov(final_board.next_player()), 0.0);
+                SHORT_TERM_HORIZONS.len()],
+            final_score: final_score_pov(final_board),
+            board_size: self.board_size(final_board),
         };
 
-        self.append_position(&final_board, &scalars, &[], &[])?;
+        self.append_position(&final_board, &scalars, &[], &[], None)?;
 
         Ok(())
     }
@@ -213,6 +266,7 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
         scalars: &Scalars,
         policy_indices: &[u32],
         policy_values: &[f32],
+        child_stats: Option<&[ChildStats]>,
     ) -> io::Result<()> {
         // encode board
         let mut board_bools = BitBuffer::new(self.mapper.input_bool_len());
@@ -229,6 +283,9 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
         assert_normalized_or_nan(scalars.zero_values.wdl.sum());
         assert_normalized_or_nan(scalars.net_values.wdl.sum());
         assert_normalized_or_nan(scalars.final_values.wdl.sum());
+        for values in &scalars.short_term_values {
+            assert_normalized_or_nan(values.wdl.sum());
+        }
         if policy_len != 0 {
             assert_normalized_or_nan(policy_values.iter().sum());
         }
@@ -252,9 +309,33 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
             self.next_offset += data.len() as u64;
         }
 
+        // optional child stats, stored as separate arrays for visits, q and prior
+        if let Some(child_stats) = child_stats {
+            let visits: Vec<f32> = child_stats.iter().map(|c| c.visits as f32).collect();
+            let q: Vec<f32> = child_stats.iter().map(|c| c.q).collect();
+            let prior: Vec<f32> = child_stats.iter().map(|c| c.prior).collect();
+
+            for data in [visits, q, prior] {
+                let data: &[u8] = cast_slice(&data);
+                self.bin_write.write_all(data)?;
+                self.next_offset += data.len() as u64;
+            }
+        }
+
         Ok(())
     }
 
+    /// The actual board size as a scalar, also counting it for the metadata.
+    fn board_size(&mut self, board: &B) -> f32 {
+        match self.mapper.board_size(board) {
+            None => f32::NAN,
+            Some(size) => {
+                *self.board_sizes.entry(size).or_default() += 1;
+                size as f32
+            }
+        }
+    }
+
     pub fn finish(&mut self) -> io::Result<()> {
         if self.finished {
             panic!("This output is already finished")
@@ -271,10 +352,12 @@ impl<B: Board, M: BoardMapper<B>> BinaryOutput<B, M> {
             position_count: self.position_count,
             includes_terminal_positions: true,
             includes_game_start_indices: true,
+            includes_child_stats: self.includes_child_stats.unwrap_or(false),
             max_game_length: self.max_game_length.unwrap_or(-1),
             min_game_length: self.min_game_length.unwrap_or(-1),
             root_wdl: (self.total_root_wdl.cast::<f32>() / self.game_count as f32).to_slice(),
             hit_move_limit: self.hit_move_limit_count as f32 / self.game_count as f32,
+            board_sizes: &self.board_sizes,
         };
 
         serde_json::to_writer_pretty(&mut self.json_tmp_write, &meta)?;
@@ -314,6 +397,23 @@ fn collect_policy_indices<B: Board, M: BoardMapper<B>>(board: &B, mapper: M) ->
     }
 }
 
+/// Exponentially weighted average of the future search values, giving weight `(1-λ) λ^k` to the value
+/// `k` moves in the future, with `λ = 1 - 1/horizon`. The weight left after the end of the game goes to the outcome.
+fn short_term_values<B: Board>(positions: &[Position<B>], outcome: Outcome, horizon: usize) -> Vec<ZeroValuesAbs> {
+    let lambda = 1.0 - 1.0 / horizon as f32;
+
+    let mut result = vec![ZeroValuesAbs::nan(); positions.len()];
+    let mut next = ZeroValuesAbs::from_outcome(outcome, 0.0);
+
+    for (i, position) in positions.iter().enumerate().rev() {
+        let values = position.zero_evaluation.values.un_pov(position.board.next_player());
+        next = values * (1.0 - lambda) + next * lambda;
+        result[i] = next;
+    }
+
+    result
+}
+
 fn assert_normalized_or_nan(x: f32) {
     assert!(x.is_nan() || (1.0 - x).abs() < 0.001);
 }
@@ -346,6 +446,20 @@ impl Scalars {
         "net_wdl_d",
         "net_wdl_l",
         "net_moves_left",
+        "short_6_v",
+        "short_6_wdl_w",
+        "short_6_wdl_d",
+        "short_6_wdl_l",
+        "short_16_v",
+        "short_16_wdl_w",
+        "short_16_wdl_d",
+        "short_16_wdl_l",
+        "short_50_v",
+        "short_50_wdl_w",
+        "short_50_wdl_d",
+        "short_50_wdl_l",
+        "final_score",
+        "board_size",
     ];
 
     fn to_vec(&self) -> Vec<f32> {
@@ -366,8 +480,183 @@ impl Scalars {
         result.extend_from_slice(&self.final_values.to_slice());
         result.extend_from_slice(&self.zero_values.to_slice());
         result.extend_from_slice(&self.net_values.to_slice());
+        for values in &self.short_term_values {
+            // moves left is not meaningful for these targets, so we only store value and wdl
+            result.extend_from_slice(&values.to_slice()[..4]);
+        }
+        result.push(self.final_score);
+        result.push(self.board_size);
 
         assert_eq!(result.len(), Self::NAMES.len());
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use board_game::board::{BoardMoves, Player};
+    use board_game::games::ttt::TTTBoard;
+    use board_game::pov::ScalarPov;
+    use board_game::wdl::OutcomeWDL;
+
+    use kz_core::mapping::ttt::TTTStdMapper;
+    use kz_core::mapping::InputMapper;
+    use kz_core::network::ZeroEvaluation;
+
+    use super::*;
+
+    /// A short game that always plays the first available move, with made up but distinct child stats.
+    /// The search value of each position is taken from `values`.
+    fn ttt_simulation(values: &[f32]) -> Simulation<'static, TTTBoard> {
+        let mut board = TTTBoard::default();
+        let mut positions = vec![];
+
+        for &value in values {
+            let moves: Vec<_> = board.available_moves().unwrap().collect();
+            let n = moves.len();
+
+            let mut eval = ZeroEvaluation {
+                values: ZeroValuesPov::from_outcome(OutcomeWDL::Draw, 0.0),
+                policy: vec![1.0 / n as f32; n].into(),
+                aux: None,
+            };
+            eval.values.value = ScalarPov::new(value);
+            let child_stats = (0..n)
+                .map(|i| ChildStats {
+                    visits: i as u64,
+                    q: i as f32 / 10.0,
+                    prior: 1.0 / n as f32,
+                })
+                .collect();
+
+            positions.push(Position {
+                board: board.clone(),
+                is_full_search: true,
+                played_mv: moves[0],
+                zero_visits: 100,
+                zero_evaluation: eval.clone(),
+                net_evaluation: eval,
+                child_stats: Some(child_stats),
+            });
+            board.play(moves[0]).unwrap();
+        }
+
+        Simulation {
+            positions,
+            final_board: board,
+        }
+    }
+
+    fn read_f32s(data: &[u8]) -> Vec<f32> {
+        data.chunks_exact(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn child_stats_round_trip() {
+        let dir = std::env::temp_dir().join(format!("kz_binary_output_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("games");
+
+        let mapper = TTTStdMapper;
+        let simulation = ttt_simulation(&[0.0; 3]);
+
+        let mut output = BinaryOutput::new(&path, "ttt", mapper).unwrap();
+        output.append(&simulation).unwrap();
+        output.finish().unwrap();
+
+        let meta: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path.with_extension("json")).unwrap()).unwrap();
+        assert_eq!(meta["includes_child_stats"], true);
+        assert_eq!(meta["position_count"], 4);
+
+        let bin = std::fs::read(path.with_extension("bin")).unwrap();
+        let off = std::fs::read(path.with_extension("off")).unwrap();
+        let offsets: Vec<usize> = off
+            .chunks_exact(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()) as usize)
+            .collect();
+        // position offsets followed by the game start indices
+        assert_eq!(offsets.len(), 4 + 1);
+
+        let scalar_len = Scalars::NAMES.len() * 4;
+        let bool_len = (mapper.input_bool_len() + 7) / 8;
+        let input_scalar_len = mapper.input_scalar_count() * 4;
+        let mv_count_index = Scalars::NAMES.iter().position(|&n| n == "available_mv_count").unwrap();
+
+        for pi in 0..4 {
+            let start = offsets[pi];
+            let end = if pi == 3 { bin.len() } else { offsets[pi + 1] };
+            let data = &bin[start..end];
+
+            let scalars = read_f32s(&data[..scalar_len]);
+            let n = scalars[mv_count_index] as usize;
+
+            let mut rest = &data[scalar_len + bool_len + input_scalar_len..];
+            let _policy_indices = &rest[..n * 4];
+            let policy_values = read_f32s(&rest[n * 4..n * 8]);
+            rest = &rest[n * 8..];
+
+            match simulation.positions.get(pi) {
+                Some(position) => {
+                    assert_eq!(n, position.zero_evaluation.policy.len());
+                    assert_eq!(policy_values, position.zero_evaluation.policy.as_ref());
+
+                    let stats = position.child_stats.as_ref().unwrap();
+                    let visits = stats.iter().map(|c| c.visits as f32).collect::<Vec<_>>();
+                    let q = stats.iter().map(|c| c.q).collect::<Vec<_>>();
+                    let prior = stats.iter().map(|c| c.prior).collect::<Vec<_>>();
+
+                    assert_eq!(rest.len(), 3 * n * 4);
+                    assert_eq!(read_f32s(&rest[..n * 4]), visits);
+                    assert_eq!(read_f32s(&rest[n * 4..n * 8]), q);
+                    assert_eq!(read_f32s(&rest[n * 8..]), prior);
+                }
+                None => {
+                    // the final position has no moves and so no child stats either
+                    assert_eq!(n, 0);
+                    assert!(rest.is_empty());
+                }
+            }
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn short_term_names_match_horizons() {
+        let short_names = Scalars::NAMES
+            .iter()
+            .filter(|n| n.starts_with("short_"))
+            .collect::<Vec<_>>();
+        assert_eq!(short_names.len(), 4 * SHORT_TERM_HORIZONS.len());
+
+        for (i, horizon) in SHORT_TERM_HORIZONS.iter().enumerate() {
+            for (j, suffix) in ["v", "wdl_w", "wdl_d", "wdl_l"].iter().enumerate() {
+                assert_eq!(*short_names[4 * i + j], format!("short_{}_{}", horizon, suffix));
+            }
+        }
+    }
+
+    #[test]
+    fn short_term_value_decay() {
+        // values are from the POV of the next player, so the second one is good for player A
+        let simulation = ttt_simulation(&[0.4, -0.4]);
+        let outcome = Outcome::WonBy(Player::A);
+
+        // lambda = 0.5, the remaining weight goes to the outcome
+        let values = short_term_values(&simulation.positions, outcome, 2);
+        let value_a = |i: usize| values[i].pov(Player::A).value.value;
+
+        let expected_1 = 0.5 * 0.4 + 0.5 * 1.0;
+        let expected_0 = 0.5 * 0.4 + 0.5 * expected_1;
+        assert!((value_a(1) - expected_1).abs() < 1e-6, "got {}", value_a(1));
+        assert!((value_a(0) - expected_0).abs() < 1e-6, "got {}", value_a(0));
+
+        // a horizon of 1 only looks at the position itself
+        let values = short_term_values(&simulation.positions, outcome, 1);
+        assert!((values[0].pov(Player::A).value.value - 0.4).abs() < 1e-6);
+        assert!((values[1].pov(Player::A).value.value - 0.4).abs() < 1e-6);
+    }
+}
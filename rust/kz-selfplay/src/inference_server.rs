@@ -0,0 +1,283 @@
+use std::io::{BufReader, BufWriter, Write};
+use std::net::{TcpListener, TcpStream};
+
+use itertools::Itertools;
+use kn_graph::dtype::{DTensor, Tensor};
+use kn_graph::graph::Graph;
+use kn_graph::ndarray::IxDyn;
+use kn_runtime::{Device, PreparedGraph};
+
+use kz_core::network::job_channel::{job_pair, JobClient};
+use kz_core::network::remote::{
+    read_f32s, read_u32, write_f32s, write_u32, REMOTE_MAGIC, REMOTE_STATUS_OK, REMOTE_STATUS_SHAPE_MISMATCH,
+};
+
+use crate::server::executor::{batched_executor_loop, RunCondition};
+
+/// A single encoded input.
+type Input = Vec<f32>;
+/// The outputs for a single input, one vec per graph output.
+type Output = Vec<Vec<f32>>;
+
+/// Serve `graph` to all clients that connect to `listener`, see `kz_core::network::remote::RemoteNetwork`.
+/// Requests from all clients are merged into batches of up to `batch_size` and evaluated on a single executor.
+///
+/// This function only returns once the listener fails.
+pub fn inference_server_main(graph: Graph, device: Device, batch_size: usize, listener: TcpListener) {
+    let inputs = graph.inputs();
+    assert_eq!(inputs.len(), 1, "Expected graph with a single input");
+    let input_shape = graph[inputs[0]].shape.eval(1).dims[1..].to_vec();
+
+    println!(
+        "Serving graph with input shape {:?} on {:?}",
+        input_shape,
+        listener.local_addr()
+    );
+
+    let (client, server) = job_pair::<Input, Output>(4);
+
+    let executor_input_shape = input_shape.clone();
+    std::thread::Builder::new()
+        .name("executor".to_owned())
+        .spawn(move || {
+            let (graph_sender, graph_receiver) = flume::bounded(1);
+            graph_sender.send(Some(graph)).unwrap();
+            drop(graph_sender);
+
+            batched_executor_loop(
+                batch_size,
+                RunCondition::Any,
+                graph_receiver,
+                server,
+                |graph| device.prepare(graph, batch_size),
+                |prepared, batch_x| evaluate_batch(prepared, batch_size, &executor_input_shape, batch_x),
+            );
+        })
+        .unwrap();
+
+    for (i, stream) in listener.incoming().enumerate() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Failed to accept connection: {:?}", e);
+                continue;
+            }
+        };
+
+        let client = client.clone();
+        let input_shape = input_shape.clone();
+
+        std::thread::Builder::new()
+            .name(format!("connection-{}", i))
+            .spawn(move || {
+                let peer = stream.peer_addr().ok();
+                println!("Client {:?} connected", peer);
+                match handle_connection(stream, &input_shape, batch_size, &client) {
+                    Ok(()) => println!("Client {:?} disconnected", peer),
+                    Err(e) => eprintln!("Client {:?} failed: {:?}", peer, e),
+                }
+            })
+            .unwrap();
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    input_shape: &[usize],
+    max_batch_size: usize,
+    client: &JobClient<Input, Output>,
+) -> std::io::Result<()> {
+    stream.set_nodelay(true)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = BufWriter::new(stream);
+
+    // handshake
+    let magic = read_u32(&mut reader)?;
+    if magic != REMOTE_MAGIC {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Wrong magic {:#x}", magic),
+        ));
+    }
+
+    let rank = read_u32(&mut reader)?;
+    let client_shape = (0..rank)
+        .map(|_| read_u32(&mut reader).map(|d| d as usize))
+        .collect::<std::io::Result<Vec<usize>>>()?;
+
+    if client_shape != input_shape {
+        write_u32(&mut writer, REMOTE_STATUS_SHAPE_MISMATCH)?;
+        writer.flush()?;
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "Input shape mismatch, client {:?} graph {:?}",
+                client_shape, input_shape
+            ),
+        ));
+    }
+    write_u32(&mut writer, REMOTE_STATUS_OK)?;
+    write_u32(&mut writer, max_batch_size as u32)?;
+    writer.flush()?;
+
+    let input_len = input_shape.iter().product::<usize>();
+
+    loop {
+        // a disconnect between requests is the normal way for a client to stop
+        let batch_size = match read_u32(&mut reader) {
+            Ok(batch_size) => batch_size as usize,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        // the client was told the max batch size during the handshake, a larger batch is a protocol error
+        //   and would otherwise make us allocate an arbitrarily large input buffer
+        if batch_size > max_batch_size {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Batch size {} larger than max {}", batch_size, max_batch_size),
+            ));
+        }
+
+        let input = read_f32s(&mut reader, batch_size * input_len)?;
+        let x = input.chunks_exact(input_len).map(|c| c.to_vec()).collect_vec();
+        let y = client.map_blocking(x);
+
+        // transpose back to output-major
+        let output_count = y.first().map_or(0, |y| y.len());
+        write_u32(&mut writer, output_count as u32)?;
+        for oi in 0..output_count {
+            let item_len = y[0][oi].len();
+            write_u32(&mut writer, item_len as u32)?;
+            for item in &y {
+                write_f32s(&mut writer, &item[oi])?;
+            }
+        }
+        writer.flush()?;
+    }
+}
+
+fn evaluate_batch(
+    prepared: &mut PreparedGraph,
+    batch_size: usize,
+    input_shape: &[usize],
+    batch_x: &[Input],
+) -> Vec<Output> {
+    let input_len = input_shape.iter().product::<usize>();
+
+    // concatenate and pad the inputs
+    let mut input = Vec::with_capacity(batch_size * input_len);
+    for x in batch_x {
+        input.extend_from_slice(x);
+    }
+    input.resize(batch_size * input_len, 0.0);
+
+    let mut full_shape = vec![batch_size];
+    full_shape.extend_from_slice(input_shape);
+    let input = Tensor::from_shape_vec(IxDyn(&full_shape), input).unwrap();
+
+    let outputs = prepared.eval(&[DTensor::F32(input)]);
+    let outputs = outputs
+        .iter()
+        .map(|t| t.unwrap_f32().unwrap().as_slice().unwrap())
+        .collect_vec();
+
+    // split the outputs per item, dropping the padding
+    (0..batch_x.len())
+        .map(|bi| {
+            outputs
+                .iter()
+                .map(|output| {
+                    let item_len = output.len() / batch_size;
+                    output[bi * item_len..(bi + 1) * item_len].to_vec()
+                })
+                .collect_vec()
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use board_game::board::{Board, BoardMoves};
+    use board_game::games::ttt::TTTBoard;
+    use internal_iterator::InternalIterator;
+    use kn_graph::dtype::DType;
+    use kn_graph::graph::Graph;
+    use kn_graph::shape;
+    use kn_graph::shape::{Shape, Size};
+    use kn_runtime::Device;
+
+    use kz_core::mapping::ttt::TTTStdMapper;
+    use kz_core::mapping::{InputMapper, PolicyMapper};
+    use kz_core::network::cpu::CPUNetwork;
+    use kz_core::network::remote::RemoteNetwork;
+    use kz_core::network::Network;
+
+    use super::inference_server_main;
+
+    /// A linear (scalars, policy) network with fixed pseudo-random weights.
+    fn linear_graph(mapper: TTTStdMapper) -> Graph {
+        let input_shape = mapper.input_full_shape();
+        let input_len = input_shape.iter().product::<usize>();
+        let policy_len = mapper.policy_len();
+
+        let mut graph = Graph::new();
+        let input = graph.input(Shape::fixed(&input_shape).batched(), DType::F32);
+        let flat = graph.flatten(input, 1);
+
+        let mut weight = |len: usize| {
+            let data = (0..input_len * len)
+                .map(|i| ((i * 7919) % 13) as f32 / 13.0 - 0.5)
+                .collect();
+            let weight = graph.constant(shape![input_len, len], data);
+            graph.mat_mul(flat, weight)
+        };
+        let scalars = weight(5);
+        let policy = weight(policy_len);
+
+        let policy_shape = shape![Size::BATCH].concat(&Shape::fixed(mapper.policy_shape()));
+        let policy = graph.view(policy, policy_shape);
+
+        graph.output_all(&[scalars, policy]);
+        graph
+    }
+
+    #[test]
+    fn remote_matches_local() {
+        let mapper = TTTStdMapper;
+        let graph = linear_graph(mapper);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_graph = graph.clone();
+        // the server never returns, the thread is cleaned up when the test process exits
+        std::thread::spawn(move || inference_server_main(server_graph, Device::Cpu, 4, listener));
+
+        let mut boards = vec![TTTBoard::default()];
+        for _ in 0..3 {
+            let board = boards.last().unwrap();
+            let mv = board.available_moves().unwrap().next().unwrap();
+            boards.push(board.clone_and_play(mv).unwrap());
+        }
+
+        let mut local = CPUNetwork::new(mapper, graph, None).unwrap();
+        let mut remote = RemoteNetwork::connect(mapper, addr).unwrap();
+        assert_eq!(remote.max_batch_size(), 4);
+
+        let expected = local.try_evaluate_batch(&boards).unwrap();
+        let actual = remote.try_evaluate_batch(&boards).unwrap();
+
+        assert_eq!(expected.len(), actual.len());
+        for (e, a) in expected.iter().zip(&actual) {
+            let close = |x: f32, y: f32| (x - y).abs() < 1e-5;
+            assert!(close(e.values.value.value, a.values.value.value), "{:?} != {:?}", e, a);
+            assert!(close(e.values.moves_left, a.values.moves_left), "{:?} != {:?}", e, a);
+            assert_eq!(e.policy.len(), a.policy.len());
+            for (&pe, &pa) in e.policy.iter().zip(a.policy.iter()) {
+                assert!(close(pe, pa), "{:?} != {:?}", e, a);
+            }
+        }
+    }
+}
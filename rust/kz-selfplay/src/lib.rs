@@ -1,4 +1,5 @@
 pub mod binary_output;
+pub mod inference_server;
 pub mod move_selector;
 pub mod simulation;
 pub mod superluminal;
@@ -1,61 +1,354 @@
+use std::fmt::{Display, Formatter};
+use std::num::{ParseFloatError, ParseIntError};
+use std::str::FromStr;
+
 use decorum::N32;
 use itertools::Itertools;
 use rand::distributions::WeightedIndex;
 use rand::Rng;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct MoveSelector {
-    /// The temperature applied to the policy before sampling. Can be any positive value.
-    /// * `0.0`: always pick the move with highest policy
-    /// * `inf`: pick a completely random move
-    pub temperature: f32,
+    /// The temperature applied to the policy before sampling, as a function of the move count.
+    pub schedule: TemperatureSchedule,
+
+    /// Only moves with at least this fraction of the policy of the best move are considered for sampling.
+    /// * `0.0`: all moves are considered
+    /// * `1.0`: only the best move(s) are considered
+    pub visit_threshold: f32,
+
+    /// Whether the value target for a position should be taken from the best move,
+    /// even if a different move was sampled and played.
+    pub best_value_target: bool,
+}
 
-    /// After this number of moves, use temperature zero to always select the best move.
-    pub zero_temp_move_count: u32,
+/// The temperature to use as a function of the number of moves already played. All temperatures can be any positive value.
+/// * `0.0`: always pick the move with highest policy
+/// * `inf`: pick a completely random move
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemperatureSchedule {
+    /// Use `temperature` for the first `zero_temp_move_count` moves, then switch to temperature zero.
+    Step {
+        temperature: f32,
+        zero_temp_move_count: u32,
+    },
+    /// Start at `start` and multiply by `decay` after every move, but never go below `min`.
+    Exponential { start: f32, decay: f32, min: f32 },
+    /// Linearly interpolate from `start` to `end` over the first `move_count` moves, then stay at `end`.
+    Linear { start: f32, end: f32, move_count: u32 },
+    /// Piecewise constant temperature for different phases of the game.
+    /// Each phase is `(start_move, temperature)`, the phases are sorted by `start_move` and the first one starts at zero.
+    Phases(Vec<(u32, f32)>),
 }
 
 impl MoveSelector {
-    pub fn new(temperature: f32, zero_temp_move_count: u32) -> Self {
+    pub fn new(schedule: TemperatureSchedule, visit_threshold: f32, best_value_target: bool) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&visit_threshold),
+            "Visit threshold must be in [0, 1], got {}",
+            visit_threshold
+        );
         MoveSelector {
-            temperature,
-            zero_temp_move_count,
+            schedule,
+            visit_threshold,
+            best_value_target,
         }
     }
 
     /// Always select the move with the maximum policy, ie. temperature 0.
     pub fn zero_temp() -> Self {
-        Self::new(0.0, 0)
+        Self::constant_temp(0.0)
     }
 
     pub fn constant_temp(temperature: f32) -> Self {
-        MoveSelector {
-            temperature,
-            zero_temp_move_count: u32::MAX,
-        }
+        Self::new(TemperatureSchedule::constant(temperature), 0.0, false)
     }
 }
 
 impl MoveSelector {
     pub fn select(&self, move_count: u32, policy: &[f32], rng: &mut impl Rng) -> usize {
-        let temperature = if move_count >= self.zero_temp_move_count {
-            0.0
-        } else {
-            self.temperature
-        };
+        let temperature = self.schedule.temperature(move_count);
         assert!(temperature >= 0.0);
 
         // we handle the extreme cases separately, in theory that would not be necessary but they're degenerate
         if temperature == 0.0 {
-            // pick the best move
-            policy.iter().copied().map(N32::from).position_max().unwrap()
-        } else if temperature == f32::INFINITY {
-            // pick a random move
-            rng.gen_range(0..policy.len())
+            return self.best(policy);
+        }
+
+        // only keep moves that got enough visits compared to the best move
+        let max_p = policy.iter().copied().fold(0.0, f32::max);
+        let threshold = self.visit_threshold * max_p;
+        let keep = |p: f32| p > 0.0 && p >= threshold;
+
+        if temperature == f32::INFINITY {
+            // pick a random move, only among the allowed moves if there is a threshold
+            if self.visit_threshold == 0.0 {
+                rng.gen_range(0..policy.len())
+            } else {
+                let candidates = (0..policy.len()).filter(|&i| keep(policy[i])).collect_vec();
+                candidates[rng.gen_range(0..candidates.len())]
+            }
         } else {
             // pick according to `policy ** (1/temperature)`
-            let policy_temp = policy.iter().map(|p| p.powf(1.0 / temperature));
+            let policy_temp = policy
+                .iter()
+                .map(|&p| if keep(p) { p.powf(1.0 / temperature) } else { 0.0 });
             let distr = WeightedIndex::new(policy_temp).unwrap();
             rng.sample(distr)
         }
     }
+
+    /// The move with the highest policy, this is the move that would be selected with temperature zero.
+    pub fn best(&self, policy: &[f32]) -> usize {
+        policy.iter().copied().map(N32::from).position_max().unwrap()
+    }
+}
+
+impl TemperatureSchedule {
+    pub fn constant(temperature: f32) -> Self {
+        TemperatureSchedule::Step {
+            temperature,
+            zero_temp_move_count: u32::MAX,
+        }
+    }
+
+    pub fn temperature(&self, move_count: u32) -> f32 {
+        match *self {
+            TemperatureSchedule::Step {
+                temperature,
+                zero_temp_move_count,
+            } => {
+                if move_count >= zero_temp_move_count {
+                    0.0
+                } else {
+                    temperature
+                }
+            }
+            TemperatureSchedule::Exponential { start, decay, min } => {
+                f32::max(start * decay.powf(move_count as f32), min)
+            }
+            TemperatureSchedule::Linear {
+                start,
+                end,
+                move_count: n,
+            } => {
+                if move_count >= n {
+                    end
+                } else {
+                    let t = move_count as f32 / n as f32;
+                    start + (end - start) * t
+                }
+            }
+            TemperatureSchedule::Phases(ref phases) => phases
+                .iter()
+                .take_while(|&&(start_move, _)| start_move <= move_count)
+                .last()
+                .map_or(0.0, |&(_, temperature)| temperature),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ScheduleParseError {
+    Prefix(String),
+    Format(String),
+    Float(ParseFloatError),
+    Int(ParseIntError),
+    /// A temperature or decay that is negative or NaN.
+    Value(String),
+}
+
+impl Display for TemperatureSchedule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            TemperatureSchedule::Step {
+                temperature,
+                zero_temp_move_count,
+            } => {
+                if zero_temp_move_count == u32::MAX {
+                    write!(f, "constant:{}", temperature)
+                } else {
+                    write!(f, "step:{}@{}", temperature, zero_temp_move_count)
+                }
+            }
+            TemperatureSchedule::Exponential { start, decay, min } => write!(f, "exp:{},{},{}", start, decay, min),
+            TemperatureSchedule::Linear { start, end, move_count } => {
+                write!(f, "linear:{},{},{}", start, end, move_count)
+            }
+            TemperatureSchedule::Phases(ref phases) => {
+                write!(f, "phases:")?;
+                for (i, &(start_move, temperature)) in phases.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}@{}", temperature, start_move)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parse a schedule from a string, the supported formats are:
+/// * `constant:T`
+/// * `step:T@N`: temperature `T` for the first `N` moves, zero afterwards
+/// * `exp:T,D,M`: start at `T`, multiply by `D` every move, clamp to at least `M`
+/// * `linear:T0,T1,N`: interpolate from `T0` to `T1` over `N` moves
+/// * `phases:T0@0,T1@N1,T2@N2`: temperature `Ti` starting from move `Ni`
+impl FromStr for TemperatureSchedule {
+    type Err = ScheduleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_f32 = |s: &str| f32::from_str(s).map_err(ScheduleParseError::Float);
+        // infinity is fine, it means picking a random move, but NaN and negative values would panic when selecting
+        let parse_non_negative = |s: &str| {
+            let x = parse_f32(s)?;
+            if x >= 0.0 {
+                Ok(x)
+            } else {
+                Err(ScheduleParseError::Value(s.to_owned()))
+            }
+        };
+        let parse_u32 = |s: &str| u32::from_str(s).map_err(ScheduleParseError::Int);
+        let format_err = || ScheduleParseError::Format(s.to_owned());
+
+        if let Some(rest) = s.strip_prefix("constant:") {
+            return Ok(TemperatureSchedule::constant(parse_non_negative(rest)?));
+        }
+
+        if let Some(rest) = s.strip_prefix("step:") {
+            let (temperature, count) = rest.split_once('@').ok_or_else(format_err)?;
+            return Ok(TemperatureSchedule::Step {
+                temperature: parse_non_negative(temperature)?,
+                zero_temp_move_count: parse_u32(count)?,
+            });
+        }
+
+        if let Some(rest) = s.strip_prefix("exp:") {
+            let (start, decay, min) = rest.split(',').collect_tuple().ok_or_else(format_err)?;
+            return Ok(TemperatureSchedule::Exponential {
+                start: parse_non_negative(start)?,
+                decay: parse_non_negative(decay)?,
+                min: parse_non_negative(min)?,
+            });
+        }
+
+        if let Some(rest) = s.strip_prefix("linear:") {
+            let (start, end, count) = rest.split(',').collect_tuple().ok_or_else(format_err)?;
+            return Ok(TemperatureSchedule::Linear {
+                start: parse_non_negative(start)?,
+                end: parse_non_negative(end)?,
+                move_count: parse_u32(count)?,
+            });
+        }
+
+        if let Some(rest) = s.strip_prefix("phases:") {
+            let mut phases = vec![];
+            for phase in rest.split(',') {
+                let (temperature, start_move) = phase.split_once('@').ok_or_else(format_err)?;
+                phases.push((parse_u32(start_move)?, parse_non_negative(temperature)?));
+            }
+
+            let sorted = phases.windows(2).all(|w| w[0].0 < w[1].0);
+            if phases.first().map(|p| p.0) != Some(0) || !sorted {
+                return Err(format_err());
+            }
+            return Ok(TemperatureSchedule::Phases(phases));
+        }
+
+        Err(ScheduleParseError::Prefix(s.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_string() {
+        let cases = [
+            ("constant:1.5", TemperatureSchedule::constant(1.5)),
+            (
+                "step:1@30",
+                TemperatureSchedule::Step {
+                    temperature: 1.0,
+                    zero_temp_move_count: 30,
+                },
+            ),
+            (
+                "exp:1,0.9,0.1",
+                TemperatureSchedule::Exponential {
+                    start: 1.0,
+                    decay: 0.9,
+                    min: 0.1,
+                },
+            ),
+            (
+                "linear:1,0.25,40",
+                TemperatureSchedule::Linear {
+                    start: 1.0,
+                    end: 0.25,
+                    move_count: 40,
+                },
+            ),
+            (
+                "phases:1@0,0.5@20,0@60",
+                TemperatureSchedule::Phases(vec![(0, 1.0), (20, 0.5), (60, 0.0)]),
+            ),
+        ];
+
+        for (s, schedule) in cases {
+            assert_eq!(TemperatureSchedule::from_str(s), Ok(schedule.clone()));
+            assert_eq!(schedule.to_string(), s);
+        }
+
+        assert!(TemperatureSchedule::from_str("phases:1@5").is_err());
+        assert!(TemperatureSchedule::from_str("exp:1,2").is_err());
+    }
+
+    #[test]
+    fn schedule_invalid_values() {
+        for s in [
+            "constant:-1",
+            "constant:NaN",
+            "constant:-inf",
+            "step:-0.5@10",
+            "exp:NaN,0.9,0.1",
+            "exp:1,-0.9,0.1",
+            "exp:1,0.9,-0.1",
+            "linear:1,-1,10",
+            "phases:-0.5@0",
+            "phases:1@0,NaN@10",
+        ] {
+            let result = TemperatureSchedule::from_str(s);
+            assert!(
+                matches!(result, Err(ScheduleParseError::Value(_))),
+                "{} should be rejected, got {:?}",
+                s,
+                result
+            );
+        }
+
+        // infinity is a valid temperature, it means picking a random move
+        assert_eq!(
+            TemperatureSchedule::from_str("constant:inf"),
+            Ok(TemperatureSchedule::constant(f32::INFINITY))
+        );
+    }
+
+    #[test]
+    fn schedule_temperature() {
+        let phases = TemperatureSchedule::Phases(vec![(0, 1.0), (20, 0.5), (60, 0.0)]);
+        assert_eq!(phases.temperature(0), 1.0);
+        assert_eq!(phases.temperature(20), 0.5);
+        assert_eq!(phases.temperature(100), 0.0);
+
+        let linear = TemperatureSchedule::Linear {
+            start: 1.0,
+            end: 0.0,
+            move_count: 10,
+        };
+        assert_eq!(linear.temperature(5), 0.5);
+        assert_eq!(linear.temperature(20), 0.0);
+    }
 }
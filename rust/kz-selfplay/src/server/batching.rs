@@ -0,0 +1,291 @@
+use std::time::Duration;
+
+use itertools::Itertools;
+
+use crate::server::executor::RunCondition;
+
+/// Decides when the executor evaluates a batch and how many items it takes from the queue.
+pub trait BatchPolicy {
+    /// The largest number of items that will ever be evaluated at once.
+    fn max_batch_size(&self) -> usize;
+
+    /// The condition for starting an evaluation when there are fewer than `max_batch_size` items queued.
+    fn run_condition(&self) -> RunCondition;
+
+    /// The number of items to evaluate next, given the current number of queued items.
+    /// The result must be at least one and at most `min(queue_len, max_batch_size)`.
+    fn next_batch_size(&mut self, queue_len: usize) -> usize;
+
+    /// Called after each evaluated batch.
+    /// `queue_wait` is the time the oldest job in the batch spent waiting in the queue.
+    fn record_batch(&mut self, items: usize, queue_wait: Duration, eval_time: Duration);
+}
+
+/// A single batch size and a fixed run condition, the historical executor behaviour.
+#[derive(Debug, Copy, Clone)]
+pub struct FixedBatching {
+    pub max_batch_size: usize,
+    pub run_condition: RunCondition,
+}
+
+impl BatchPolicy for FixedBatching {
+    fn max_batch_size(&self) -> usize {
+        self.max_batch_size
+    }
+
+    fn run_condition(&self) -> RunCondition {
+        self.run_condition
+    }
+
+    fn next_batch_size(&mut self, queue_len: usize) -> usize {
+        queue_len.min(self.max_batch_size)
+    }
+
+    fn record_batch(&mut self, _: usize, _: Duration, _: Duration) {}
+}
+
+/// Summary of the decisions of an [AdaptiveBatching] policy over the last window of batches.
+#[derive(Debug, Clone)]
+pub struct BatchingReport {
+    /// The `JobCount` threshold at the end of the window.
+    pub job_count: usize,
+    /// For each compiled batch size, the number of batches that used it.
+    pub size_counts: Vec<(usize, u64)>,
+    /// The fraction of the compiled batches that was filled with real items.
+    pub fill: f32,
+    /// The mean time the oldest job of each batch waited in the queue.
+    pub mean_queue_wait: Duration,
+    /// The mean time it took to evaluate a batch.
+    pub mean_eval_time: Duration,
+}
+
+/// Batch policy that picks between several compiled batch sizes based on the queue length and the measured
+/// latency of each size, and tunes the `JobCount` threshold online.
+///
+/// The threshold is increased while batches are underfilled, and decreased when jobs wait in the queue longer
+/// than it takes to evaluate a batch, since at that point the generators are stalling on the executor.
+pub struct AdaptiveBatching<R: FnMut(BatchingReport)> {
+    /// The compiled batch sizes, sorted ascending.
+    sizes: Vec<usize>,
+    /// Exponential moving average of the evaluation time for each size, `None` if not measured yet.
+    latency: Vec<Option<f32>>,
+
+    job_count: usize,
+    max_job_count: usize,
+    target_fill: f32,
+    window_size: u64,
+
+    window: Window,
+    report: R,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    batches: u64,
+    items: u64,
+    potential: u64,
+    queue_wait: f32,
+    eval_time: f32,
+    size_counts: Vec<u64>,
+}
+
+const LATENCY_DECAY: f32 = 0.1;
+
+impl<R: FnMut(BatchingReport)> AdaptiveBatching<R> {
+    /// * `sizes`: the compiled batch sizes, the network passed to the executor must support all of them.
+    /// * `max_job_count`: the upper bound for the `JobCount` threshold,
+    ///     this must be low enough that the generators can always submit that many jobs at once.
+    /// * `report`: called with the decisions after every `window_size` batches.
+    pub fn new(sizes: &[usize], max_job_count: usize, target_fill: f32, window_size: u64, report: R) -> Self {
+        assert!(!sizes.is_empty(), "Need at least one batch size");
+        assert!(
+            sizes.iter().all(|&s| s > 0),
+            "Batch sizes must be positive, got {:?}",
+            sizes
+        );
+        assert!(max_job_count > 0);
+        assert!(window_size > 0);
+
+        let sizes = sizes.iter().copied().sorted().dedup().collect_vec();
+        let latency = vec![None; sizes.len()];
+        let window = Window {
+            size_counts: vec![0; sizes.len()],
+            ..Default::default()
+        };
+
+        AdaptiveBatching {
+            sizes,
+            latency,
+            job_count: max_job_count,
+            max_job_count,
+            target_fill,
+            window_size,
+            window,
+            report,
+        }
+    }
+
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    pub fn job_count(&self) -> usize {
+        self.job_count
+    }
+
+    /// The index of the smallest compiled size that fits `items`.
+    fn size_index(&self, items: usize) -> usize {
+        self.sizes
+            .iter()
+            .position(|&s| s >= items)
+            .unwrap_or_else(|| panic!("No compiled batch size fits {} items, sizes {:?}", items, self.sizes))
+    }
+
+    /// Estimated items per second when evaluating `items` using the size at `index`.
+    /// Unmeasured sizes are estimated as infinitely fast so they get tried at least once.
+    fn throughput(&self, index: usize, items: usize) -> f32 {
+        match self.latency[index] {
+            None => f32::INFINITY,
+            Some(latency) => items as f32 / latency,
+        }
+    }
+
+    fn tune_job_count(&mut self) {
+        let w = &self.window;
+        let fill = w.items as f32 / w.potential as f32;
+        let mean_wait = w.queue_wait / w.batches as f32;
+        let mean_eval = w.eval_time / w.batches as f32;
+
+        if mean_wait > mean_eval {
+            // generators are waiting on us, evaluate earlier
+            self.job_count = (self.job_count - 1).max(1);
+        } else if fill < self.target_fill {
+            // we can afford to wait for more jobs
+            self.job_count = (self.job_count + 1).min(self.max_job_count);
+        }
+    }
+
+    fn finish_window(&mut self) {
+        self.tune_job_count();
+
+        let w = std::mem::take(&mut self.window);
+        let report = BatchingReport {
+            job_count: self.job_count,
+            size_counts: self.sizes.iter().copied().zip(w.size_counts).collect(),
+            fill: w.items as f32 / w.potential as f32,
+            mean_queue_wait: Duration::from_secs_f32(w.queue_wait / w.batches as f32),
+            mean_eval_time: Duration::from_secs_f32(w.eval_time / w.batches as f32),
+        };
+        (self.report)(report);
+
+        self.window.size_counts = vec![0; self.sizes.len()];
+    }
+}
+
+impl<R: FnMut(BatchingReport)> BatchPolicy for AdaptiveBatching<R> {
+    fn max_batch_size(&self) -> usize {
+        *self.sizes.last().unwrap()
+    }
+
+    fn run_condition(&self) -> RunCondition {
+        RunCondition::JobCount(self.job_count)
+    }
+
+    fn next_batch_size(&mut self, queue_len: usize) -> usize {
+        assert!(queue_len > 0);
+        let max_batch_size = self.max_batch_size();
+        if queue_len >= max_batch_size {
+            return max_batch_size;
+        }
+
+        // either pad everything into the next larger size, or fill a smaller size completely and leave the rest
+        let upper = self.size_index(queue_len);
+        let lower = self.sizes.iter().rposition(|&s| s <= queue_len);
+
+        match lower {
+            Some(lower)
+                if lower != upper && self.throughput(lower, self.sizes[lower]) > self.throughput(upper, queue_len) =>
+            {
+                self.sizes[lower]
+            }
+            _ => queue_len,
+        }
+    }
+
+    fn record_batch(&mut self, items: usize, queue_wait: Duration, eval_time: Duration) {
+        let index = self.size_index(items);
+        let eval_time = eval_time.as_secs_f32();
+
+        let latency = &mut self.latency[index];
+        *latency = Some(match *latency {
+            None => eval_time,
+            Some(prev) => prev + LATENCY_DECAY * (eval_time - prev),
+        });
+
+        let w = &mut self.window;
+        w.batches += 1;
+        w.items += items as u64;
+        w.potential += self.sizes[index] as u64;
+        w.queue_wait += queue_wait.as_secs_f32();
+        w.eval_time += eval_time;
+        w.size_counts[index] += 1;
+
+        if w.batches >= self.window_size {
+            self.finish_window();
+        }
+    }
+}
+
+impl<R: FnMut(BatchingReport)> std::fmt::Debug for AdaptiveBatching<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdaptiveBatching")
+            .field("sizes", &self.sizes)
+            .field("latency", &self.latency)
+            .field("job_count", &self.job_count)
+            .field("max_job_count", &self.max_job_count)
+            .field("target_fill", &self.target_fill)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptive_prefers_faster_size() {
+        let mut policy = AdaptiveBatching::new(&[16, 64], 4, 0.9, 1000, |_| {});
+
+        // unmeasured sizes are tried first
+        assert_eq!(policy.next_batch_size(100), 64);
+        assert_eq!(policy.next_batch_size(40), 40);
+
+        // filling the small size completely is faster than padding to the large one
+        policy.record_batch(16, Duration::ZERO, Duration::from_millis(1));
+        policy.record_batch(40, Duration::ZERO, Duration::from_millis(10));
+        assert_eq!(policy.next_batch_size(40), 16);
+    }
+
+    #[test]
+    fn adaptive_tunes_job_count() {
+        let mut reports = vec![];
+
+        {
+            let mut policy = AdaptiveBatching::new(&[8], 4, 0.9, 2, |r| reports.push(r.job_count));
+
+            // long queue waits decrease the threshold
+            for _ in 0..4 {
+                policy.record_batch(8, Duration::from_millis(10), Duration::from_millis(1));
+            }
+            assert_eq!(policy.job_count(), 2);
+
+            // underfilled batches without waiting increase it again
+            for _ in 0..2 {
+                policy.record_batch(2, Duration::ZERO, Duration::from_millis(1));
+            }
+            assert_eq!(policy.job_count(), 3);
+        }
+
+        assert_eq!(reports, vec![3, 2, 3]);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write as _;
 use std::fs::create_dir_all;
 use std::io::{BufWriter, Write as _};
@@ -10,6 +10,7 @@ use flume::Receiver;
 use kz_core::mapping::BoardMapper;
 
 use crate::binary_output::BinaryOutput;
+use crate::server::batching::BatchingReport;
 use crate::server::protocol::{Evals, GeneratorUpdate, ServerUpdate};
 
 pub fn collector_main<B: Board>(
@@ -39,6 +40,9 @@ pub fn collector_main<B: Board>(
 
     let mut last_print_time = Instant::now();
     let mut curr_game_lengths = HashMap::new();
+    let mut batching_reports = BTreeMap::new();
+    // total finished games for each board size, only for mappers that support multiple sizes
+    let mut board_sizes = BTreeMap::new();
 
     for update in update_receiver {
         match update {
@@ -62,6 +66,9 @@ pub fn collector_main<B: Board>(
             } => {
                 counter.games += 1;
                 curr_game_lengths.remove(&generator_id);
+                if let Some(size) = mapper.board_size(simulation.start_board()) {
+                    *board_sizes.entry(size).or_insert(0) += 1;
+                }
 
                 // write file to disk, possibly starting a new generation
                 curr_output
@@ -75,12 +82,7 @@ pub fn collector_main<B: Board>(
                     curr_gen += 1;
                     curr_output = new_output(curr_gen);
 
-                    let message = ServerUpdate::FinishedFile { index: prev_i };
-                    writer
-                        .write_all(serde_json::to_string(&message).unwrap().as_bytes())
-                        .unwrap();
-                    writer.write_all(&[b'\n']).unwrap();
-                    writer.flush().unwrap();
+                    write_update(&mut writer, &ServerUpdate::FinishedFile { index: prev_i });
                 }
             }
             GeneratorUpdate::RootEvals(evals) => {
@@ -89,6 +91,13 @@ pub fn collector_main<B: Board>(
             GeneratorUpdate::ExpandEvals(evals) => {
                 counter.expand_evals += evals;
             }
+            GeneratorUpdate::Batching { executor, report } => {
+                batching_reports.insert(executor, report);
+            }
+            GeneratorUpdate::Error(message) => {
+                eprintln!("Collector: forwarding error {:?}", message);
+                write_update(&mut writer, &ServerUpdate::Error { message });
+            }
         }
 
         // periodically print stats
@@ -99,7 +108,15 @@ pub fn collector_main<B: Board>(
             total_moves += counter.moves;
 
             let info = counter
-                .to_string(delta, total_moves, total_games, &curr_game_lengths, muzero)
+                .to_string(
+                    delta,
+                    total_moves,
+                    total_games,
+                    &curr_game_lengths,
+                    &batching_reports,
+                    &board_sizes,
+                    muzero,
+                )
                 .unwrap();
             print!("{}", info);
 
@@ -108,8 +125,12 @@ pub fn collector_main<B: Board>(
         }
     }
 
+    write_update(&mut writer, &ServerUpdate::Stopped);
+}
+
+fn write_update(writer: &mut impl std::io::Write, update: &ServerUpdate) {
     writer
-        .write_all(serde_json::to_string(&ServerUpdate::Stopped).unwrap().as_bytes())
+        .write_all(serde_json::to_string(update).unwrap().as_bytes())
         .unwrap();
     writer.write_all(&[b'\n']).unwrap();
     writer.flush().unwrap()
@@ -131,6 +152,8 @@ impl Counter {
         total_moves: u64,
         total_games: u64,
         game_lengths: &HashMap<usize, usize>,
+        batching_reports: &BTreeMap<String, BatchingReport>,
+        board_sizes: &BTreeMap<usize, u64>,
         muzero: bool,
     ) -> Result<String, std::fmt::Error> {
         let move_throughput = self.moves as f32 / delta;
@@ -164,6 +187,21 @@ impl Counter {
             max_game_length,
             mean_game_length
         )?;
+        if !board_sizes.is_empty() {
+            writeln!(f, "  games per board size: {:?}", board_sizes)?;
+        }
+        for (executor, report) in batching_reports {
+            writeln!(
+                f,
+                "  batching {}: job count {}, fill {:.2}, queue wait {:.2}ms, eval {:.2}ms, sizes {:?}",
+                executor,
+                report.job_count,
+                report.fill,
+                report.mean_queue_wait.as_secs_f32() * 1000.0,
+                report.mean_eval_time.as_secs_f32() * 1000.0,
+                report.size_counts,
+            )?;
+        }
 
         Ok(result)
     }
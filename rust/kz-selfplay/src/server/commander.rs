@@ -5,6 +5,7 @@ use std::sync::Arc;
 use board_game::board::Board;
 use flume::Sender;
 
+use kz_core::error::NetworkError;
 use kz_core::network::dummy::{DummyNetwork, NetworkOrDummy};
 
 use crate::server::protocol::{Command, GeneratorUpdate, Settings};
@@ -15,7 +16,7 @@ pub fn commander_main<B: Board, G>(
     settings_senders: Vec<Sender<Settings>>,
     graph_senders: Vec<GraphSender<G>>,
     update_sender: Sender<GeneratorUpdate<B>>,
-    load_graph: impl Fn(&str) -> G,
+    load_graph: impl Fn(&str) -> Result<G, NetworkError>,
 ) {
     let send_graph_command = |command: GraphMessage<G>| {
         for sender in &graph_senders {
@@ -28,20 +29,37 @@ pub fn commander_main<B: Board, G>(
 
         match cmd {
             Command::StartupSettings(_) => panic!("Already received startup settings"),
-            Command::NewSettings(settings) => {
-                for sender in &settings_senders {
-                    sender.send(settings.clone()).unwrap();
+            Command::NewSettings(settings) => match settings.validate() {
+                Ok(()) => {
+                    for sender in &settings_senders {
+                        sender.send(settings.clone()).unwrap();
+                    }
                 }
-            }
+                Err(e) => {
+                    eprintln!("Rejecting invalid settings: {}", e);
+                    update_sender
+                        .send(GeneratorUpdate::Error(format!("Invalid settings: {}", e)))
+                        .unwrap();
+                }
+            },
             Command::NewNetwork(path) => {
                 println!("Commander loading & optimizing new network {:?}", path);
-                let graph = load_graph(&path);
+                match load_graph(&path) {
+                    Ok(graph) => {
+                        // put it in an arc so we don't need to clone it a bunch of times
+                        let fused = Arc::new(graph);
 
-                // put it in an arc so we don't need to clone it a bunch of times
-                let fused = Arc::new(graph);
-
-                println!("Sending new network to executors");
-                send_graph_command(Some(NetworkOrDummy::Left(Arc::clone(&fused))));
+                        println!("Sending new network to executors");
+                        send_graph_command(Some(NetworkOrDummy::Left(Arc::clone(&fused))));
+                    }
+                    Err(e) => {
+                        // don't silently continue with a dummy or stale network, let python decide what to do
+                        let message = format!("Failed to load network {:?}: {}", path, e);
+                        eprintln!("{}", message);
+                        send_graph_command(None);
+                        update_sender.send(GeneratorUpdate::Error(message)).unwrap();
+                    }
+                }
             }
             Command::WaitForNewNetwork => {
                 println!("Waiting for new network");
@@ -1,6 +1,6 @@
-use std::cmp::min;
 use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter};
+use std::time::{Duration, Instant};
 
 use board_game::board::Board;
 use flume::{Receiver, RecvError, Selector, Sender, TryRecvError};
@@ -15,6 +15,7 @@ use kz_core::network::cudnn::CudaNetwork;
 use kz_core::network::job_channel::{Job, JobServer};
 use kz_core::network::{Network, ZeroEvaluation};
 
+use crate::server::batching::{BatchPolicy, FixedBatching};
 use crate::superluminal::{CL_BLUE, CL_GREEN, CL_YELLOW};
 
 #[derive(Debug, Copy, Clone)]
@@ -29,11 +30,27 @@ pub fn batched_executor_loop<G, N, X, Y>(
     run_condition: RunCondition,
     graph_receiver: Receiver<Option<G>>,
     server: JobServer<X, Y>,
+    load_network: impl FnMut(G) -> N,
+    evaluate_batch: impl FnMut(&mut N, &[X]) -> Vec<Y>,
+) {
+    let policy = FixedBatching {
+        max_batch_size,
+        run_condition,
+    };
+    batched_executor_loop_with_policy(policy, graph_receiver, server, load_network, evaluate_batch)
+}
+
+/// Same as [batched_executor_loop], but the batch sizes and run condition are decided by `policy`.
+/// `evaluate_batch` must accept any batch size returned by [BatchPolicy::next_batch_size].
+pub fn batched_executor_loop_with_policy<G, N, X, Y>(
+    mut policy: impl BatchPolicy,
+    graph_receiver: Receiver<Option<G>>,
+    server: JobServer<X, Y>,
     mut load_network: impl FnMut(G) -> N,
     mut evaluate_batch: impl FnMut(&mut N, &[X]) -> Vec<Y>,
 ) {
     let thread_name = std::thread::current().name().unwrap_or("unnamed").to_owned();
-    assert_ne!(max_batch_size, 0, "Got batch size 0 for {}", thread_name);
+    assert_ne!(policy.max_batch_size(), 0, "Got batch size 0 for {}", thread_name);
 
     let job_receiver = server.into_receiver();
 
@@ -80,7 +97,7 @@ pub fn batched_executor_loop<G, N, X, Y>(
 
                         // check for additional jobs (non-blocking)
                         //   we could loop again but we might as well just fall back to the outer loop
-                        while state.x.len() < max_batch_size {
+                        while state.x.len() < policy.max_batch_size() {
                             match job_receiver.try_recv() {
                                 // yay, we've got an additional job
                                 Ok(job) => state.push_job(job),
@@ -92,8 +109,8 @@ pub fn batched_executor_loop<G, N, X, Y>(
                         }
 
                         // optionally evaluate some batches
-                        if state.should_eval(run_condition, max_batch_size) {
-                            run_eval(&mut state, network, &mut evaluate_batch, max_batch_size);
+                        while state.should_eval(policy.run_condition(), policy.max_batch_size()) {
+                            run_eval(&mut state, network, &mut evaluate_batch, &mut policy);
                         }
 
                         continue;
@@ -102,9 +119,8 @@ pub fn batched_executor_loop<G, N, X, Y>(
                         // the job channel has disconnected
 
                         // evaluate all remaining jobs if any
-                        assert!(state.items_to_eval() < max_batch_size);
-                        if state.items_to_eval() > 0 {
-                            run_eval(&mut state, network, &mut evaluate_batch, max_batch_size);
+                        while state.items_to_eval() > 0 {
+                            run_eval(&mut state, network, &mut evaluate_batch, &mut policy);
                         }
                         assert!(state.items_to_eval() == 0 && state.items_to_send() == 0);
 
@@ -162,7 +178,7 @@ pub fn alphazero_batched_executor_loop<B: Board, M: BoardMapper<B>>(
         run_condition,
         graph_receiver,
         server,
-        |graph| CudaNetwork::new(mapper, &graph, max_batch_size, device),
+        |graph| CudaNetwork::new(mapper, &graph, max_batch_size, device, None).unwrap(),
         |network, batch_x| network.evaluate_batch(&batch_x),
     )
 }
@@ -175,7 +191,7 @@ enum Message<G, J> {
 
 struct State<X, Y> {
     x: VecDeque<X>,
-    senders: VecDeque<(usize, Sender<Vec<Y>>)>,
+    senders: VecDeque<(usize, Instant, Sender<Vec<Y>>)>,
     leftover_y: VecDeque<Y>,
 }
 
@@ -216,7 +232,7 @@ impl<X, Y> State<X, Y> {
     }
 
     fn items_to_send(&self) -> usize {
-        self.senders.iter().map(|&(len, _)| len).sum::<usize>()
+        self.senders.iter().map(|&(len, _, _)| len).sum::<usize>()
     }
 
     fn push_job(&mut self, job: Job<X, Y>) {
@@ -230,7 +246,7 @@ impl<X, Y> State<X, Y> {
             // avoid ever putting empty senders in the queue since that introduces tricky edge cases
             let _ = sender.send(vec![]);
         } else {
-            self.senders.push_back((x.len(), sender));
+            self.senders.push_back((x.len(), Instant::now(), sender));
             self.x.extend(x.into_iter());
         }
 
@@ -252,9 +268,15 @@ impl<X, Y> State<X, Y> {
         }
     }
 
-    fn get_batch(&mut self, max_batch_size: usize) -> &[X] {
-        let batch_size = min(self.x.len(), max_batch_size);
-        assert_ne!(batch_size, 0);
+    /// The time the oldest unanswered job has been waiting.
+    fn oldest_wait(&self) -> Duration {
+        self.senders
+            .front()
+            .map_or(Duration::ZERO, |&(_, time, _)| time.elapsed())
+    }
+
+    fn get_batch(&mut self, batch_size: usize) -> &[X] {
+        assert!(batch_size != 0 && batch_size <= self.x.len());
 
         if self.x.as_slices().0.len() < batch_size {
             self.x.make_contiguous();
@@ -269,7 +291,7 @@ impl<X, Y> State<X, Y> {
                 assert_eq!(self.leftover_y.len(), 0);
                 false
             }
-            Some(&(count, _)) => self.leftover_y.len() >= count,
+            Some(&(count, _, _)) => self.leftover_y.len() >= count,
         }
     }
 
@@ -284,14 +306,14 @@ impl<X, Y> State<X, Y> {
 
         if self.leftover_y.is_empty() && self.senders[0].0 == batch_size {
             // shortcut to avoid extra copies, just send the entire vec immediately
-            let _ = self.senders.pop_front().unwrap().1.send(batch_y);
+            let _ = self.senders.pop_front().unwrap().2.send(batch_y);
         } else {
             // add results to temporary storage
             self.leftover_y.extend(batch_y.into_iter());
 
             // send as many values out as possible
             while self.can_fill_next_sender() {
-                let (count, sender) = self.senders.pop_front().unwrap();
+                let (count, _, sender) = self.senders.pop_front().unwrap();
                 let block_y = self.leftover_y.drain(0..count).collect_vec();
                 let _ = sender.send(block_y);
             }
@@ -305,11 +327,16 @@ fn run_eval<X, Y, N>(
     state: &mut State<X, Y>,
     network: &mut N,
     mut evaluate_batch: impl FnMut(&mut N, &[X]) -> Vec<Y>,
-    max_batch_size: usize,
+    policy: &mut impl BatchPolicy,
 ) {
     begin_event_with_color("run", CL_GREEN);
-    let batch_x = state.get_batch(max_batch_size);
+    let batch_size = policy.next_batch_size(state.items_to_eval());
+    let queue_wait = state.oldest_wait();
+
+    let start = Instant::now();
+    let batch_x = state.get_batch(batch_size);
     let batch_y = evaluate_batch(network, batch_x);
+    policy.record_batch(batch_size, queue_wait, start.elapsed());
     end_event();
 
     begin_event_with_color("reply", CL_YELLOW);
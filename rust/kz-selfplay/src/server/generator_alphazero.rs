@@ -1,13 +1,15 @@
 use board_game::board::Board;
 use board_game::games::max_length::MaxMovesBoard;
+use board_game::pov::NonPov;
 use flume::{Receiver, TryRecvError};
 use itertools::Itertools;
-use lru::LruCache;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
 use std::hash::Hash;
+use std::sync::Arc;
 
+use kz_core::mapping::BoardMapper;
 use kz_core::network::common::policy_softmax_temperature_in_place;
 use kz_core::network::{EvalClient, ZeroEvaluation};
 use kz_core::zero::step::{zero_step_apply, zero_step_gather, ZeroRequest};
@@ -15,28 +17,34 @@ use kz_core::zero::tree::Tree;
 use kz_util::sequence::zip_eq_exact;
 use kz_util::stable_dirichlet::StableDirichlet;
 
-use crate::move_selector::MoveSelector;
 use crate::server::protocol::{Evals, GeneratorUpdate, Settings};
 use crate::server::server::UpdateSender;
-use crate::simulation::{Position, Simulation};
+use crate::server::shared_cache::SharedCache;
+use crate::simulation::{ChildStats, Position, Simulation};
 
-pub async fn generator_alphazero_main<B: Board + Hash>(
+pub async fn generator_alphazero_main<B: Board + Hash, M: BoardMapper<B>>(
     generator_id: usize,
+    mapper: M,
     start_pos: impl Fn(&mut StdRng) -> B,
     settings_receiver: Receiver<Settings>,
     search_batch_size: usize,
     eval_client: EvalClient<B>,
+    cache: Arc<SharedCache<B>>,
     update_sender: UpdateSender<B>,
 ) {
     let mut rng = StdRng::from_entropy();
 
     // wait for initial settings
     let mut settings = settings_receiver.recv_async().await.unwrap();
+    cache.resize(settings.cache_size);
 
     loop {
         // possibly get new settings
         match settings_receiver.try_recv() {
-            Ok(new_settings) => settings = new_settings,
+            Ok(new_settings) => {
+                settings = new_settings;
+                cache.resize(settings.cache_size);
+            }
             Err(TryRecvError::Empty) => (),
             Err(TryRecvError::Disconnected) => break,
         }
@@ -47,10 +55,12 @@ pub async fn generator_alphazero_main<B: Board + Hash>(
 
         let simulation = generate_simulation(
             generator_id,
+            mapper,
             &settings,
             search_batch_size,
             &update_sender,
             &eval_client,
+            &cache,
             start_pos(&mut rng),
             &mut rng,
         )
@@ -65,23 +75,21 @@ pub async fn generator_alphazero_main<B: Board + Hash>(
     }
 }
 
-type Cache<B> = LruCache<B, ZeroEvaluation<'static>>;
-
-async fn generate_simulation<B: Board + Hash>(
+async fn generate_simulation<B: Board + Hash, M: BoardMapper<B>>(
     generator_id: usize,
+    mapper: M,
     settings: &Settings,
     search_batch_size: usize,
     update_sender: &UpdateSender<B>,
     eval_client: &EvalClient<B>,
+    cache: &SharedCache<B>,
     start: B,
     rng: &mut impl Rng,
 ) -> Simulation<'static, B> {
-    // create a new cache for every game, to prevent long-term stale values for short games
-    // TODO maybe explicitly clear the cache when a new network is loaded instead?
-    let mut cache: Cache<B> = LruCache::new(settings.cache_size);
-
     let mut positions = vec![];
 
+    let move_selector = settings.move_selector();
+
     let max_moves = settings.max_game_length.unwrap_or(u64::MAX);
     let mut curr_board = MaxMovesBoard::new(start, max_moves);
 
@@ -96,23 +104,39 @@ async fn generate_simulation<B: Board + Hash>(
 
         // run tree search
         let (tree, cached_evals, net_evaluation) = build_tree(
+            mapper,
             settings,
             search_batch_size,
             eval_client,
-            &mut cache,
+            cache,
             &curr_board,
             target_visits,
             rng,
         )
         .await;
-        let zero_evaluation = tree.eval();
+        let mut zero_evaluation = tree.eval();
+        let child_stats = root_child_stats(&tree, &net_evaluation);
 
         // pick a move to play
-        let move_selector = MoveSelector::new(settings.temperature, settings.zero_temp_move_count);
         let picked_index = move_selector.select(positions.len() as u32, zero_evaluation.policy.as_ref(), rng);
         let picked_child = tree[0].children.unwrap().get(picked_index);
         let picked_move = tree[picked_child].last_move.unwrap();
 
+        // use the value of the best move as the target, the picked move might be a bad exploration move
+        if move_selector.best_value_target {
+            let best_index = move_selector.best(zero_evaluation.policy.as_ref());
+            if best_index != picked_index {
+                let best_child = tree[0].children.unwrap().get(best_index);
+                zero_evaluation.values = tree[best_child].values().pov(curr_board.next_player());
+            }
+        }
+
+        // only prune the recorded policy target, the move itself is picked from the full visit distribution
+        if settings.policy_target_pruning {
+            let policy = tree.policy_pruned(settings.weights.to_uct(), settings.q_mode.0);
+            zero_evaluation.policy = policy.into();
+        }
+
         // record position
         let position = Position {
             board: curr_board.inner().clone(),
@@ -121,6 +145,7 @@ async fn generate_simulation<B: Board + Hash>(
             zero_visits: tree.root_visits(),
             zero_evaluation,
             net_evaluation,
+            child_stats: Some(child_stats),
         };
         positions.push(position);
 
@@ -148,11 +173,28 @@ async fn generate_simulation<B: Board + Hash>(
     }
 }
 
-async fn build_tree<B: Board + Hash>(
+fn root_child_stats<B: Board>(tree: &Tree<B>, net_evaluation: &ZeroEvaluation) -> Vec<ChildStats> {
+    let root_player = tree.root_board().next_player();
+    let children = tree[0].children.unwrap();
+
+    zip_eq_exact(children, net_evaluation.policy.iter())
+        .map(|(child, &prior)| {
+            let node = &tree[child];
+            ChildStats {
+                visits: node.complete_visits,
+                q: node.values().pov(root_player).value.value,
+                prior,
+            }
+        })
+        .collect()
+}
+
+async fn build_tree<B: Board + Hash, M: BoardMapper<B>>(
+    mapper: M,
     settings: &Settings,
     search_batch_size: usize,
     eval_client: &EvalClient<B>,
-    cache: &mut Cache<B>,
+    cache: &SharedCache<B>,
     curr_board: &MaxMovesBoard<B>,
     target_visits: u64,
     rng: &mut impl Rng,
@@ -161,14 +203,22 @@ async fn build_tree<B: Board + Hash>(
     let mut cached_evals = 0;
     let mut root_net_eval = None;
 
+    // only boards that actually finished have a meaningful score, not the ones that hit the move limit
+    let final_score = |board: &MaxMovesBoard<B>| {
+        let inner = board.inner();
+        inner.is_done().then(|| mapper.final_score(inner)).flatten()
+    };
+
     while tree.root_visits() < target_visits {
         let mut requests = vec![];
         let mut terminal_gathers = 0;
+        let generation = cache.generation();
 
         // collect a batch of requests
         while requests.len() < search_batch_size && terminal_gathers < search_batch_size {
             let request = zero_step_gather(
                 &mut tree,
+                &final_score,
                 settings.weights.to_uct(),
                 settings.q_mode.0,
                 settings.search_fpu_root.0,
@@ -186,7 +236,7 @@ async fn build_tree<B: Board + Hash>(
                         //   (for selfplay we usually use small batches sizes so it's not that bad)
                         Some(eval) => {
                             cached_evals += 1;
-                            apply_eval(&mut tree, request, eval.clone(), &mut root_net_eval, settings, rng);
+                            apply_eval(&mut tree, request, eval, &mut root_net_eval, settings, rng);
                         }
                         None => {
                             requests.push(request);
@@ -205,7 +255,7 @@ async fn build_tree<B: Board + Hash>(
 
         // apply all of them
         for (request, eval) in zip_eq_exact(requests, evals) {
-            cache.put(request.board.inner().clone(), eval.clone());
+            cache.insert(generation, request.board.inner(), eval.clone());
             apply_eval(&mut tree, request, eval, &mut root_net_eval, settings, rng);
         }
     }
@@ -42,15 +42,20 @@ pub async fn generator_muzero_main<B: AltBoard, M: BoardMapper<B>>(
 ) {
     // wait for initial settings
     let mut settings = settings_receiver.recv_async().await.unwrap();
+    let mut move_selector = settings.move_selector();
     let mut pool: Option<DevicePool> = None;
 
     let mut rng = StdRng::from_entropy();
-    let state_size = saved_state_channels * mapper.state_board_size() * mapper.state_board_size();
+    let [state_h, state_w] = mapper.state_board_shape();
+    let state_size = saved_state_channels * state_h * state_w;
 
     loop {
         // possibly get new settings
         match settings_receiver.try_recv() {
-            Ok(new_settings) => settings = new_settings,
+            Ok(new_settings) => {
+                settings = new_settings;
+                move_selector = settings.move_selector();
+            }
             Err(TryRecvError::Empty) => (),
             Err(TryRecvError::Disconnected) => break,
         }
@@ -72,6 +77,7 @@ pub async fn generator_muzero_main<B: AltBoard, M: BoardMapper<B>>(
         let simulation = generate_simulation(
             generator_id,
             &settings,
+            &move_selector,
             &update_sender,
             &root_client,
             &expand_client,
@@ -96,6 +102,7 @@ pub async fn generator_muzero_main<B: AltBoard, M: BoardMapper<B>>(
 async fn generate_simulation<B: AltBoard, M: BoardMapper<B>>(
     generator_id: usize,
     settings: &Settings,
+    move_selector: &MoveSelector,
     update_sender: &UpdateSender<B>,
     root_client: &RootClient<B>,
     expand_client: &ExpandClient,
@@ -194,12 +201,20 @@ async fn generate_simulation<B: AltBoard, M: BoardMapper<B>>(
 
         // extract stats
         let net_evaluation = root_net_eval.unwrap();
-        let zero_evaluation = tree.eval();
+        let mut zero_evaluation = tree.eval();
 
         //pick a move to play
-        let move_selector = MoveSelector::new(settings.temperature, settings.zero_temp_move_count);
         let picked_index = move_selector.select(positions.len() as u32, zero_evaluation.policy.as_ref(), rng);
         let picked_child = tree[0].inner.as_ref().unwrap().children.get(picked_index);
+
+        // use the value of the best move as the target, the picked move might be a bad exploration move
+        if move_selector.best_value_target {
+            let best_index = move_selector.best(zero_evaluation.policy.as_ref());
+            if best_index != picked_index {
+                let best_child = tree[0].inner.as_ref().unwrap().children.get(best_index);
+                zero_evaluation.values = tree[best_child].values();
+            }
+        }
         let picked_move_index = tree[picked_child].last_move_index.unwrap();
         let picked_move = mapper.index_to_move(tree.root_board(), picked_move_index).unwrap();
 
@@ -211,6 +226,8 @@ async fn generate_simulation<B: AltBoard, M: BoardMapper<B>>(
             zero_visits: tree.root_visits(),
             zero_evaluation,
             net_evaluation,
+            // TODO add child stats for MuZero too, the root children are available in the tree
+            child_stats: None,
         };
         positions.push(position);
 
@@ -293,5 +310,6 @@ fn extract_zero_eval<B: AltBoard, M: BoardMapper<B>>(
     ZeroEvaluation {
         values: eval.values,
         policy: Cow::Owned(policy),
+        aux: None,
     }
 }
@@ -6,6 +6,7 @@ mod server_alphazero;
 #[cfg(feature = "muzero")]
 mod server_muzero;
 
+pub mod batching;
 pub mod collector;
 pub mod commander;
 pub mod executor;
@@ -15,4 +16,4 @@ pub mod generator_alphazero;
 pub mod generator_muzero;
 
 pub mod rebatcher;
-pub mod start_pos;
+pub mod shared_cache;
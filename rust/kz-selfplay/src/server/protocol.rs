@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use kz_core::zero::node::UctWeights;
 use kz_core::zero::step::{FpuMode, QMode};
 
+use crate::move_selector::{MoveSelector, TemperatureSchedule};
+use crate::server::batching::BatchingReport;
 use crate::server::serde_helper::ToFromStringArg;
 use crate::simulation::Simulation;
 
@@ -17,13 +19,19 @@ pub struct StartupSettings {
     pub output_folder: String,
     pub games_per_gen: usize,
 
-    // TODO implement some kind of adaptive batch sizing, especially for root
     pub cpu_threads_per_device: usize,
     pub gpu_threads_per_device: usize,
     pub gpu_batch_size: usize,
     pub gpu_batch_size_root: usize,
     pub search_batch_size: usize,
 
+    // adaptive batch sizing, only implemented for the AlphaZero executors
+    // `gpu_batch_size` is always included in the compiled sizes and is the maximum
+    #[serde(default)]
+    pub adaptive_batching: bool,
+    #[serde(default)]
+    pub gpu_batch_sizes: Vec<usize>,
+
     pub saved_state_channels: usize,
     pub eval_random_symmetries: bool,
 }
@@ -69,6 +77,14 @@ pub enum GeneratorUpdate<B: Board> {
 
     ExpandEvals(Evals),
     RootEvals(Evals),
+
+    Batching {
+        executor: String,
+        report: BatchingReport,
+    },
+
+    /// Something went wrong that the python side should know about, forwarded as [ServerUpdate::Error].
+    Error(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -76,6 +92,7 @@ pub enum GeneratorUpdate<B: Board> {
 pub enum ServerUpdate {
     Stopped,
     FinishedFile { index: u32 },
+    Error { message: String },
 }
 
 //TODO split this into AlphaZero and MuZero structs, the overlap is getting pretty small
@@ -87,8 +104,9 @@ pub struct Settings {
     pub weights: Weights,
     pub q_mode: ToFromStringArg<QMode>,
 
-    pub temperature: f32,
-    pub zero_temp_move_count: u32,
+    pub temperature: ToFromStringArg<TemperatureSchedule>,
+    pub temperature_visit_threshold: f32,
+    pub temperature_best_value_target: bool,
 
     pub dirichlet_alpha: f32,
     pub dirichlet_eps: f32,
@@ -98,6 +116,7 @@ pub struct Settings {
     pub search_fpu_root: ToFromStringArg<FpuMode>,
     pub search_fpu_child: ToFromStringArg<FpuMode>,
     pub search_virtual_loss_weight: f32,
+    pub policy_target_pruning: bool,
 
     pub full_search_prob: f64,
     pub full_iterations: u64,
@@ -106,6 +125,7 @@ pub struct Settings {
     pub top_moves: usize,
 
     // performance
+    // the number of cached evaluations per generator, the generators of a device share a cache of the combined size
     pub cache_size: usize,
 }
 
@@ -115,6 +135,9 @@ pub struct Weights {
     pub moves_left_weight: Option<f32>,
     pub moves_left_clip: Option<f32>,
     pub moves_left_sharpness: Option<f32>,
+    pub score_weight: Option<f32>,
+    pub score_scale: Option<f32>,
+    pub forced_playouts_k: Option<f32>,
 }
 
 impl Weights {
@@ -125,7 +148,31 @@ impl Weights {
             moves_left_weight: self.moves_left_weight.unwrap_or(default.moves_left_weight),
             moves_left_clip: self.moves_left_clip.unwrap_or(default.moves_left_clip),
             moves_left_sharpness: self.moves_left_sharpness.unwrap_or(default.moves_left_sharpness),
+            score_weight: self.score_weight.unwrap_or(default.score_weight),
+            score_scale: self.score_scale.unwrap_or(default.score_scale),
+            forced_playouts_k: self.forced_playouts_k.unwrap_or(default.forced_playouts_k),
+        }
+    }
+}
+
+impl Settings {
+    /// Check the settings that would otherwise only cause a panic deep inside the generators.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.temperature_visit_threshold) {
+            return Err(format!(
+                "temperature_visit_threshold must be in [0, 1], got {}",
+                self.temperature_visit_threshold
+            ));
         }
+        Ok(())
+    }
+
+    pub fn move_selector(&self) -> MoveSelector {
+        MoveSelector::new(
+            self.temperature.0.clone(),
+            self.temperature_visit_threshold,
+            self.temperature_best_value_target,
+        )
     }
 }
 
@@ -5,27 +5,17 @@ use std::path::Path;
 use std::sync::Arc;
 
 use board_game::board::{AltBoard, Board};
-use board_game::games::arimaa::ArimaaBoard;
-use board_game::games::chess::ChessBoard;
-use board_game::games::sttt::STTTBoard;
-use board_game::games::ttt::TTTBoard;
 use clap::Parser;
 use crossbeam::thread::Scope;
 use flume::{Receiver, Sender};
 use itertools::Itertools;
 use kn_cuda_sys::wrapper::handle::CudaDevice;
 use rand::rngs::StdRng;
-use trictrac_bot::trictrac_board::TrictracBoard;
-
-use kz_core::mapping::arimaa::ArimaaSplitMapper;
-use kz_core::mapping::ataxx::AtaxxStdMapper;
-use kz_core::mapping::chess::{ChessHistoryMapper, ChessStdMapper};
-use kz_core::mapping::go::GoStdMapper;
-use kz_core::mapping::sttt::STTTStdMapper;
-use kz_core::mapping::trictrac::TrictracStdMapper;
-use kz_core::mapping::ttt::TTTStdMapper;
+
+use kz_core::error::{parse_game, NetworkError};
 use kz_core::mapping::BoardMapper;
 use kz_core::network::dummy::NetworkOrDummy;
+use kz_core::registry::{visit_game, GameEntry, GameVisitor};
 use kz_util::game::Game;
 
 use crate::server::collector::collector_main;
@@ -34,7 +24,6 @@ use crate::server::protocol::{Command, GeneratorUpdate, Settings, StartupSetting
 use crate::server::server_alphazero::AlphaZeroSpecialization;
 #[cfg(feature = "muzero")]
 use crate::server::server_muzero::MuZeroSpecialization;
-use crate::server::start_pos::{ataxx_start_pos, go_start_pos};
 
 #[derive(Debug, clap::Parser)]
 struct Args {
@@ -96,119 +85,52 @@ pub fn selfplay_server_main() {
         startup_settings.output_folder
     );
 
-    let game =
-        Game::parse(&startup_settings.game).unwrap_or_else(|| panic!("Unknown game '{}'", startup_settings.game));
+    let game = parse_game(&startup_settings.game).unwrap_or_else(|e| panic!("{}", e));
 
-    selfplay_start_dispatch_game(game, devices, startup_settings, writer, reader)
+    let visitor = SelfplayVisitor {
+        devices,
+        startup_settings,
+        writer,
+        reader,
+    };
+    visit_game(game, visitor)
 }
 
-fn selfplay_start_dispatch_game(
-    game: Game,
+/// Starts selfplay for the visited game, using the alternating specializations if possible.
+struct SelfplayVisitor<R, W> {
     devices: Vec<CudaDevice>,
     startup_settings: StartupSettings,
-    writer: BufWriter<&TcpStream>,
-    reader: BufReader<&TcpStream>,
-) {
-    //TODO static dispatch this early means we're generating a lot of code N times
-    //  is it actually that much? -> investigate with objdump or similar
-    //  would it be relatively easy to delay this dispatch some more?
-    match game {
-        Game::TTT => {
-            assert_eq!(startup_settings.start_pos, "default");
-            selfplay_start_dispatch_spec_alt(
-                game,
-                devices,
-                startup_settings,
-                |_| TTTBoard::default(),
-                TTTStdMapper,
-                reader,
-                writer,
-            )
-        }
-        Game::STTT => {
-            assert_eq!(startup_settings.start_pos, "default");
-            selfplay_start_dispatch_spec_alt(
-                game,
-                devices,
-                startup_settings,
-                |_| STTTBoard::default(),
-                STTTStdMapper,
-                reader,
-                writer,
-            )
-        }
-        Game::Ataxx { size } => {
-            let start_pos = ataxx_start_pos(size, &startup_settings.start_pos);
-            selfplay_start_dispatch_spec_alt(
-                game,
-                devices,
-                startup_settings,
-                start_pos,
-                AtaxxStdMapper::new(size),
-                reader,
-                writer,
-            )
-        }
-        Game::Chess => {
-            assert_eq!(startup_settings.start_pos, "default");
-            selfplay_start_dispatch_spec_alt(
-                game,
-                devices,
-                startup_settings,
-                |_| ChessBoard::default(),
-                ChessStdMapper,
-                reader,
-                writer,
-            )
-        }
-        Game::ChessHist { length } => {
-            assert_eq!(startup_settings.start_pos, "default");
-            selfplay_start_dispatch_spec_alt(
-                game,
-                devices,
-                startup_settings,
-                |_| ChessBoard::default(),
-                ChessHistoryMapper::new(length),
-                reader,
-                writer,
-            )
-        }
-        Game::ArimaaSplit => {
-            assert_eq!(startup_settings.start_pos, "default");
-            selfplay_start_dispatch_spec_non_alt(
-                game,
-                devices,
-                startup_settings,
-                |_| ArimaaBoard::default(),
-                ArimaaSplitMapper,
-                reader,
-                writer,
-            )
-        }
-        Game::Trictrac => {
-            assert_eq!(startup_settings.start_pos, "default");
-            selfplay_start_dispatch_spec_non_alt(
-                game,
-                devices,
-                startup_settings,
-                |_| TrictracBoard::default(),
-                TrictracStdMapper,
-                reader,
-                writer,
-            )
-        }
-        Game::Go { size } => {
-            let start_pos = go_start_pos(size, &startup_settings.start_pos);
-            selfplay_start_dispatch_spec_non_alt(
-                game,
-                devices,
-                startup_settings,
-                start_pos,
-                GoStdMapper::new(size, true),
-                reader,
-                writer,
-            )
-        }
+    writer: BufWriter<W>,
+    reader: BufReader<R>,
+}
+
+impl<R: Read + Send, W: Write + Send> GameVisitor for SelfplayVisitor<R, W> {
+    type Output = ();
+
+    fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) {
+        let start_pos = entry.start_pos(&self.startup_settings.start_pos);
+        selfplay_start_dispatch_spec_non_alt(
+            entry.game,
+            self.devices,
+            self.startup_settings,
+            start_pos,
+            entry.mapper,
+            self.reader,
+            self.writer,
+        )
+    }
+
+    fn visit_alt<B: AltBoard + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) {
+        let start_pos = entry.start_pos(&self.startup_settings.start_pos);
+        selfplay_start_dispatch_spec_alt(
+            entry.game,
+            self.devices,
+            self.startup_settings,
+            start_pos,
+            entry.mapper,
+            self.reader,
+            self.writer,
+        )
     }
 }
 
@@ -312,7 +234,9 @@ pub trait ZeroSpecialization<B: Board, M: BoardMapper<B> + 'static> {
         update_sender: UpdateSender<B>,
     ) -> (Vec<Sender<Settings>>, Vec<GraphSender<Self::G>>);
 
-    fn load_graph(&self, path: &str, mapper: M, startup: &StartupSettings) -> Self::G;
+    /// Load the graph at `path`. On error the commander stops the executors (by sending them `None`)
+    /// and reports the error to python as a [GeneratorUpdate::Error].
+    fn load_graph(&self, path: &str, mapper: M, startup: &StartupSettings) -> Result<Self::G, NetworkError>;
 }
 
 fn selfplay_start<B: Board, M: BoardMapper<B> + 'static, Z: ZeroSpecialization<B, M> + Send + Sync>(
@@ -325,7 +249,7 @@ fn selfplay_start<B: Board, M: BoardMapper<B> + 'static, Z: ZeroSpecialization<B
     writer: BufWriter<impl Write + Send>,
     spec: Z,
 ) {
-    assert!(!devices.is_empty());
+    assert!(!devices.is_empty(), "Selfplay needs at least one device");
 
     let total_cpu_threads = startup.cpu_threads_per_device * devices.len();
     let startup = &startup;
@@ -375,3 +299,53 @@ fn selfplay_start<B: Board, M: BoardMapper<B> + 'static, Z: ZeroSpecialization<B
     })
     .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use super::*;
+
+    fn startup_settings(game: Game) -> StartupSettings {
+        StartupSettings {
+            game: game.to_string(),
+            muzero: false,
+            start_pos: "default".to_owned(),
+            first_gen: 0,
+            output_folder: std::env::temp_dir().to_str().unwrap().to_owned(),
+            games_per_gen: 1,
+            cpu_threads_per_device: 1,
+            gpu_threads_per_device: 1,
+            gpu_batch_size: 1,
+            gpu_batch_size_root: 0,
+            search_batch_size: 1,
+            adaptive_batching: false,
+            gpu_batch_sizes: vec![],
+            saved_state_channels: 0,
+            eval_random_symmetries: false,
+        }
+    }
+
+    /// Without devices selfplay can't actually start,
+    /// but everything up to that point (start positions, mapper, specialization dispatch) still runs.
+    #[test]
+    fn visitor_all_games() {
+        for game in Game::examples() {
+            println!("Visiting {}", game);
+
+            let visitor = SelfplayVisitor {
+                devices: vec![],
+                startup_settings: startup_settings(game),
+                writer: BufWriter::new(std::io::sink()),
+                reader: BufReader::new(std::io::empty()),
+            };
+
+            let payload = catch_unwind(AssertUnwindSafe(|| visit_game(game, visitor))).unwrap_err();
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str));
+            assert_eq!(message, Some("Selfplay needs at least one device"), "Game {}", game);
+        }
+    }
+}
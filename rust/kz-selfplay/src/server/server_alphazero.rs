@@ -1,4 +1,5 @@
 use std::hash::Hash;
+use std::sync::Arc;
 
 use board_game::board::Board;
 use crossbeam::thread::Scope;
@@ -6,22 +7,38 @@ use flume::Sender;
 use futures::executor::ThreadPoolBuilder;
 use kn_cuda_sys::wrapper::handle::CudaDevice;
 use kn_graph::graph::Graph;
-use kn_graph::onnx::load_graph_from_onnx_path;
-use kn_graph::optimizer::optimize_graph;
 use rand::rngs::StdRng;
+use rand::rngs::ThreadRng;
 use rand::thread_rng;
 
+use kz_core::error::NetworkError;
 use kz_core::mapping::BoardMapper;
+use kz_core::network::common::{check_graph_shapes, try_load_graph};
 use kz_core::network::cudnn::CudaNetwork;
+use kz_core::network::dummy::NetworkOrDummy;
 use kz_core::network::job_channel::job_pair;
+use kz_core::network::metadata::NetworkMetadata;
+use kz_core::network::multibatch::MultiBatchNetwork;
 use kz_core::network::symmetry::RandomSymmetryNetwork;
 use kz_core::network::Network;
 use kz_util::math::ceil_div;
 
-use crate::server::executor::{batched_executor_loop, RunCondition};
+use crate::server::batching::AdaptiveBatching;
+use crate::server::executor::{batched_executor_loop, batched_executor_loop_with_policy, RunCondition};
 use crate::server::generator_alphazero::generator_alphazero_main;
 use crate::server::protocol::{Evals, GeneratorUpdate, Settings, StartupSettings};
 use crate::server::server::{GraphSender, ZeroSpecialization};
+use crate::server::shared_cache::SharedCache;
+
+/// The number of independently locked shards in the shared eval cache.
+const CACHE_SHARD_COUNT: usize = 64;
+
+/// The fraction of the compiled batch the adaptive executors try to fill before evaluating earlier.
+const ADAPTIVE_TARGET_FILL: f32 = 0.9;
+/// The number of batches between adaptive batching adjustments and reports.
+const ADAPTIVE_WINDOW_SIZE: u64 = 64;
+
+type ExpandNetwork<B, M> = NetworkOrDummy<RandomSymmetryNetwork<B, MultiBatchNetwork<CudaNetwork<B, M>>, ThreadRng>>;
 
 #[derive(Debug)]
 pub struct AlphaZeroSpecialization;
@@ -44,6 +61,9 @@ impl<B: Board + Hash, M: BoardMapper<B> + 'static> ZeroSpecialization<B, M> for
         let cpu_threads = startup.cpu_threads_per_device;
         let gpu_threads = startup.gpu_threads_per_device;
 
+        let adaptive_batching = startup.adaptive_batching;
+        let batch_sizes = compiled_batch_sizes(startup);
+
         let concurrent_games = ceil_div((gpu_threads + 1) * gpu_batch_size, search_batch_size);
         let eval_job_count = gpu_batch_size / search_batch_size;
 
@@ -55,6 +75,9 @@ impl<B: Board + Hash, M: BoardMapper<B> + 'static> ZeroSpecialization<B, M> for
         let job_buffer_size = ceil_div(gpu_threads * gpu_batch_size, search_batch_size);
         let (eval_client, eval_server) = job_pair(job_buffer_size);
 
+        // the capacity is set by the generators once they receive the settings
+        let cache = Arc::new(SharedCache::new(CACHE_SHARD_COUNT, concurrent_games));
+
         // spawn cpu threads
         let pool = ThreadPoolBuilder::new()
             .pool_size(cpu_threads)
@@ -67,6 +90,7 @@ impl<B: Board + Hash, M: BoardMapper<B> + 'static> ZeroSpecialization<B, M> for
 
             let start_pos = start_pos.clone();
             let eval_client = eval_client.clone();
+            let cache = Arc::clone(&cache);
             let update_sender = update_sender.clone();
 
             let (settings_sender, settings_receiver) = flume::bounded(1);
@@ -75,10 +99,12 @@ impl<B: Board + Hash, M: BoardMapper<B> + 'static> ZeroSpecialization<B, M> for
             pool.spawn_ok(async move {
                 generator_alphazero_main(
                     generator_id,
+                    mapper,
                     start_pos,
                     settings_receiver,
                     search_batch_size,
                     eval_client,
+                    cache,
                     update_sender,
                 )
                 .await;
@@ -91,31 +117,67 @@ impl<B: Board + Hash, M: BoardMapper<B> + 'static> ZeroSpecialization<B, M> for
             graph_senders.push(graph_sender);
 
             let eval_server = eval_server.clone();
+            let cache = Arc::clone(&cache);
             let update_sender = update_sender.clone();
             let eval_random_symmetries = startup.eval_random_symmetries;
 
+            let batch_sizes = batch_sizes.clone();
+
             s.builder()
                 .name(format!("gpu-expand-{}-{}", device_id, local_id))
                 .spawn(move |_| {
-                    batched_executor_loop(
-                        gpu_batch_size,
-                        RunCondition::JobCount(eval_job_count),
-                        graph_receiver,
-                        eval_server,
-                        |graph| {
-                            graph.map_left(|graph| {
-                                let inner = CudaNetwork::new(mapper, &graph, gpu_batch_size, device);
-                                RandomSymmetryNetwork::new(inner, thread_rng(), eval_random_symmetries)
-                            })
-                        },
-                        |network, x| {
-                            let y = network.evaluate_batch(&x);
-                            let msg =
-                                GeneratorUpdate::ExpandEvals(Evals::new(x.len() as u64, gpu_batch_size as u64, 0));
-                            update_sender.send(msg).unwrap();
-                            y
-                        },
-                    );
+                    let load_network = |graph: NetworkOrDummy<Arc<Graph>>| -> ExpandNetwork<B, M> {
+                        // cached evals from the previous network are no longer valid
+                        cache.invalidate();
+
+                        graph.map_left(|graph| {
+                            // the metadata and shapes have already been checked in load_graph
+                            let inner = MultiBatchNetwork::build_sizes(&batch_sizes, |size| {
+                                CudaNetwork::new(mapper, &graph, size, device, None).unwrap()
+                            });
+                            RandomSymmetryNetwork::new(inner, thread_rng(), eval_random_symmetries)
+                        })
+                    };
+
+                    let evaluate_batch = |network: &mut ExpandNetwork<B, M>, x: &[B]| {
+                        let y = network.evaluate_batch(x);
+                        let potential = batch_sizes.iter().copied().find(|&size| size >= x.len()).unwrap();
+                        let msg = GeneratorUpdate::ExpandEvals(Evals::new(x.len() as u64, potential as u64, 0));
+                        update_sender.send(msg).unwrap();
+                        y
+                    };
+
+                    if adaptive_batching {
+                        let executor = std::thread::current().name().unwrap_or("unnamed").to_owned();
+                        let policy = AdaptiveBatching::new(
+                            &batch_sizes,
+                            eval_job_count.max(1),
+                            ADAPTIVE_TARGET_FILL,
+                            ADAPTIVE_WINDOW_SIZE,
+                            |report| {
+                                let executor = executor.clone();
+                                update_sender
+                                    .send(GeneratorUpdate::Batching { executor, report })
+                                    .unwrap();
+                            },
+                        );
+                        batched_executor_loop_with_policy(
+                            policy,
+                            graph_receiver,
+                            eval_server,
+                            load_network,
+                            evaluate_batch,
+                        );
+                    } else {
+                        batched_executor_loop(
+                            gpu_batch_size,
+                            RunCondition::JobCount(eval_job_count),
+                            graph_receiver,
+                            eval_server,
+                            load_network,
+                            evaluate_batch,
+                        );
+                    }
                 })
                 .unwrap();
         }
@@ -123,7 +185,38 @@ impl<B: Board + Hash, M: BoardMapper<B> + 'static> ZeroSpecialization<B, M> for
         (settings_senders, graph_senders)
     }
 
-    fn load_graph(&self, path: &str, _: M, _: &StartupSettings) -> Self::G {
-        optimize_graph(&load_graph_from_onnx_path(path, false).unwrap(), Default::default())
+    fn load_graph(&self, path: &str, mapper: M, startup: &StartupSettings) -> Result<Self::G, NetworkError> {
+        let graph = try_load_graph(path)?;
+
+        if let Some(metadata) = NetworkMetadata::load_for_onnx(path)? {
+            metadata.check_game(&startup.game)?;
+            metadata.check(mapper, &graph)?;
+        }
+        check_graph_shapes(mapper, &graph)?;
+
+        Ok(graph)
+    }
+}
+
+/// The batch sizes to compile the network for, sorted and always ending with `gpu_batch_size`.
+fn compiled_batch_sizes(startup: &StartupSettings) -> Vec<usize> {
+    let max = startup.gpu_batch_size;
+    if !startup.adaptive_batching {
+        return vec![max];
     }
+
+    for &size in &startup.gpu_batch_sizes {
+        assert!(
+            size > 0 && size <= max,
+            "Adaptive batch size {} must be in 1..={}, the gpu batch size",
+            size,
+            max
+        );
+    }
+
+    let mut sizes = startup.gpu_batch_sizes.clone();
+    sizes.push(max);
+    sizes.sort_unstable();
+    sizes.dedup();
+    sizes
 }
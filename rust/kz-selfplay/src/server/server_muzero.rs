@@ -5,6 +5,7 @@ use futures::executor::ThreadPoolBuilder;
 use kn_cuda_sys::wrapper::handle::CudaDevice;
 use rand::rngs::StdRng;
 
+use kz_core::error::NetworkError;
 use kz_core::mapping::BoardMapper;
 use kz_core::network::job_channel::job_pair;
 use kz_core::network::muzero::{MuZeroFusedGraphs, MuZeroGraphs};
@@ -157,8 +158,12 @@ impl<B: AltBoard, M: BoardMapper<B> + 'static> ZeroSpecialization<B, M> for MuZe
         (settings_senders, graph_senders)
     }
 
-    fn load_graph(&self, path: &str, mapper: M, startup: &StartupSettings) -> Self::G {
-        let graphs = MuZeroGraphs::load(path, mapper).unwrap();
+    fn load_graph(&self, path: &str, mapper: M, startup: &StartupSettings) -> Result<Self::G, NetworkError> {
+        let graphs = MuZeroGraphs::load(path, mapper).map_err(|e| NetworkError::GraphLoad {
+            path: path.into(),
+            message: format!("{:?}", e),
+        })?;
+        graphs.check_metadata(path, &startup.game)?;
 
         assert_eq!(
             startup.saved_state_channels, graphs.info.state_channels_saved,
@@ -166,6 +171,6 @@ impl<B: AltBoard, M: BoardMapper<B> + 'static> ZeroSpecialization<B, M> for MuZe
             startup.saved_state_channels, graphs.info.state_channels_saved
         );
 
-        graphs.fuse(Default::default())
+        Ok(graphs.fuse(Default::default()))
     }
 }
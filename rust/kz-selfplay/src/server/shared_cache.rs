@@ -0,0 +1,89 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use board_game::board::Board;
+use lru::LruCache;
+
+use kz_core::network::ZeroEvaluation;
+use kz_util::math::ceil_div;
+
+/// A cache of network evaluations shared between all generators that use the same executors.
+/// The cache is split into independently locked shards to limit contention, the board hash picks the shard.
+///
+/// The cache should be invalidated whenever a new network is loaded, see [SharedCache::invalidate].
+#[derive(Debug)]
+pub struct SharedCache<B: Board + Hash> {
+    shards: Vec<Mutex<LruCache<B, ZeroEvaluation<'static>>>>,
+    generation: AtomicU64,
+    generator_count: usize,
+}
+
+/// The cache generation at the time a request was sent to the executors.
+/// Used to prevent evaluations from an old network from being inserted after the cache has been invalidated.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CacheGeneration(u64);
+
+impl<B: Board + Hash> SharedCache<B> {
+    /// Create an empty cache shared by `generator_count` generators, use [SharedCache::resize] to set the capacity.
+    pub fn new(shard_count: usize, generator_count: usize) -> Self {
+        assert!(shard_count > 0, "Need at least one shard");
+
+        SharedCache {
+            shards: (0..shard_count).map(|_| Mutex::new(LruCache::new(0))).collect(),
+            generation: AtomicU64::new(0),
+            generator_count,
+        }
+    }
+
+    pub fn generation(&self) -> CacheGeneration {
+        CacheGeneration(self.generation.load(Ordering::Acquire))
+    }
+
+    pub fn get(&self, board: &B) -> Option<ZeroEvaluation<'static>> {
+        let mut shard = self.shard(board).lock().unwrap();
+        shard.get(board).cloned()
+    }
+
+    /// Insert an evaluation, unless the cache has been invalidated since `generation`.
+    pub fn insert(&self, generation: CacheGeneration, board: &B, eval: ZeroEvaluation<'static>) {
+        let mut shard = self.shard(board).lock().unwrap();
+
+        // checked while holding the lock, so we can't race with the clearing in invalidate
+        if self.generation() == generation {
+            shard.put(board.clone(), eval);
+        }
+    }
+
+    /// Remove all entries and reject any pending inserts from before this call.
+    pub fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        for shard in &self.shards {
+            shard.lock().unwrap().clear();
+        }
+    }
+
+    /// Change the capacity of the cache, evicting entries if necessary.
+    /// The capacity is per generator, like `Settings::cache_size`, the total capacity is `capacity * generator_count`.
+    pub fn resize(&self, capacity: usize) {
+        let shard_capacity = ceil_div(capacity * self.generator_count, self.shards.len());
+        for shard in &self.shards {
+            let mut shard = shard.lock().unwrap();
+            if shard.cap() != shard_capacity {
+                shard.resize(shard_capacity);
+            }
+        }
+    }
+
+    fn shard(&self, board: &B) -> &Mutex<LruCache<B, ZeroEvaluation<'static>>> {
+        &self.shards[(board_hash(board) % self.shards.len() as u64) as usize]
+    }
+}
+
+fn board_hash(board: &impl Hash) -> u64 {
+    // the default hasher is deterministic when constructed like this, which is what we want here
+    let mut hasher = DefaultHasher::new();
+    board.hash(&mut hasher);
+    hasher.finish()
+}
@@ -20,6 +20,20 @@ pub struct Position<'a, B: Board> {
     pub zero_visits: u64,
     pub zero_evaluation: ZeroEvaluation<'a>,
     pub net_evaluation: ZeroEvaluation<'a>,
+
+    /// Optional search statistics for each available move, in the same order as the policy.
+    /// Only the AlphaZero generator fills these in, MuZero selfplay files never include them.
+    pub child_stats: Option<Vec<ChildStats>>,
+}
+
+/// Search statistics for a single child of the root.
+#[derive(Debug, Copy, Clone)]
+pub struct ChildStats {
+    pub visits: u64,
+    /// The value of the child from the POV of the player that played the move, `NaN` if the child was never visited.
+    pub q: f32,
+    /// The raw network policy for the move.
+    pub prior: f32,
 }
 
 impl<'a, B: Board> Simulation<'a, B> {
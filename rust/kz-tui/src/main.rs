@@ -1,13 +1,11 @@
 use std::cell::RefCell;
 use std::cmp::{max, min, Reverse};
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use board_game::board::{Board, Outcome, Player};
-use board_game::games::ataxx::AtaxxBoard;
-use board_game::games::chess::{ChessBoard, Rules};
-use board_game::games::go;
-use board_game::games::go::{GoBoard, Komi};
 use clap::Parser;
 use crossterm::event::{
     DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton,
@@ -22,7 +20,6 @@ use kn_graph::optimizer::optimize_graph;
 use kn_runtime::{compiled_with_cuda_support, Device};
 use rand::rngs::StdRng;
 use rand::{thread_rng, SeedableRng};
-use trictrac_bot::trictrac_board::TrictracBoard;
 use tui::backend::CrosstermBackend;
 use tui::buffer::Buffer;
 use tui::layout::{Margin, Rect};
@@ -30,21 +27,20 @@ use tui::style::{Color, Modifier, Style};
 use tui::widgets::Widget;
 use tui::Terminal;
 
-use kz_core::mapping::ataxx::AtaxxStdMapper;
-use kz_core::mapping::chess::ChessStdMapper;
-use kz_core::mapping::go::GoStdMapper;
-use kz_core::mapping::trictrac::TrictracStdMapper;
-use kz_core::mapping::BoardMapper;
+use kz_core::error::parse_game;
+use kz_core::mapping::{BoardMapper, PolicyMapper};
+use kz_core::network::cache::CachedNetwork;
+use kz_core::network::metadata::NetworkMetadata;
 use kz_core::network::prepared::PreparedNetwork;
 use kz_core::network::symmetry::RandomSymmetryNetwork;
-use kz_core::network::Network;
+use kz_core::network::{AuxOutputs, Network};
+use kz_core::registry::{visit_game, GameEntry, GameVisitor};
 use kz_core::zero::node::{Uct, UctWeights};
 use kz_core::zero::step::{zero_step_apply, zero_step_gather, FpuMode, QMode, ZeroRequest};
 use kz_core::zero::tree::Tree;
 use kz_core::zero::values::ZeroValuesAbs;
 use kz_core::zero::wrapper::ZeroSettings;
 use kz_util::display::display_option_empty;
-use kz_util::game::Game;
 
 #[derive(clap::Parser)]
 struct Args {
@@ -61,6 +57,8 @@ struct Args {
     cpu: bool,
     #[clap(long)]
     random_symmetries: bool,
+    #[clap(long, default_value_t = 0)]
+    cache_size: usize,
 
     // search
     #[clap(long, default_value_t = 1)]
@@ -80,12 +78,17 @@ struct State<B: Board> {
     rng: StdRng,
 
     tree: Tree<B>,
+    /// The score lead of terminal boards, see [PolicyMapper::final_score].
+    final_score: Box<dyn Fn(&B) -> Option<f32>>,
 
     board_cache: RefCell<HashMap<usize, B>>,
     prev_nodes: Vec<RenderNode>,
 
     expanded_nodes: HashSet<usize>,
     selected_node: usize,
+    /// The node the auxiliary network outputs were last evaluated for, and those outputs.
+    /// The tree itself doesn't keep them.
+    selected_aux: Option<(usize, Option<Arc<AuxOutputs>>)>,
 
     view_offset: usize,
 }
@@ -99,49 +102,31 @@ struct RenderNode {
 fn main() -> std::io::Result<()> {
     let args: Args = Args::parse();
 
-    let game = Game::parse(&args.game).expect("Invalid game");
+    let game = parse_game(&args.game).unwrap_or_else(|e| panic!("{}", e));
+    visit_game(game, TuiVisitor { args: &args })
+}
 
-    match game {
-        Game::Chess => {
-            let board = args.fen.as_ref().map_or(ChessBoard::default(), |fen| {
-                ChessBoard::new_without_history_fen(fen, Rules::default())
-            });
-            main_game(&args, board, ChessStdMapper)
-        }
-        Game::Trictrac => {
-            let board = args.fen.as_ref().map_or(TrictracBoard::default(), |fen| {
-                TrictracBoard::from_fen(fen).expect("Invalid fen")
-            });
-            main_game(&args, board, TrictracStdMapper)
-        }
-        Game::Ataxx { size } => {
-            let board = args.fen.as_ref().map_or(AtaxxBoard::diagonal(size), |fen| {
-                AtaxxBoard::from_fen(fen).expect("Invalid fen")
-            });
-            assert_eq!(board.size(), size, "Fen has wrong size");
-            main_game(&args, board, AtaxxStdMapper::new(size))
-        }
-        Game::Go { size } => {
-            let komi = Komi::try_from(7.5).unwrap();
-            let rules = go::Rules::tromp_taylor();
-            let board = args.fen.as_ref().map_or(GoBoard::new(size, komi, rules), |fen| {
-                GoBoard::from_fen(fen, rules).expect("Invalid fen")
-            });
-            assert_eq!(board.size(), size, "Fen has wrong size");
-            main_game(&args, board, GoStdMapper::new(size, false))
-        }
+struct TuiVisitor<'a> {
+    args: &'a Args,
+}
+
+impl GameVisitor for TuiVisitor<'_> {
+    type Output = std::io::Result<()>;
 
-        _ => panic!("Game {game:?} not implemented yet"),
+    fn visit<B: Board + Hash, M: BoardMapper<B> + 'static>(self, entry: GameEntry<B, M>) -> Self::Output {
+        let board = entry.board_from_optional_fen(self.args.fen.as_deref());
+        main_game(self.args, board, entry.mapper)
     }
 }
 
-fn main_game<B: Board, M: BoardMapper<B>>(args: &Args, board: B, mapper: M) -> std::io::Result<()> {
+fn main_game<B: Board + Hash, M: BoardMapper<B>>(args: &Args, board: B, mapper: M) -> std::io::Result<()> {
     println!("Using board:");
     println!("{}", board);
     println!("Using mapper: {:?}", mapper);
 
     println!("Loading graph...");
-    let graph = load_graph_from_onnx_path(&args.network, true).unwrap();
+    let graph = load_graph_from_onnx_path(&args.network, true)
+        .unwrap_or_else(|e| panic!("Failed to load network {:?}: {:?}", args.network, e));
     println!("Optimizing graph...");
     let graph = optimize_graph(&graph, Default::default());
 
@@ -157,7 +142,15 @@ fn main_game<B: Board, M: BoardMapper<B>>(args: &Args, board: B, mapper: M) -> s
     };
     println!("Using device {:?}", device);
 
-    let network_inner = PreparedNetwork::new(mapper, device, graph, args.batch_size);
+    let metadata = NetworkMetadata::load_for_onnx(&args.network).unwrap_or_else(|e| panic!("{}", e));
+    if let Some(metadata) = &metadata {
+        metadata.check_game(&args.game).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    let network_inner = PreparedNetwork::new(mapper, device, graph, args.batch_size, metadata.as_ref())
+        .unwrap_or_else(|e| panic!("{}", e));
+    // no canonicalization, that would collapse all random symmetries into the same cached evaluation
+    let network_inner = CachedNetwork::new(network_inner, args.cache_size, false);
     let mut network = RandomSymmetryNetwork::new(network_inner, thread_rng(), args.random_symmetries);
 
     // TODO expose as params?
@@ -171,11 +164,12 @@ fn main_game<B: Board, M: BoardMapper<B>>(args: &Args, board: B, mapper: M) -> s
         args.policy_temperature,
     );
 
-    main_impl(&mut network, board, settings, args.visits)
+    main_impl(&mut network, mapper, board, settings, args.visits)
 }
 
-fn main_impl<B: Board>(
+fn main_impl<B: Board, M: PolicyMapper<B> + 'static>(
     network: &mut impl Network<B>,
+    mapper: M,
     board: B,
     settings: ZeroSettings,
     visits: u64,
@@ -196,11 +190,13 @@ fn main_impl<B: Board>(
     let mut requests = VecDeque::new();
     let mut state = State {
         tree,
+        final_score: Box::new(move |board: &B| mapper.final_score(board)),
         settings,
         prev_nodes: Default::default(),
         board_cache: Default::default(),
         expanded_nodes: Default::default(),
         selected_node: 0,
+        selected_aux: None,
         view_offset: 0,
         rng,
     };
@@ -216,6 +212,7 @@ fn main_impl<B: Board>(
     // event loop
     loop {
         let mut prev_area = None;
+        state.update_selected_aux(network);
 
         terminal.draw(|f| {
             let area = f.size().inner(&Margin {
@@ -302,6 +299,7 @@ fn main_impl<B: Board>(
 }
 
 const HEADER_SIZE: u16 = 2;
+const FOOTER_SIZE: u16 = 1;
 const OFFSET_MARGIN: usize = 3;
 const COL_SPACING: u16 = 2;
 
@@ -345,10 +343,46 @@ impl<B: Board> State<B> {
         board
     }
 
+    fn update_selected_aux(&mut self, network: &mut impl Network<B>) {
+        // only evaluate when the selection changes, this is called for every event
+        let node = self.selected_node;
+        if matches!(self.selected_aux, Some((prev, _)) if prev == node) {
+            return;
+        }
+
+        let board = self.node_board(node);
+        let aux = if board.is_done() {
+            None
+        } else {
+            network.evaluate(&board).aux
+        };
+        self.selected_aux = Some((node, aux));
+    }
+
+    fn aux_line(&self) -> String {
+        let aux = match &self.selected_aux {
+            Some((_, Some(aux))) => aux,
+            _ => return "Aux: -".to_owned(),
+        };
+
+        let outputs = aux
+            .iter()
+            .map(|(name, values)| match values.as_slice() {
+                [value] => format!("{}: {:.3}", name, value),
+                _ => {
+                    let mean = values.iter().sum::<f32>() / values.len() as f32;
+                    format!("{}: [{} values, mean {:.3}]", name, values.len(), mean)
+                }
+            })
+            .join(", ");
+        format!("Aux: {}", outputs)
+    }
+
     fn gather_step(&mut self, requests: &mut VecDeque<ZeroRequest<B>>) {
         // gather a single node
         let request = zero_step_gather(
             &mut self.tree,
+            &self.final_score,
             self.settings.weights,
             self.settings.q_mode,
             self.settings.fpu_root,
@@ -387,7 +421,7 @@ impl<B: Board> State<B> {
         let selected = self.selected_index();
         let margin = min(OFFSET_MARGIN, ((area.height - 1) / 2) as usize);
         let offset = (self.view_offset as i32).clamp(
-            selected as i32 - (area.height as i32 - HEADER_SIZE as i32) + margin as i32,
+            selected as i32 - (area.height as i32 - HEADER_SIZE as i32 - FOOTER_SIZE as i32) + margin as i32,
             selected.saturating_sub(margin) as i32,
         );
 
@@ -615,7 +649,8 @@ impl<B: Board> Widget for &State<B> {
             buf.set_string_safe(col_starts[i], area.y + 1, n2, Style::default().fg(color));
         }
 
-        for y in 0..area.height - HEADER_SIZE {
+        let body_height = area.height.saturating_sub(HEADER_SIZE + FOOTER_SIZE);
+        for y in 0..body_height {
             let full_y = area.y + y + HEADER_SIZE;
             let i = y as u32 + self.view_offset as u32;
 
@@ -641,6 +676,9 @@ impl<B: Board> Widget for &State<B> {
                 }
             }
         }
+
+        let footer_y = area.y + HEADER_SIZE + body_height;
+        buf.set_string_safe(area.x, footer_y, self.aux_line(), Style::default().fg(Color::Gray));
     }
 }
 
@@ -658,3 +696,42 @@ impl BufExt for Buffer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use kz_util::game::Game;
+
+    use super::*;
+
+    /// There is no network to load, but the board and mapper are still built for every game.
+    #[test]
+    fn visitor_all_games() {
+        for game in Game::examples() {
+            println!("Visiting {}", game);
+
+            let args = Args {
+                game: game.to_string(),
+                fen: None,
+                network: PathBuf::from("does-not-exist.onnx"),
+                cpu: true,
+                random_symmetries: false,
+                cache_size: 0,
+                batch_size: 1,
+                virtual_loss_weight: 1.0,
+                policy_temperature: 1.0,
+                visits: 0,
+            };
+
+            let payload = catch_unwind(AssertUnwindSafe(|| visit_game(game, TuiVisitor { args: &args }))).unwrap_err();
+            let message = payload.downcast_ref::<String>().unwrap();
+            assert!(
+                message.starts_with("Failed to load network"),
+                "Game {}: {}",
+                game,
+                message
+            );
+        }
+    }
+}
@@ -1,29 +1,108 @@
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Copy, Clone)]
+/// The largest supported hex board, the same as `kz_core::games::hex::MAX_SIZE`.
+pub const HEX_MAX_SIZE: u8 = 19;
+
+/// The largest supported go board, the same as the limit of `board_game::games::go::GoBoard`.
+pub const GO_MAX_SIZE: u8 = 19;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Game {
     TTT,
     STTT,
+    Connect4,
+    Othello,
     Chess,
+    ChessAtt,
     Trictrac,
+    TrictracV2,
+    TrictracHist { length: usize },
     ChessHist { length: usize },
     Ataxx { size: u8 },
+    AtaxxHist { size: u8, length: usize },
     ArimaaSplit,
-    Go { size: u8 },
+    Go { min_size: u8, max_size: u8 },
+    Hex { size: u8 },
 }
 
 impl Game {
+    /// At least one example of each variant, for everything that needs to cover all games.
+    pub fn examples() -> Vec<Game> {
+        let examples = vec![
+            Game::TTT,
+            Game::STTT,
+            Game::Connect4,
+            Game::Othello,
+            Game::Chess,
+            Game::ChessAtt,
+            Game::Trictrac,
+            Game::TrictracV2,
+            Game::TrictracHist { length: 2 },
+            Game::ChessHist { length: 8 },
+            Game::Ataxx { size: 7 },
+            Game::AtaxxHist { size: 7, length: 2 },
+            Game::ArimaaSplit,
+            Game::Go {
+                min_size: 9,
+                max_size: 9,
+            },
+            Game::Go {
+                min_size: 9,
+                max_size: 19,
+            },
+            Game::Hex { size: 11 },
+        ];
+
+        // no wildcard, so adding a variant fails to compile here until it gets an example above
+        for game in &examples {
+            match game {
+                Game::TTT
+                | Game::STTT
+                | Game::Connect4
+                | Game::Othello
+                | Game::Chess
+                | Game::ChessAtt
+                | Game::Trictrac
+                | Game::TrictracV2
+                | Game::TrictracHist { .. }
+                | Game::ChessHist { .. }
+                | Game::Ataxx { .. }
+                | Game::AtaxxHist { .. }
+                | Game::ArimaaSplit
+                | Game::Go { .. }
+                | Game::Hex { .. } => {}
+            }
+        }
+
+        examples
+    }
+
     pub fn parse(str: &str) -> Option<Game> {
         match str {
             "ttt" => return Some(Game::TTT),
             "sttt" => return Some(Game::STTT),
+            "connect4" => return Some(Game::Connect4),
+            "othello" => return Some(Game::Othello),
             "chess" => return Some(Game::Chess),
+            "chess-att" => return Some(Game::ChessAtt),
             "trictrac" => return Some(Game::Trictrac),
+            "trictrac-v2" => return Some(Game::TrictracV2),
             "ataxx" => return Some(Game::Ataxx { size: 7 }),
             "arimaa-split" => return Some(Game::ArimaaSplit),
             _ => {}
         };
 
+        if let Some(rest) = str.strip_prefix("ataxx-") {
+            if let Some((size, length)) = rest.split_once("-hist-") {
+                let size: u8 = size.parse().ok()?;
+                let length: usize = length.parse().ok()?;
+                return Some(Game::AtaxxHist { size, length });
+            }
+        }
+        if let Some(length) = str.strip_prefix("trictrac-hist-") {
+            let length: usize = length.parse().ok()?;
+            return Some(Game::TrictracHist { length });
+        }
         if let Some(size) = str.strip_prefix("ataxx-") {
             let size: u8 = size.parse().ok()?;
             return Some(Game::Ataxx { size });
@@ -32,9 +111,26 @@ impl Game {
             let length: usize = length.parse().ok()?;
             return Some(Game::ChessHist { length });
         }
-        if let Some(size) = str.strip_prefix("go-") {
+        // "go-9" for a single size, "go-9..19" for all sizes in the range played with a single network
+        if let Some(rest) = str.strip_prefix("go-") {
+            let (min_size, max_size) = match rest.split_once("..") {
+                Some((min_size, max_size)) => (min_size.parse().ok()?, max_size.parse().ok()?),
+                None => {
+                    let size: u8 = rest.parse().ok()?;
+                    (size, size)
+                }
+            };
+            if min_size < 1 || max_size > GO_MAX_SIZE || min_size > max_size {
+                return None;
+            }
+            return Some(Game::Go { min_size, max_size });
+        }
+        if let Some(size) = str.strip_prefix("hex-") {
             let size: u8 = size.parse().ok()?;
-            return Some(Game::Go { size });
+            if !(1..=HEX_MAX_SIZE).contains(&size) {
+                return None;
+            }
+            return Some(Game::Hex { size });
         }
 
         None
@@ -46,12 +142,25 @@ impl Display for Game {
         match self {
             Game::TTT => write!(f, "ttt"),
             Game::STTT => write!(f, "sttt"),
+            Game::Connect4 => write!(f, "connect4"),
+            Game::Othello => write!(f, "othello"),
             Game::Chess => write!(f, "chess"),
+            Game::ChessAtt => write!(f, "chess-att"),
             Game::Trictrac => write!(f, "trictrac"),
+            Game::TrictracV2 => write!(f, "trictrac-v2"),
+            Game::TrictracHist { length } => write!(f, "trictrac-hist-{}", length),
             Game::ChessHist { length } => write!(f, "chess-hist-{}", length),
             Game::Ataxx { size } => write!(f, "ataxx-{}", size),
+            Game::AtaxxHist { size, length } => write!(f, "ataxx-{}-hist-{}", size, length),
             Game::ArimaaSplit => write!(f, "arimaa-split"),
-            Game::Go { size } => write!(f, "go-{}", size),
+            Game::Go { min_size, max_size } => {
+                if min_size == max_size {
+                    write!(f, "go-{}", max_size)
+                } else {
+                    write!(f, "go-{}..{}", min_size, max_size)
+                }
+            }
+            Game::Hex { size } => write!(f, "hex-{}", size),
         }
     }
 }
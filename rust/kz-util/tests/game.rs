@@ -0,0 +1,57 @@
+use kz_util::game::Game;
+
+#[test]
+fn go_single_size() {
+    assert_eq!(
+        Game::parse("go-9"),
+        Some(Game::Go {
+            min_size: 9,
+            max_size: 9
+        })
+    );
+    assert_eq!(
+        Game::Go {
+            min_size: 9,
+            max_size: 9
+        }
+        .to_string(),
+        "go-9"
+    );
+}
+
+#[test]
+fn go_size_range() {
+    let game = Game::Go {
+        min_size: 9,
+        max_size: 19,
+    };
+    assert_eq!(Game::parse("go-9..19"), Some(game));
+    assert_eq!(game.to_string(), "go-9..19");
+}
+
+#[test]
+fn go_invalid() {
+    assert_eq!(Game::parse("go-19..9"), None);
+    assert_eq!(Game::parse("go-9.."), None);
+    assert_eq!(Game::parse("go-9..=19"), None);
+    assert_eq!(Game::parse("go-"), None);
+    assert_eq!(Game::parse("go-0"), None);
+    assert_eq!(Game::parse("go-20"), None);
+    assert_eq!(Game::parse("go-0..19"), None);
+    assert_eq!(Game::parse("go-9..25"), None);
+}
+
+#[test]
+fn hex_size() {
+    assert_eq!(Game::parse("hex-1"), Some(Game::Hex { size: 1 }));
+    assert_eq!(Game::parse("hex-19"), Some(Game::Hex { size: 19 }));
+    assert_eq!(Game::parse("hex-0"), None);
+    assert_eq!(Game::parse("hex-20"), None);
+}
+
+#[test]
+fn examples_roundtrip() {
+    for game in Game::examples() {
+        assert_eq!(Game::parse(&game.to_string()), Some(game));
+    }
+}